@@ -0,0 +1,109 @@
+use std::{hint::black_box, thread};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use quake_log_parse::quake_log_parse::model::{
+    generator::{generate_log, GeneratorOptions},
+    identity::{process_identity_ranking, AliasMap},
+    log_model::{process_events_matches, process_ranking, Match, ParseOptions},
+    parallel::process_events_matches_parallel,
+};
+
+/// Number of matches of the generated logs, from a short session to a few months of a busy server.
+const SIZES: [usize; 3] = [100, 1_000, 5_000];
+
+fn log_of(matches: usize) -> String {
+    generate_log(&GeneratorOptions {
+        seed: 2024,
+        matches,
+        players: 200,
+        players_per_match: 12,
+        ..Default::default()
+    })
+}
+
+fn parse(content: &str) -> Vec<Match> {
+    let mut matches = Vec::new();
+    process_events_matches(&mut matches, content).unwrap();
+    matches
+}
+
+/// Parsing throughput of the sequential parser over logs of increasing size.
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+
+    for size in SIZES {
+        let content = log_of(size);
+        group.throughput(Throughput::Bytes(content.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &content, |b, content| {
+            b.iter(|| parse(black_box(content)))
+        });
+    }
+    group.finish();
+}
+
+/// Compares the sequential parser with the parallel one on the largest log.
+fn bench_parse_parallel(c: &mut Criterion) {
+    let content = log_of(SIZES[SIZES.len() - 1]);
+    let options = ParseOptions::default();
+    let cores = thread::available_parallelism().map_or(1, |n| n.get());
+
+    let mut group = c.benchmark_group("parse_parallel");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(content.len() as u64));
+
+    group.bench_function("sequential", |b| b.iter(|| parse(black_box(&content))));
+    let mut thread_counts = vec![2, 4, cores];
+    thread_counts.sort();
+    thread_counts.dedup();
+    for threads in thread_counts {
+        group.bench_with_input(
+            BenchmarkId::new("threads", threads),
+            &threads,
+            |b, &threads| {
+                b.iter(|| {
+                    let mut matches = Vec::new();
+                    process_events_matches_parallel(
+                        &mut matches,
+                        black_box(&content),
+                        &options,
+                        threads,
+                    )
+                    .unwrap();
+                    matches
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Ranking of the players by name and by identity over the matches of logs of increasing size.
+fn bench_ranking(c: &mut Criterion) {
+    let aliases = AliasMap::default();
+    let mut group = c.benchmark_group("ranking");
+    group.sample_size(10);
+
+    for size in SIZES {
+        let mut matches = parse(&log_of(size));
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("by_name", size), &size, |b, _| {
+            b.iter(|| {
+                let mut ranking = Vec::new();
+                process_ranking(black_box(&mut matches), &mut ranking);
+                ranking
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("by_identity", size), &size, |b, _| {
+            b.iter(|| {
+                let mut ranking = Vec::new();
+                process_identity_ranking(black_box(&matches), &aliases, &mut ranking);
+                ranking
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_parse_parallel, bench_ranking);
+criterion_main!(benches);
@@ -1,39 +1,503 @@
-use std::io;
+use std::{env, process::ExitCode};
 
-use crate::quake_log_parse::controller::LogController;
+use quake_log_parse::quake_log_parse::{
+    controller::{
+        DiffFormat, LogController, OutputFormat, OutputOptions, ReplController, ValidateFormat,
+    },
+    model::{
+        error::LogError,
+        filter::{MatchFilter, FILTER_NAMES},
+        generator::GeneratorOptions,
+        log_model::ParseOptions,
+        session::NamePolicy,
+    },
+};
 
-mod quake_log_parse;
+const USAGE: &str = "Usage:
+  quake_log_parse                                  Interactive shell over qgames.log, with commands
+                                                   to load logs, list matches and players, rank and
+                                                   export (type help once inside)
+  quake_log_parse report [--parallel] [INPUT...]   Report of log files, directories or glob patterns
+  quake_log_parse report --db=FILE [INPUT...]      Report of the matches stored in a database,
+                                                   importing the inputs first
+  quake_log_parse report --checkpoint=FILE LOG     Report of the matches completed in a log since
+                                                   the last run, resuming from the checkpoint file
+      Filters, applied to every report before the players are ranked:
+      --map=NAME --gametype=N --hostname=NAME --player=NAME --ids=FROM..TO
+      --time=FROM..TO (start of the match as m:ss since the server started; logs have no dates)
+      --min-players=N --completed (only matches that logged a ShutdownGame)
+      Output: --format=table|json|legacy-json (default: tables on a terminal, JSON otherwise;
+      legacy-json is the shape without schema_version, one document per section)
+      --color=auto|always|never (default: on a terminal unless NO_COLOR is set)
+      --name-policy=first|last|most-used (name reported for players who renamed themselves during
+      a match, default last; also taken by the shell and every other command reading logs)
+  quake_log_parse merge [OPTION...] REPORT...       Report combining JSON reports exported with
+                                                   --format=json, e.g. of several servers or days,
+                                                   into one ranking (same filters and output)
+  quake_log_parse diff [--format=text|json] OLD NEW
+                                                   Compare two JSON reports or logs match by match
+                                                   and player by player (exit code 1 when they
+                                                   differ)
+  quake_log_parse validate [--format=text|json] [INPUT...]
+                                                   Check logs for structural problems (kills
+                                                   outside a match, unknown client ids or means of
+                                                   death, time going backwards, unterminated
+                                                   matches) as errors and warnings (exit code 1
+                                                   when there are errors)
+  quake_log_parse query QUERY [INPUT...]           Answer a query over the kill, item and chat
+                                                   events of logs, e.g.
+      'kills where killer = Zeh and map = q3dm17 and minute >= 5 group by means order by count desc'
+      Sources: kills, items, chat. Clauses: where (= != < <= > >= ~, and, or, not),
+      group by, count, sum(FIELD), order by [asc|desc], limit
+  quake_log_parse events [--output=FILE] [INPUT...]
+                                                   Every event of logs as JSON Lines, one object
+                                                   per line with the match, line number, client
+                                                   ids, player names and means of death
+  quake_log_parse browse [LOG]                     Browse the matches of a log in a full-screen
+                                                   terminal UI: scoreboards, players and the kill
+                                                   timeline of each match
+  quake_log_parse import --db=FILE INPUT...        Store the matches of logs in a SQLite database
+  quake_log_parse serve [--addr=ADDRESS] [--db=FILE] [INPUT...]
+                                                   Serve reports over a local HTTP API (default
+                                                   address 127.0.0.1:8080)
+  quake_log_parse serve [--addr=ADDRESS] --follow LOG
+                                                   Also push the events of the log as it is
+                                                   written to /events (SSE) and /events/ws
+                                                   (metrics are served on /metrics)
+  quake_log_parse metrics [--output=FILE] INPUT...
+                                                   Prometheus metrics of log files, written to
+                                                   FILE for the node exporter textfile collector
+  quake_log_parse gen [OPTION=VALUE...]            Write a synthetic log to the standard output
+      --seed=N --matches=N --players=N --players-per-match=N --kills=N
+      --renames=P --disconnects=P --truncations=P (probabilities from 0 to 1) --output=FILE
+  quake_log_parse schema                           Print the JSON Schema of the JSON report
+  quake_log_parse help                             Show this message";
 
-fn main() {
-    execute_choice();
-}
-
-fn execute_choice() {
-    loop {
-        let mut choice = String::new();
+/// The address the HTTP API listens on by default, only reachable from the same machine.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
 
-        println!("Select an option:");
-        println!("1. Report each match and a player ranking.");
-        println!("0. Exit");
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().all(|arg| arg.starts_with("--name-policy=")) {
+        let options = match parse_options(&args) {
+            Ok((options, _)) => options,
+            Err(message) => {
+                eprintln!("{}\n{}", message, USAGE);
+                return ExitCode::from(2);
+            }
+        };
+        return match ReplController::run(&options) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("Error: {:?}", err);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    execute_command(&args)
+}
 
-        io::stdin()
-            .read_line(&mut choice)
-            .expect("Failed to read the line");
+fn execute_command(args: &[String]) -> ExitCode {
+    let (flags, inputs): (Vec<String>, Vec<String>) = args[1..]
+        .iter()
+        .cloned()
+        .partition(|arg| arg.starts_with("--"));
 
-        match choice.trim() {
-            "1" => {
-                println!("You chose Report each match and a player ranking.");
-                if let Err(err) = LogController::generate_and_render_report() {
-                    println!("Error: {:?}", err);
+    let result = match args[0].as_str() {
+        "report" => {
+            let parsed = match_filter(&flags).and_then(|(filter, flags)| {
+                let (output, flags) = output_options(&flags)?;
+                Ok((filter, output, parse_options(&flags)?))
+            });
+            let (filter, output, (options, flags)) = match parsed {
+                Ok(parsed) => parsed,
+                Err(message) => {
+                    eprintln!("{}\n{}", message, USAGE);
+                    return ExitCode::from(2);
+                }
+            };
+            let database = flags.iter().find_map(|flag| flag.strip_prefix("--db="));
+            let checkpoint = flags
+                .iter()
+                .find_map(|flag| flag.strip_prefix("--checkpoint="));
+            if let Some(flag) = flags.iter().find(|flag| {
+                *flag != "--parallel"
+                    && !flag.starts_with("--db=")
+                    && !flag.starts_with("--checkpoint=")
+            }) {
+                eprintln!("Unknown option: {}\n{}", flag, USAGE);
+                return ExitCode::from(2);
+            }
+            if let Some(checkpoint) = checkpoint {
+                if inputs.len() != 1 || database.is_some() {
+                    eprintln!("--checkpoint needs a single log file\n{}", USAGE);
+                    return ExitCode::from(2);
+                }
+                LogController::generate_and_render_incremental_report(
+                    &inputs[0], checkpoint, &filter, &options, &output,
+                )
+            } else if let Some(database) = database {
+                LogController::generate_and_render_database_report(
+                    database, &inputs, &filter, &options, &output,
+                )
+            } else if inputs.is_empty() {
+                LogController::generate_and_render_report(&filter, &options, &output)
+            } else {
+                let parallel = flags.iter().any(|flag| flag == "--parallel");
+                LogController::generate_and_render_logs_report(
+                    &inputs, parallel, &filter, &options, &output,
+                )
+            }
+        }
+        "merge" => {
+            let parsed = match_filter(&flags)
+                .and_then(|(filter, flags)| Ok((filter, output_options(&flags)?)));
+            let (filter, (output, flags)) = match parsed {
+                Ok(parsed) => parsed,
+                Err(message) => {
+                    eprintln!("{}\n{}", message, USAGE);
+                    return ExitCode::from(2);
+                }
+            };
+            if let Some(flag) = flags.first() {
+                eprintln!("Unknown option: {}\n{}", flag, USAGE);
+                return ExitCode::from(2);
+            }
+            if inputs.is_empty() {
+                eprintln!("Missing reports to merge\n{}", USAGE);
+                return ExitCode::from(2);
+            }
+            LogController::merge_and_render_reports(&inputs, &filter, &output)
+        }
+        "diff" => {
+            let (options, flags) = match parse_options(&flags) {
+                Ok(parsed) => parsed,
+                Err(message) => {
+                    eprintln!("{}\n{}", message, USAGE);
+                    return ExitCode::from(2);
+                }
+            };
+            let mut format = DiffFormat::default();
+            for flag in &flags {
+                format = match flag.as_str() {
+                    "--format=text" => DiffFormat::Text,
+                    "--format=json" => DiffFormat::Json,
+                    _ => {
+                        eprintln!("Unknown option: {}\n{}", flag, USAGE);
+                        return ExitCode::from(2);
+                    }
+                };
+            }
+            if inputs.len() != 2 {
+                eprintln!("diff compares two reports or logs\n{}", USAGE);
+                return ExitCode::from(2);
+            }
+            // Like diff(1): 0 when the same, 1 when different and 2 when they cannot be compared.
+            return match LogController::diff(&inputs[0], &inputs[1], format, &options) {
+                Ok(false) => ExitCode::SUCCESS,
+                Ok(true) => ExitCode::from(1),
+                Err(err) => {
+                    eprintln!("Error: {:?}", err);
+                    ExitCode::from(2)
+                }
+            };
+        }
+        "validate" => {
+            let mut format = ValidateFormat::default();
+            for flag in &flags {
+                format = match flag.as_str() {
+                    "--format=text" => ValidateFormat::Text,
+                    "--format=json" => ValidateFormat::Json,
+                    _ => {
+                        eprintln!("Unknown option: {}\n{}", flag, USAGE);
+                        return ExitCode::from(2);
+                    }
+                };
+            }
+            // Warnings do not fail, so logs with only unusual lines still pass in scripts.
+            return match LogController::validate(&inputs, format) {
+                Ok(false) => ExitCode::SUCCESS,
+                Ok(true) => ExitCode::from(1),
+                Err(err) => {
+                    eprintln!("Error: {:?}", err);
+                    ExitCode::from(2)
+                }
+            };
+        }
+        "import" => {
+            let (options, flags) = match parse_options(&flags) {
+                Ok(parsed) => parsed,
+                Err(message) => {
+                    eprintln!("{}\n{}", message, USAGE);
+                    return ExitCode::from(2);
+                }
+            };
+            let database = flags.iter().find_map(|flag| flag.strip_prefix("--db="));
+            let unknown = flags.iter().find(|flag| !flag.starts_with("--db="));
+            match (database, unknown) {
+                (_, Some(flag)) => {
+                    eprintln!("Unknown option: {}\n{}", flag, USAGE);
+                    return ExitCode::from(2);
+                }
+                (None, None) => {
+                    eprintln!("Missing option: --db=FILE\n{}", USAGE);
+                    return ExitCode::from(2);
+                }
+                _ if inputs.is_empty() => {
+                    eprintln!("Missing log files to import\n{}", USAGE);
+                    return ExitCode::from(2);
+                }
+                (Some(database), None) => LogController::import_logs(database, &inputs, &options),
+            }
+        }
+        "serve" => {
+            let (options, flags) = match parse_options(&flags) {
+                Ok(parsed) => parsed,
+                Err(message) => {
+                    eprintln!("{}\n{}", message, USAGE);
+                    return ExitCode::from(2);
                 }
+            };
+            let address = flags
+                .iter()
+                .find_map(|flag| flag.strip_prefix("--addr="))
+                .unwrap_or(DEFAULT_ADDRESS);
+            let database = flags.iter().find_map(|flag| flag.strip_prefix("--db="));
+            let follow = flags.iter().any(|flag| flag == "--follow");
+            if let Some(flag) = flags.iter().find(|flag| {
+                !flag.starts_with("--addr=") && !flag.starts_with("--db=") && *flag != "--follow"
+            }) {
+                eprintln!("Unknown option: {}\n{}", flag, USAGE);
+                return ExitCode::from(2);
             }
-            "0" => {
-                println!("Exiting the program.");
-                return;
+            if inputs.is_empty() && database.is_none() {
+                eprintln!("Missing log files or --db=FILE to serve\n{}", USAGE);
+                return ExitCode::from(2);
             }
-            _ => {
-                println!("Invalid option. Please try again.");
+            if follow && (inputs.len() != 1 || database.is_some()) {
+                eprintln!("--follow needs a single log file\n{}", USAGE);
+                return ExitCode::from(2);
             }
+            LogController::serve(address, &inputs, database, follow, &options)
+        }
+        "metrics" => {
+            let (options, flags) = match parse_options(&flags) {
+                Ok(parsed) => parsed,
+                Err(message) => {
+                    eprintln!("{}\n{}", message, USAGE);
+                    return ExitCode::from(2);
+                }
+            };
+            let output = flags.iter().find_map(|flag| flag.strip_prefix("--output="));
+            if let Some(flag) = flags.iter().find(|flag| !flag.starts_with("--output=")) {
+                eprintln!("Unknown option: {}\n{}", flag, USAGE);
+                return ExitCode::from(2);
+            }
+            if inputs.is_empty() {
+                eprintln!("Missing log files\n{}", USAGE);
+                return ExitCode::from(2);
+            }
+            LogController::generate_and_render_metrics(&inputs, &options, output)
+        }
+        "events" => {
+            let (options, flags) = match parse_options(&flags) {
+                Ok(parsed) => parsed,
+                Err(message) => {
+                    eprintln!("{}\n{}", message, USAGE);
+                    return ExitCode::from(2);
+                }
+            };
+            let output = flags.iter().find_map(|flag| flag.strip_prefix("--output="));
+            if let Some(flag) = flags.iter().find(|flag| !flag.starts_with("--output=")) {
+                eprintln!("Unknown option: {}\n{}", flag, USAGE);
+                return ExitCode::from(2);
+            }
+            LogController::export_events(&inputs, &options, output)
+        }
+        "query" => {
+            let (options, flags) = match parse_options(&flags) {
+                Ok(parsed) => parsed,
+                Err(message) => {
+                    eprintln!("{}\n{}", message, USAGE);
+                    return ExitCode::from(2);
+                }
+            };
+            if let Some(flag) = flags.first() {
+                eprintln!("Unknown option: {}\n{}", flag, USAGE);
+                return ExitCode::from(2);
+            }
+            let Some((query, inputs)) = inputs.split_first() else {
+                eprintln!("Missing query\n{}", USAGE);
+                return ExitCode::from(2);
+            };
+            LogController::run_query(query, inputs, &options)
+        }
+        "browse" => {
+            let (options, flags) = match parse_options(&flags) {
+                Ok(parsed) => parsed,
+                Err(message) => {
+                    eprintln!("{}\n{}", message, USAGE);
+                    return ExitCode::from(2);
+                }
+            };
+            if let Some(flag) = flags.first() {
+                eprintln!("Unknown option: {}\n{}", flag, USAGE);
+                return ExitCode::from(2);
+            }
+            if inputs.len() > 1 {
+                eprintln!("browse reads a single log file\n{}", USAGE);
+                return ExitCode::from(2);
+            }
+            LogController::browse(inputs.first().map(String::as_str), &options)
+        }
+        "gen" => {
+            if let Some(input) = inputs.first() {
+                eprintln!("Unexpected argument: {}\n{}", input, USAGE);
+                return ExitCode::from(2);
+            }
+            match generator_options(&flags) {
+                Ok((options, output)) => LogController::generate_log(&options, output.as_deref()),
+                Err(message) => {
+                    eprintln!("{}\n{}", message, USAGE);
+                    return ExitCode::from(2);
+                }
+            }
+        }
+        "schema" => {
+            if let Some(arg) = args.get(1) {
+                eprintln!("Unexpected argument: {}\n{}", arg, USAGE);
+                return ExitCode::from(2);
+            }
+            LogController::render_report_schema();
+            Ok(())
+        }
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        command => {
+            eprintln!("Unknown command: {}\n{}", command, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Builds the match filter from the `--name=value` flags named in `FILTER_NAMES`, returning it with
+/// the other flags.
+fn match_filter(flags: &[String]) -> Result<(MatchFilter, Vec<String>), String> {
+    let mut filter = MatchFilter::default();
+    let mut others = Vec::new();
+
+    for flag in flags {
+        let option = flag.trim_start_matches('-');
+        let (name, value) = option.split_once('=').unwrap_or((option, ""));
+        if !FILTER_NAMES.contains(&name) {
+            others.push(flag.clone());
+            continue;
+        }
+        if value.is_empty() && name != "completed" {
+            return Err(format!("Missing value for option: {}", flag));
+        }
+        filter.set(name, value).map_err(|err| match err {
+            LogError::FilterError(message) => message,
+            err => format!("{:?}", err),
+        })?;
+    }
+    Ok((filter, others))
+}
+
+/// Builds the output options from the `--format=` and `--color=` flags, returning them with the
+/// other flags.
+fn output_options(flags: &[String]) -> Result<(OutputOptions, Vec<String>), String> {
+    let mut output = OutputOptions::default();
+    let mut others = Vec::new();
+
+    for flag in flags {
+        if let Some(format) = flag.strip_prefix("--format=") {
+            output.format = match format {
+                "auto" => OutputFormat::Auto,
+                "json" => OutputFormat::Json,
+                "legacy-json" => OutputFormat::LegacyJson,
+                "table" => OutputFormat::Table,
+                _ => return Err(format!("Invalid value for option --format: {}", format)),
+            };
+        } else if let Some(color) = flag.strip_prefix("--color=") {
+            output.color = match color {
+                "auto" => None,
+                "always" => Some(true),
+                "never" => Some(false),
+                _ => return Err(format!("Invalid value for option --color: {}", color)),
+            };
+        } else {
+            others.push(flag.clone());
+        }
+    }
+    Ok((output, others))
+}
+
+/// Builds the parse options from the `--name-policy=` flag, returning them with the other flags.
+fn parse_options(flags: &[String]) -> Result<(ParseOptions, Vec<String>), String> {
+    let mut options = ParseOptions::default();
+    let mut others = Vec::new();
+
+    for flag in flags {
+        if let Some(policy) = flag.strip_prefix("--name-policy=") {
+            options.name_policy = match policy {
+                "first" => NamePolicy::First,
+                "last" => NamePolicy::Last,
+                "most-used" => NamePolicy::MostUsed,
+                _ => {
+                    return Err(format!(
+                        "Invalid value for option --name-policy: {}",
+                        policy
+                    ))
+                }
+            };
+        } else {
+            others.push(flag.clone());
+        }
+    }
+    Ok((options, others))
+}
+
+/// Builds the generator options and the output file from the `--name=value` flags of `gen`.
+fn generator_options(flags: &[String]) -> Result<(GeneratorOptions, Option<String>), String> {
+    let mut options = GeneratorOptions::default();
+    let mut output = None;
+
+    for flag in flags {
+        let (name, value) = flag
+            .split_once('=')
+            .ok_or_else(|| format!("Missing value for option: {}", flag))?;
+        let invalid = || format!("Invalid value for option {}: {}", name, value);
+        let count = || value.parse::<usize>().map_err(|_| invalid());
+        let probability = || {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|p| (0.0..=1.0).contains(p))
+                .ok_or_else(invalid)
+        };
+        match name {
+            "--seed" => options.seed = value.parse().map_err(|_| invalid())?,
+            "--matches" => options.matches = count()?,
+            "--players" => options.players = count()?,
+            "--players-per-match" => options.players_per_match = count()?,
+            "--kills" => options.kills_per_match = count()?,
+            "--renames" => options.rename_rate = probability()?,
+            "--disconnects" => options.disconnect_rate = probability()?,
+            "--truncations" => options.truncate_rate = probability()?,
+            "--output" => output = Some(value.to_string()),
+            _ => return Err(format!("Unknown option: {}", name)),
         }
     }
+    Ok((options, output))
 }
@@ -0,0 +1,552 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    net::SocketAddr,
+    sync::{
+        mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex, RwLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use serde_json::Value;
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
+use tungstenite::{handshake::derive_accept_key, protocol::Role, Message, WebSocket};
+
+use crate::quake_log_parse::{
+    model::{
+        error::LogError,
+        filter::{filter_matches, MatchFilter, FILTER_NAMES},
+        identity::AliasMap,
+        log_model::{Match, PlayerScore},
+        metrics::Metrics,
+        stats::{player_stats, weapon_stats, PlayerStats},
+    },
+    view::{
+        api_view::{ApiView, Page},
+        metrics_view::{self, MetricsView},
+    },
+};
+
+/// The number of items of a page when the request does not say.
+pub const DEFAULT_PAGE_LIMIT: usize = 100;
+/// The largest page a request can ask for.
+pub const MAX_PAGE_LIMIT: usize = 1000;
+/// The number of threads answering requests.
+const WORKERS: usize = 4;
+/// How long a live connection stays silent before a keep-alive is sent, to notice closed clients.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// The data served by the HTTP API.
+///
+/// Player statistics of every match are computed once, when the data is loaded, rather than on every
+/// request; only requests filtering the matches compute them again.
+pub struct ApiState {
+    matches: Vec<Match>,
+    players: Vec<PlayerStats>,
+    ranking: Vec<PlayerScore>,
+    aliases: AliasMap,
+}
+
+/// A response of the HTTP API: a status code and a JSON body.
+#[derive(Debug, PartialEq)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: Value,
+}
+
+/// A local HTTP server answering the requests of the API on a few threads.
+///
+/// Besides the endpoints of `handle_request`, live events published on `events()` are pushed to the
+/// clients of `/events`, as Server-Sent Events, and of `/events/ws`, as WebSocket text messages, and
+/// the `metrics()` are served on `/metrics` in the Prometheus text format.
+pub struct ApiServer {
+    server: Arc<Server>,
+    state: Arc<RwLock<ApiState>>,
+    events: Arc<Broadcaster>,
+    metrics: Arc<Mutex<Metrics>>,
+}
+
+/// Pushes messages to every live client connected.
+#[derive(Default)]
+pub struct Broadcaster {
+    clients: Mutex<Vec<Sender<Arc<str>>>>,
+}
+
+impl Broadcaster {
+    /// Sends a message to every client, forgetting the clients that disconnected.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message, e.g. a `LiveEvent` serialised as JSON.
+    pub fn publish(&self, message: &str) {
+        let message: Arc<str> = Arc::from(message);
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.retain(|client| client.send(Arc::clone(&message)).is_ok());
+        }
+    }
+
+    /// Registers a new client, which receives the messages published from now on.
+    pub fn subscribe(&self) -> Receiver<Arc<str>> {
+        let (sender, receiver) = channel();
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.push(sender);
+        }
+        receiver
+    }
+}
+
+impl ApiState {
+    /// Prepares matches to be served, computing the statistics of each player.
+    ///
+    /// # Arguments
+    ///
+    /// * `matches` - A vector of `Match` structs to serve.
+    /// * `aliases` - A reference to the `AliasMap` supplied by the user.
+    pub fn new(matches: Vec<Match>, aliases: &AliasMap) -> Self {
+        let players = player_stats(&matches, aliases);
+        let ranking = ranking(&players);
+        ApiState {
+            matches,
+            players,
+            ranking,
+            aliases: aliases.clone(),
+        }
+    }
+}
+
+impl ApiServer {
+    /// Binds the server to a local address.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address to listen on, e.g. `127.0.0.1:8080`, or `127.0.0.1:0` for any free
+    ///   port (see `local_addr`).
+    /// * `state` - The `ApiState` to serve.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ApiServer, LogError>` - A `Result` with the server, or an error (`Err`) if the address
+    ///   cannot be bound.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the address is invalid or already in use.
+    pub fn bind(address: &str, state: ApiState) -> Result<Self, LogError> {
+        let server = Server::http(address).map_err(|err| {
+            LogError::ServerError(format!("Error listening on {}: {}", address, err))
+        })?;
+        Ok(ApiServer {
+            server: Arc::new(server),
+            state: Arc::new(RwLock::new(state)),
+            events: Arc::new(Broadcaster::default()),
+            metrics: Arc::new(Mutex::new(Metrics::default())),
+        })
+    }
+
+    /// Returns the address the server listens on.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    /// Returns the data served, which can be replaced while the server runs.
+    pub fn state(&self) -> Arc<RwLock<ApiState>> {
+        Arc::clone(&self.state)
+    }
+
+    /// Returns the broadcaster of the live events pushed to the clients of `/events`.
+    pub fn events(&self) -> Arc<Broadcaster> {
+        Arc::clone(&self.events)
+    }
+
+    /// Returns the metrics served on `/metrics`, which can be updated while the server runs.
+    pub fn metrics(&self) -> Arc<Mutex<Metrics>> {
+        Arc::clone(&self.metrics)
+    }
+
+    /// Answers requests until the server is stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if a thread answering requests cannot be started.
+    pub fn run(&self) -> Result<(), LogError> {
+        let workers = (0..WORKERS)
+            .map(|idx| {
+                let server = Arc::clone(&self.server);
+                let state = Arc::clone(&self.state);
+                let events = Arc::clone(&self.events);
+                let metrics = Arc::clone(&self.metrics);
+                thread::Builder::new()
+                    .name(format!("http-{}", idx))
+                    .spawn(move || {
+                        for request in server.incoming_requests() {
+                            match request.url() {
+                                "/events" | "/events/ws" => stream_events(request, &events),
+                                "/metrics" => respond_metrics(request, &metrics),
+                                _ => respond(request, &state),
+                            }
+                        }
+                    })
+                    .map_err(|err| {
+                        LogError::ServerError(format!("Error starting the server: {}", err))
+                    })
+            })
+            .collect::<Result<Vec<_>, LogError>>()?;
+
+        for worker in workers {
+            let _ = worker.join();
+        }
+        Ok(())
+    }
+}
+
+/// Streams live events to a client on a thread of its own, for as long as it stays connected.
+///
+/// `/events/ws` expects a WebSocket handshake; `/events` answers with a `text/event-stream`.
+fn stream_events(request: Request, events: &Arc<Broadcaster>) {
+    let websocket = request.url() == "/events/ws";
+    let key = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Sec-WebSocket-Key"))
+        .map(|header| derive_accept_key(header.value.as_bytes()));
+    if websocket && key.is_none() {
+        let body = ApiView::error("Expected a WebSocket handshake").to_string();
+        let _ = request.respond(Response::from_string(body).with_status_code(400));
+        return;
+    }
+    if request.method() != &Method::Get {
+        let body = ApiView::error("Only GET requests are supported").to_string();
+        let _ = request.respond(Response::from_string(body).with_status_code(405));
+        return;
+    }
+
+    let receiver = events.subscribe();
+    let _ = thread::Builder::new()
+        .name("http-events".to_string())
+        .spawn(move || match key {
+            Some(key) => stream_websocket(request, &key, &receiver),
+            None => stream_server_sent_events(request, &receiver),
+        });
+}
+
+fn stream_server_sent_events(request: Request, receiver: &Receiver<Arc<str>>) {
+    let mut writer = request.into_writer();
+    let headers = write!(
+        writer,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\
+         Connection: keep-alive\r\n\r\n"
+    );
+    if headers.and_then(|()| writer.flush()).is_err() {
+        return;
+    }
+    loop {
+        let sent = match receiver.recv_timeout(KEEP_ALIVE) {
+            Ok(message) => write!(writer, "data: {}\n\n", message),
+            Err(RecvTimeoutError::Timeout) => write!(writer, ": keep-alive\n\n"),
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        if sent.and_then(|()| writer.flush()).is_err() {
+            return;
+        }
+    }
+}
+
+fn stream_websocket(request: Request, key: &str, receiver: &Receiver<Arc<str>>) {
+    let header = |field: &str, value: &str| {
+        Header::from_bytes(field, value).expect("handshake header is valid")
+    };
+    let response = Response::empty(StatusCode(101))
+        .with_header(header("Upgrade", "websocket"))
+        .with_header(header("Connection", "Upgrade"))
+        .with_header(header("Sec-WebSocket-Accept", key));
+    let stream = request.upgrade("websocket", response);
+    let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+    loop {
+        let sent = match receiver.recv_timeout(KEEP_ALIVE) {
+            Ok(message) => socket.send(Message::text(message.to_string())),
+            Err(RecvTimeoutError::Timeout) => socket.send(Message::Ping(Vec::new())),
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        if sent.is_err() {
+            return;
+        }
+    }
+}
+
+/// Answers a request on its connection; a client that went away is not an error of the server.
+fn respond(request: Request, state: &RwLock<ApiState>) {
+    let response = match state.read() {
+        Ok(state) => handle_request(&state, request.method().as_str(), request.url()),
+        Err(_) => ApiResponse {
+            status: 500,
+            body: ApiView::error("The served data is unavailable"),
+        },
+    };
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+    let _ = request.respond(
+        Response::from_string(response.body.to_string())
+            .with_status_code(response.status)
+            .with_header(content_type),
+    );
+}
+
+/// Answers a request for the metrics, in the Prometheus text format.
+fn respond_metrics(request: Request, metrics: &Mutex<Metrics>) {
+    if request.method() != &Method::Get {
+        let body = ApiView::error("Only GET requests are supported").to_string();
+        let _ = request.respond(Response::from_string(body).with_status_code(405));
+        return;
+    }
+    let response = match metrics.lock() {
+        Ok(metrics) => Response::from_string(MetricsView::render(&metrics)),
+        Err(_) => Response::from_string("The metrics are unavailable").with_status_code(500),
+    };
+    let content_type = Header::from_bytes("Content-Type", metrics_view::CONTENT_TYPE)
+        .expect("static header is valid");
+    let _ = request.respond(response.with_header(content_type));
+}
+
+/// Answers a request of the HTTP API.
+///
+/// The endpoints are:
+///
+/// * `/matches` - The matches, filtered by the parameters named in `FILTER_NAMES` (see
+///   `MatchFilter::set`), e.g. `?map=q3dm17&min-players=4`.
+/// * `/matches/{id}` - A match by id.
+/// * `/players` - The statistics of every player, in ranking order, over the matches filtered like
+///   `/matches`.
+/// * `/players/{name}` - The statistics of a player, by any of its names.
+/// * `/ranking` - The player ranking, over the matches filtered like `/matches`.
+/// * `/weapons` - The kills by means of death, filtered like `/matches`.
+///
+/// The lists take `offset` and `limit` parameters (at most `MAX_PAGE_LIMIT`) to be read page by page.
+///
+/// # Arguments
+///
+/// * `state` - A reference to the `ApiState` to serve.
+/// * `method` - The HTTP method of the request.
+/// * `url` - The path and query of the request, e.g. `/matches?map=q3dm17&limit=10`.
+///
+/// # Returns
+///
+/// * `ApiResponse` - The status and JSON body of the response: 200 with the data, 400 for an invalid
+///   parameter, 404 for an unknown resource or 405 for a method other than `GET`.
+pub fn handle_request(state: &ApiState, method: &str, url: &str) -> ApiResponse {
+    if method != Method::Get.as_str() {
+        return error(405, "Only GET requests are supported");
+    }
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let params = match parse_query(query) {
+        Ok(params) => params,
+        Err(message) => return error(400, &message),
+    };
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(percent_decode)
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    let result = match segments.as_slice() {
+        ["matches"] => list_matches(state, &params),
+        ["matches", id] => get_match(state, id, &params),
+        ["players"] => list_players(state, &params),
+        ["players", name] => get_player(state, name, &params),
+        ["ranking"] => list_ranking(state, &params),
+        ["weapons"] => list_weapons(state, &params),
+        _ => Err(error(404, &format!("Unknown endpoint: {}", path))),
+    };
+    result.unwrap_or_else(|response| response)
+}
+
+type Params = HashMap<String, String>;
+
+fn list_matches(state: &ApiState, params: &Params) -> Result<ApiResponse, ApiResponse> {
+    let (offset, limit) = pagination(params, FILTER_NAMES)?;
+    let matches = filter_matches(&state.matches, &match_filter(params)?);
+    Ok(ok(ApiView::matches(page(&matches, offset, limit))))
+}
+
+fn get_match(state: &ApiState, id: &str, params: &Params) -> Result<ApiResponse, ApiResponse> {
+    allow_params(params, &[])?;
+    let found = id
+        .parse::<i32>()
+        .ok()
+        .and_then(|id| state.matches.iter().find(|mat| mat.id == id));
+    match found {
+        Some(mat) => Ok(ok(ApiView::game(mat))),
+        None => Err(error(404, &format!("Unknown match: {}", id))),
+    }
+}
+
+fn list_players(state: &ApiState, params: &Params) -> Result<ApiResponse, ApiResponse> {
+    let (offset, limit) = pagination(params, FILTER_NAMES)?;
+    let filter = match_filter(params)?;
+    if filter == MatchFilter::default() {
+        return Ok(ok(ApiView::players(page(&state.players, offset, limit))));
+    }
+    let players = filtered_players(state, &filter);
+    Ok(ok(ApiView::players(page(&players, offset, limit))))
+}
+
+fn get_player(state: &ApiState, name: &str, params: &Params) -> Result<ApiResponse, ApiResponse> {
+    allow_params(params, &[])?;
+    let found = state
+        .players
+        .iter()
+        .find(|player| player.name == name)
+        .or_else(|| {
+            state
+                .players
+                .iter()
+                .find(|player| player.aliases.iter().any(|alias| alias == name))
+        });
+    match found {
+        Some(player) => Ok(ok(ApiView::player(player))),
+        None => Err(error(404, &format!("Unknown player: {}", name))),
+    }
+}
+
+fn list_ranking(state: &ApiState, params: &Params) -> Result<ApiResponse, ApiResponse> {
+    let (offset, limit) = pagination(params, FILTER_NAMES)?;
+    let filter = match_filter(params)?;
+    if filter == MatchFilter::default() {
+        return Ok(ok(ApiView::ranking(page(&state.ranking, offset, limit))));
+    }
+    let ranking = ranking(&filtered_players(state, &filter));
+    Ok(ok(ApiView::ranking(page(&ranking, offset, limit))))
+}
+
+/// Computes the statistics of the players over the matches that meet a filter.
+fn filtered_players(state: &ApiState, filter: &MatchFilter) -> Vec<PlayerStats> {
+    let matches: Vec<Match> = filter_matches(&state.matches, filter)
+        .into_iter()
+        .cloned()
+        .collect();
+    player_stats(&matches, &state.aliases)
+}
+
+fn ranking(players: &[PlayerStats]) -> Vec<PlayerScore> {
+    players
+        .iter()
+        .map(|player| PlayerScore {
+            name: player.name.clone(),
+            kills: player.kills,
+        })
+        .collect()
+}
+
+fn list_weapons(state: &ApiState, params: &Params) -> Result<ApiResponse, ApiResponse> {
+    allow_params(params, FILTER_NAMES)?;
+    let matches = filter_matches(&state.matches, &match_filter(params)?);
+    Ok(ok(ApiView::weapons(&weapon_stats(matches))))
+}
+
+fn match_filter(params: &Params) -> Result<MatchFilter, ApiResponse> {
+    let mut filter = MatchFilter::default();
+    for name in FILTER_NAMES {
+        if let Some(value) = params.get(*name) {
+            filter.set(name, value).map_err(|err| match err {
+                LogError::FilterError(message) => error(400, &message),
+                err => error(400, &format!("{:?}", err)),
+            })?;
+        }
+    }
+    Ok(filter)
+}
+
+/// Reads the `offset` and `limit` parameters, rejecting any parameter other than those and `allowed`.
+fn pagination(params: &Params, allowed: &[&str]) -> Result<(usize, usize), ApiResponse> {
+    let allowed: Vec<&str> = allowed
+        .iter()
+        .chain(&["offset", "limit"])
+        .copied()
+        .collect();
+    allow_params(params, &allowed)?;
+
+    let number = |name: &str, default: usize| match params.get(name) {
+        None => Ok(default),
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| error(400, &format!("Invalid {}: {}", name, value))),
+    };
+    let offset = number("offset", 0)?;
+    let limit = number("limit", DEFAULT_PAGE_LIMIT)?;
+    if limit == 0 || limit > MAX_PAGE_LIMIT {
+        return Err(error(
+            400,
+            &format!("The limit must be between 1 and {}", MAX_PAGE_LIMIT),
+        ));
+    }
+    Ok((offset, limit))
+}
+
+fn allow_params(params: &Params, allowed: &[&str]) -> Result<(), ApiResponse> {
+    match params.keys().find(|name| !allowed.contains(&name.as_str())) {
+        Some(name) => Err(error(400, &format!("Unknown parameter: {}", name))),
+        None => Ok(()),
+    }
+}
+
+fn page<T>(items: &[T], offset: usize, limit: usize) -> Page<'_, T> {
+    let start = offset.min(items.len());
+    let end = start.saturating_add(limit).min(items.len());
+    Page {
+        items: &items[start..end],
+        total: items.len(),
+        offset,
+        limit,
+    }
+}
+
+/// Splits a query string into its decoded parameters.
+fn parse_query(query: &str) -> Result<Params, String> {
+    let mut params = Params::new();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let name = percent_decode(&name.replace('+', " "));
+        let value = percent_decode(&value.replace('+', " "));
+        if params.insert(name.clone(), value).is_some() {
+            return Err(format!("Repeated parameter: {}", name));
+        }
+    }
+    Ok(params)
+}
+
+/// Decodes the `%XX` escapes of a URL component, keeping invalid escapes as they are.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let escaped = (bytes[idx] == b'%')
+            .then(|| text.get(idx + 1..idx + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                idx += 3;
+            }
+            None => {
+                decoded.push(bytes[idx]);
+                idx += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn ok(body: Value) -> ApiResponse {
+    ApiResponse { status: 200, body }
+}
+
+fn error(status: u16, message: &str) -> ApiResponse {
+    ApiResponse {
+        status,
+        body: ApiView::error(message),
+    }
+}
@@ -1,16 +1,115 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, IsTerminal, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    thread,
+    time::Duration,
+};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    DefaultTerminal,
+};
+
 use crate::quake_log_parse::{
-    model::{error::LogError, log_model::LogModel},
-    view::log_view::LogView,
+    controller::api_controller::{ApiServer, ApiState, Broadcaster},
+    model::{
+        browser::{Browser, BrowserAction},
+        checkpoint::{self, Checkpoint},
+        diff,
+        error::LogError,
+        event::LogEvent,
+        export::{self, EventExporter},
+        filter::{retain_matches, MatchFilter},
+        generator::{self, GeneratorOptions},
+        identity::{get_alias_path, AliasMap},
+        input::{open_log_file, read_log_file},
+        live::{LiveFeed, LogTail},
+        log_model::{get_log_path, read_log, LogModel, Match, ParseOptions},
+        metrics::Metrics,
+        query::Query,
+        report::{self, Report, ReportCheckpoint},
+        sources::{self, LogsReport},
+        storage::{ImportSummary, Storage},
+        validate::{self, Validation},
+    },
+    view::{
+        browser_view::BrowserView, diff_view::DiffView, log_view::LogView,
+        metrics_view::MetricsView, query_view::QueryView, terminal_view::TerminalStyle,
+        validate_view::ValidateView,
+    },
 };
 
 pub struct LogController;
 
+/// The format reports are rendered in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Tables when the standard output is a terminal, JSON otherwise, so pipelines keep reading JSON.
+    #[default]
+    Auto,
+    /// A single JSON document with a `schema_version`, described by `REPORT_SCHEMA`.
+    Json,
+    /// The JSON of reports before `schema_version` was introduced, for existing consumers: one
+    /// document per section, with the rankings as `{name: kills}` objects.
+    LegacyJson,
+    /// Tables drawn with box-drawing characters and bar charts, for people at a terminal.
+    Table,
+}
+
+/// The format the differences between two reports are rendered in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiffFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// The format the findings of the validation of logs are rendered in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValidateFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// How reports are rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    /// Whether tables are coloured, or `None` to colour them only on a terminal when `NO_COLOR` is
+    /// not set.
+    pub color: Option<bool>,
+}
+
+impl OutputOptions {
+    /// Returns the style to draw tables with, or `None` to render JSON.
+    fn terminal_style(&self) -> Option<TerminalStyle> {
+        let tables = match self.format {
+            OutputFormat::Auto => io::stdout().is_terminal(),
+            OutputFormat::Json | OutputFormat::LegacyJson => false,
+            OutputFormat::Table => true,
+        };
+        tables.then(|| TerminalStyle::detect(self.color))
+    }
+}
+
 impl LogController {
     /// Generates and renders a game report.
     ///
     /// This function generates a game report by first processing the game log using `LogModel::process_log()`
     /// to obtain information about game matches, player rankings, and means of kills. It then renders the report using
-    /// `LogView::render_report()`.
+    /// `LogView::render_json_report()`, `LogView::render_report()` for the legacy JSON, or
+    /// `LogView::render_report_tables()` for tables.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - A reference to the `MatchFilter` selecting the matches to report.
+    ///
+    /// * `options` - A reference to the `ParseOptions` to parse the log with.
+    ///
+    /// * `output` - A reference to the `OutputOptions` to render the report with.
     ///
     /// # Returns
     ///
@@ -20,10 +119,834 @@ impl LogController {
     /// # Errors
     ///
     /// Returns an error of type `LogError` if there are any issues with processing the log or rendering the report.
-    pub fn generate_and_render_report() -> Result<(), LogError> {
-        let (matches, player_rank) = LogModel::process_log()?;
-        LogView::render_report(matches, player_rank)?;
+    pub fn generate_and_render_report(
+        filter: &MatchFilter,
+        options: &ParseOptions,
+        output: &OutputOptions,
+    ) -> Result<(), LogError> {
+        let (matches, player_rank) = LogModel::process_log(filter, options)?;
+        match output.terminal_style() {
+            Some(style) => LogView::render_report_tables(&matches, &player_rank, &style),
+            None if output.format == OutputFormat::LegacyJson => {
+                LogView::render_report(matches, player_rank)?
+            }
+            None => LogView::render_json_report(&Report::from_matches(matches, &player_rank))?,
+        }
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Generates and renders a report of several log files.
+    ///
+    /// This function expands the inputs into log files (`sources::resolve_inputs`), parses them,
+    /// optionally in parallel, and renders a report of each file along with the player ranking combined
+    /// across all of them as a single JSON document (`Report`). Only the matches that meet `filter`
+    /// are reported and ranked.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A slice of strings with the files, directories and glob patterns to read.
+    ///
+    /// * `parallel` - Whether to parse the files in parallel.
+    ///
+    /// * `filter` - A reference to the `MatchFilter` selecting the matches to report.
+    ///
+    /// * `options` - A reference to the `ParseOptions` to parse the logs with.
+    ///
+    /// * `output` - A reference to the `OutputOptions` to render the report with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during report generation or rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if any input cannot be read or parsed, or if there are any
+    /// issues with rendering the report.
+    pub fn generate_and_render_logs_report(
+        inputs: &[String],
+        parallel: bool,
+        filter: &MatchFilter,
+        options: &ParseOptions,
+        output: &OutputOptions,
+    ) -> Result<(), LogError> {
+        let aliases = match get_alias_path() {
+            Some(path) => AliasMap::load(&path)?,
+            None => AliasMap::default(),
+        };
+        let paths = sources::resolve_inputs(inputs)?;
+        let mut matches = sources::process_files(&paths, options, parallel)?;
+        retain_matches(&mut matches, filter);
+        let files: Vec<String> = paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        render_logs_report(sources::report_matches(&files, matches, &aliases), output)?;
+
+        Ok(())
+    }
+
+    /// Imports log files into a database and renders what was imported.
+    ///
+    /// This function expands the inputs into log files (`sources::resolve_inputs`) and stores the
+    /// matches of each one with `Storage::import_file()`, skipping the matches the database already
+    /// holds, then renders the number of matches imported, skipped and updated per file.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The path of the SQLite database, created if it does not exist.
+    ///
+    /// * `inputs` - A slice of strings with the files, directories and glob patterns to read.
+    ///
+    /// * `options` - A reference to the `ParseOptions` to parse the logs with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during the import or rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the database cannot be opened, or any input cannot be
+    /// read, parsed or stored.
+    pub fn import_logs(
+        database: &str,
+        inputs: &[String],
+        options: &ParseOptions,
+    ) -> Result<(), LogError> {
+        let mut storage = Storage::open(Path::new(database))?;
+        let summaries = import_files(&mut storage, inputs, options)?;
+        LogView::render_import_summary(summaries)?;
+
+        Ok(())
+    }
+
+    /// Generates and renders a report of the matches stored in a database.
+    ///
+    /// This function first imports the given inputs, if any, like `import_logs()`, then loads every
+    /// match of the database and renders a report of each log file they were read from along with the
+    /// player ranking combined across all of them, as `generate_and_render_logs_report()` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The path of the SQLite database, created if it does not exist.
+    ///
+    /// * `inputs` - A slice of strings with the files, directories and glob patterns to import first.
+    ///
+    /// * `filter` - A reference to the `MatchFilter` selecting the matches to report.
+    ///
+    /// * `options` - A reference to the `ParseOptions` to parse the logs with.
+    ///
+    /// * `output` - A reference to the `OutputOptions` to render the report with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during report generation or rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the database cannot be opened or read, if any input
+    /// cannot be imported, or if there are any issues with rendering the report.
+    pub fn generate_and_render_database_report(
+        database: &str,
+        inputs: &[String],
+        filter: &MatchFilter,
+        options: &ParseOptions,
+        output: &OutputOptions,
+    ) -> Result<(), LogError> {
+        let aliases = match get_alias_path() {
+            Some(path) => AliasMap::load(&path)?,
+            None => AliasMap::default(),
+        };
+        let mut storage = Storage::open(Path::new(database))?;
+        import_files(&mut storage, inputs, options)?;
+
+        let mut matches = storage.load_matches()?;
+        retain_matches(&mut matches, filter);
+        let mut files: Vec<String> = Vec::new();
+        for mat in &matches {
+            if !files.contains(&mat.data.source_file) {
+                files.push(mat.data.source_file.clone());
+            }
+        }
+        let report = sources::report_matches(&files, matches, &aliases);
+        render_logs_report(report, output)?;
+
+        Ok(())
+    }
+
+    /// Merges reports exported with `--format=json` and renders a report of all their matches.
+    ///
+    /// This function expands the inputs into report files (`sources::resolve_inputs`), loads them with
+    /// `Report::load()` and combines their matches with `report::merge_reports()`, then renders a
+    /// report of each log file they were read from along with the player ranking combined across all
+    /// of them, as `generate_and_render_logs_report()` does, without reading the logs again. Matches
+    /// found in more than one report are merged once, and how many were skipped is written to stderr.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A slice of strings with the report files, directories and glob patterns to read.
+    ///
+    /// * `filter` - A reference to the `MatchFilter` selecting the matches to report.
+    ///
+    /// * `output` - A reference to the `OutputOptions` to render the report with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during the merge or rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if any report cannot be read or is not a report of the
+    /// current schema version, or if there are any issues with rendering the report.
+    pub fn merge_and_render_reports(
+        inputs: &[String],
+        filter: &MatchFilter,
+        output: &OutputOptions,
+    ) -> Result<(), LogError> {
+        let aliases = match get_alias_path() {
+            Some(path) => AliasMap::load(&path)?,
+            None => AliasMap::default(),
+        };
+        let mut reports = Vec::new();
+        for path in sources::resolve_inputs(inputs)? {
+            let report = Report::load(&path)?;
+            reports.push((path.display().to_string(), report));
+        }
+
+        let merged = report::merge_reports(reports);
+        LogView::render_skipped_matches(merged.skipped);
+        let mut matches = merged.matches;
+        retain_matches(&mut matches, filter);
+        let mut files: Vec<String> = Vec::new();
+        for mat in &matches {
+            if !files.contains(&mat.data.source_file) {
+                files.push(mat.data.source_file.clone());
+            }
+        }
+        render_logs_report(sources::report_matches(&files, matches, &aliases), output)?;
+
+        Ok(())
+    }
+
+    /// Compares two reports, or two logs, and renders the differences.
+    ///
+    /// This function loads the matches of both files with `diff::load_matches()`, so each one can be a
+    /// report exported with `--format=json` or a log, compares them with `diff::diff_matches()` and
+    /// renders the matches added, removed and changed, and the movements in the ranking.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - The path of the first report or log.
+    ///
+    /// * `after` - The path of the second report or log.
+    ///
+    /// * `format` - The `DiffFormat` to render the differences in.
+    ///
+    /// * `options` - A reference to the `ParseOptions` to parse the log files with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, LogError>` - A `Result` with `true` if there are differences, or an error
+    ///   (`Err`) if any problem occurs while comparing or rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if either file cannot be read or parsed, or if there are
+    /// any issues with rendering the differences.
+    pub fn diff(
+        before: &str,
+        after: &str,
+        format: DiffFormat,
+        options: &ParseOptions,
+    ) -> Result<bool, LogError> {
+        let aliases = match get_alias_path() {
+            Some(path) => AliasMap::load(&path)?,
+            None => AliasMap::default(),
+        };
+        let before = diff::load_matches(Path::new(before), options)?;
+        let after = diff::load_matches(Path::new(after), options)?;
+
+        let diff = diff::diff_matches(&before, &after, &aliases);
+        match format {
+            DiffFormat::Text => DiffView::render_text(&diff, &TerminalStyle::detect(None)),
+            DiffFormat::Json => DiffView::render_json(&diff)?,
+        }
+
+        Ok(!diff.is_empty())
+    }
+
+    /// Checks logs for structural problems and renders the findings.
+    ///
+    /// This function reads each log line by line with `validate::validate_log()`, without producing
+    /// a report, and renders every finding with its severity: kills outside a match, unknown client
+    /// ids and means of death, timestamps going backwards, unterminated matches, etc.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The log files, directories or glob patterns to check, or none for `qgames.log`
+    ///   next to the executable.
+    ///
+    /// * `format` - The `ValidateFormat` to render the findings in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, LogError>` - A `Result` with `true` if any finding is an error, or an error
+    ///   (`Err`) if any problem occurs while reading the logs or rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the inputs cannot be resolved or read, or if there are
+    /// any issues with rendering the findings.
+    pub fn validate(inputs: &[String], format: ValidateFormat) -> Result<bool, LogError> {
+        let paths = if inputs.is_empty() {
+            vec![get_log_path()?]
+        } else {
+            sources::resolve_inputs(inputs)?
+        };
+
+        let mut validation = Validation::default();
+        for path in &paths {
+            let file = path.display().to_string();
+            validation.extend(validate::validate_log(open_log_file(path)?, &file)?);
+        }
+        match format {
+            ValidateFormat::Text => {
+                ValidateView::render_text(&validation, &TerminalStyle::detect(None))
+            }
+            ValidateFormat::Json => ValidateView::render_json(&validation)?,
+        }
+
+        Ok(validation.errors > 0)
+    }
+
+    /// Generates and renders a report of the matches completed in a log since the last run.
+    ///
+    /// This function loads the checkpoint saved by the previous run, processes the log from there with
+    /// `checkpoint::process_incremental()`, renders where it resumed from and a report of the new
+    /// matches as a single JSON document (`Report`), and only then saves the new checkpoint, so a
+    /// run that fails is repeated in full by the next one.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The path of the log file.
+    ///
+    /// * `checkpoint_path` - The path of the checkpoint file, created by the first run.
+    ///
+    /// * `filter` - A reference to the `MatchFilter` selecting the new matches to report; the
+    ///   checkpoint moves past the others all the same.
+    ///
+    /// * `options` - A reference to the `ParseOptions` to parse the log with.
+    ///
+    /// * `output` - A reference to the `OutputOptions` to render the report with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during report generation or rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the log or the checkpoint cannot be read, parsed or
+    /// written, or if there are any issues with rendering the report.
+    pub fn generate_and_render_incremental_report(
+        input: &str,
+        checkpoint_path: &str,
+        filter: &MatchFilter,
+        options: &ParseOptions,
+        output: &OutputOptions,
+    ) -> Result<(), LogError> {
+        let aliases = match get_alias_path() {
+            Some(path) => AliasMap::load(&path)?,
+            None => AliasMap::default(),
+        };
+        let checkpoint_path = Path::new(checkpoint_path);
+        let previous = Checkpoint::load(checkpoint_path)?;
+        let mut run =
+            checkpoint::process_incremental(Path::new(input), previous.as_ref(), options)?;
+
+        retain_matches(&mut run.matches, filter);
+        let files = [run.checkpoint.file.clone()];
+        let report = sources::report_matches(&files, run.matches, &aliases);
+        match output.terminal_style() {
+            Some(style) => {
+                LogView::render_checkpoint_line(&run.checkpoint, run.resume, &style);
+                LogView::render_logs_report_tables(&report, &style);
+            }
+            None if output.format == OutputFormat::LegacyJson => {
+                LogView::render_checkpoint(&run.checkpoint, run.resume)?;
+                LogView::render_logs_report(report)?;
+            }
+            None => {
+                let mut document = Report::from_logs_report(report);
+                document.checkpoint = Some(ReportCheckpoint::new(&run.checkpoint, run.resume));
+                LogView::render_json_report(&document)?;
+            }
+        }
+        run.checkpoint.save(checkpoint_path)?;
+
+        Ok(())
+    }
+
+    /// Renders the JSON Schema of the report written by `--format=json`.
+    pub fn render_report_schema() {
+        LogView::render_report_schema();
+    }
+
+    /// Serves the reports and statistics of log files or a database over a local HTTP API.
+    ///
+    /// This function loads the matches of the inputs, or of the database when one is given (after
+    /// importing the inputs into it), and answers the requests of the API (see
+    /// `api_controller::handle_request`) until the process is stopped.
+    ///
+    /// When following a log, its new lines are parsed as they are written and their events pushed to
+    /// the live clients of `/events` (see `LiveFeed`), and the data served is updated with the matches
+    /// of the feed whenever a match ends. Lines that cannot be parsed are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address to listen on, e.g. `127.0.0.1:8080`.
+    ///
+    /// * `inputs` - A slice of strings with the files, directories and glob patterns to read.
+    ///
+    /// * `database` - The path of a SQLite database to serve, if any.
+    ///
+    /// * `follow` - Whether to follow the log, which must then be the only input.
+    ///
+    /// * `options` - A reference to the `ParseOptions` to parse the logs with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if the data
+    ///   cannot be loaded or the server cannot be started.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if any input or the database cannot be read, if the address
+    /// cannot be listened on, or if the log followed can no longer be read.
+    pub fn serve(
+        address: &str,
+        inputs: &[String],
+        database: Option<&str>,
+        follow: bool,
+        options: &ParseOptions,
+    ) -> Result<(), LogError> {
+        let aliases = match get_alias_path() {
+            Some(path) => AliasMap::load(&path)?,
+            None => AliasMap::default(),
+        };
+        let paths = sources::resolve_inputs(inputs)?;
+        if follow && (paths.len() != 1 || database.is_some()) {
+            return Err(LogError::ServerError(
+                "Only a single log file can be followed".to_string(),
+            ));
+        }
+        let mut followed = None;
+        let matches = match database {
+            Some(database) => {
+                let mut storage = Storage::open(Path::new(database))?;
+                import_files(&mut storage, inputs, options)?;
+                storage.load_matches()?
+            }
+            None if follow => {
+                let catch_up = catch_up_log(&paths[0], options)?;
+                let matches = served_matches(&catch_up.feed, &paths[0]);
+                followed = Some(catch_up);
+                matches
+            }
+            None => sources::process_files(&paths, options, true)?,
+        };
+
+        let server = ApiServer::bind(address, ApiState::new(matches, &aliases))?;
+        LogView::render_server_address(server.local_addr())?;
+        let Some(catch_up) = followed else {
+            if let Ok(mut metrics) = server.metrics().lock() {
+                *metrics = collect_metrics(&paths, options)?;
+            }
+            return server.run();
+        };
+
+        if let Ok(mut metrics) = server.metrics().lock() {
+            *metrics = catch_up.metrics;
+        }
+        let (state, events, metrics) = (server.state(), server.events(), server.metrics());
+        thread::spawn(move || server.run());
+        let tail = LogTail::new(&paths[0], catch_up.read as u64)?;
+        follow_log(
+            &paths[0],
+            catch_up.feed,
+            tail,
+            &state,
+            &events,
+            &metrics,
+            &aliases,
+        )
+    }
+
+    /// Generates and renders the metrics of log files.
+    ///
+    /// This function records every line of the inputs in `Metrics` and renders them in the Prometheus
+    /// text format, to the standard output or to a file for the textfile collector of the node
+    /// exporter. The file is replaced at once, so the collector never reads it half written.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The log files, directories or glob patterns to read.
+    ///
+    /// * `options` - A reference to the `ParseOptions` to parse the logs with.
+    ///
+    /// * `output` - The path of the file to write, or `None` for the standard output.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if the logs
+    ///   cannot be read or the metrics cannot be written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if any input cannot be read or the output file cannot be
+    /// written.
+    pub fn generate_and_render_metrics(
+        inputs: &[String],
+        options: &ParseOptions,
+        output: Option<&str>,
+    ) -> Result<(), LogError> {
+        let metrics = collect_metrics(&sources::resolve_inputs(inputs)?, options)?;
+        let text = MetricsView::render(&metrics);
+        let Some(output) = output else {
+            print!("{}", text);
+            return Ok(());
+        };
+
+        let path = Path::new(output);
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, text)
+            .and_then(|()| fs::rename(&temporary, path))
+            .map_err(|err| {
+                LogError::WriteLogError(format!("Error writing the metrics to {}: {}", output, err))
+            })
+    }
+
+    /// Answers a query over the events of log files and renders the rows as a table.
+    ///
+    /// This function parses the query (see `Query`), runs it over the events of the inputs, or of the
+    /// default log when there are none, and renders the answer using `QueryView::render_table()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - A string containing the query, e.g. `kills where killer = Zeh group by means`.
+    ///
+    /// * `inputs` - A slice of strings with the files, directories and glob patterns to read.
+    ///
+    /// * `options` - A reference to the `ParseOptions` naming the players that renamed themselves.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if the query
+    ///   is invalid or the logs cannot be read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the query cannot be parsed or any input cannot be read.
+    pub fn run_query(
+        query: &str,
+        inputs: &[String],
+        options: &ParseOptions,
+    ) -> Result<(), LogError> {
+        let query = Query::parse(query)?;
+        let contents = if inputs.is_empty() {
+            vec![read_log()?]
+        } else {
+            sources::resolve_inputs(inputs)?
+                .iter()
+                .map(|path| read_log_file(path))
+                .collect::<Result<Vec<String>, LogError>>()?
+        };
+        QueryView::render_table(&query.run(contents.iter().map(String::as_str), options));
+
+        Ok(())
+    }
+
+    /// Browses the matches of a log in a full-screen terminal UI.
+    ///
+    /// This function parses the log into a `Browser` and draws it with `BrowserView::draw()` until the
+    /// user quits, turning the keys pressed into `BrowserAction`s. The terminal is restored on exit,
+    /// even if drawing fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The path of the log file to browse, or `None` for the default log.
+    ///
+    /// * `options` - A reference to the `ParseOptions` to parse the log with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if the log
+    ///   cannot be read or the terminal cannot be drawn on.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the log cannot be read or parsed, or if the terminal
+    /// cannot be read from or written to.
+    pub fn browse(input: Option<&str>, options: &ParseOptions) -> Result<(), LogError> {
+        let content = match input {
+            Some(path) => read_log_file(Path::new(path))?,
+            None => read_log()?,
+        };
+        let mut browser = Browser::new(content, *options)?;
+
+        let mut terminal = ratatui::init();
+        let result = run_browser(&mut terminal, &mut browser);
+        ratatui::restore();
+        result
+            .map_err(|err| LogError::WriteLogError(format!("Error drawing the terminal: {}", err)))
+    }
+
+    /// Exports every event of log files as JSON Lines.
+    ///
+    /// This function expands the inputs into log files (`sources::resolve_inputs`), or reads
+    /// `qgames.log` when there are none, and writes one object per event (see `ExportedEvent`) to the
+    /// given file, or to the standard output. The files are read line by line, so only the match being
+    /// played is held in memory. Lines that cannot be parsed are skipped and reported on stderr.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A slice of strings with the files, directories and glob patterns to read.
+    ///
+    /// * `options` - A reference to the `ParseOptions` to parse the logs with.
+    ///
+    /// * `output` - The path of the file to write, or `None` to write to the standard output.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during the export.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if any input cannot be read, or if the output file cannot be
+    /// created or written.
+    pub fn export_events(
+        inputs: &[String],
+        options: &ParseOptions,
+        output: Option<&str>,
+    ) -> Result<(), LogError> {
+        let write_error = |err: io::Error| {
+            LogError::WriteLogError(format!(
+                "Error writing the events to {}: {}",
+                output.unwrap_or("the standard output"),
+                err
+            ))
+        };
+        let paths = if inputs.is_empty() {
+            vec![get_log_path()?]
+        } else {
+            sources::resolve_inputs(inputs)?
+        };
+
+        let mut out: Box<dyn Write> = match output {
+            Some(path) => Box::new(BufWriter::new(File::create(path).map_err(write_error)?)),
+            None => Box::new(BufWriter::new(io::stdout().lock())),
+        };
+        let mut matches = 0;
+        for path in &paths {
+            let mut exporter = EventExporter::new(*options, matches);
+            let file = path.display().to_string();
+            let summary =
+                export::export_events(open_log_file(path)?, &file, &mut exporter, &mut out)?;
+            LogView::render_skipped_lines(&file, &summary.skipped);
+            matches = exporter.matches();
+        }
+        out.flush().map_err(write_error)?;
+
+        Ok(())
+    }
+
+    /// Generates a synthetic log.
+    ///
+    /// This function writes the log described by `options` (see `generator::write_log`) to the given
+    /// file, or to the standard output.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - A reference to the `GeneratorOptions` describing the log.
+    ///
+    /// * `output` - The path of the file to write, or `None` for the standard output.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if the log
+    ///   cannot be written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the output file cannot be created or written.
+    pub fn generate_log(options: &GeneratorOptions, output: Option<&str>) -> Result<(), LogError> {
+        let write_error = |err: io::Error| {
+            LogError::WriteLogError(format!(
+                "Error writing the log to {}: {}",
+                output.unwrap_or("the standard output"),
+                err
+            ))
+        };
+
+        let mut out: Box<dyn Write> = match output {
+            Some(path) => Box::new(BufWriter::new(File::create(path).map_err(write_error)?)),
+            None => Box::new(BufWriter::new(io::stdout().lock())),
+        };
+        generator::write_log(options, &mut out).map_err(write_error)?;
+        out.flush().map_err(write_error)?;
+
+        Ok(())
+    }
+}
+
+/// Renders the report of several log files as JSON, legacy JSON or tables, as `output` says.
+fn render_logs_report(report: LogsReport, output: &OutputOptions) -> Result<(), LogError> {
+    match output.terminal_style() {
+        Some(style) => LogView::render_logs_report_tables(&report, &style),
+        None if output.format == OutputFormat::LegacyJson => LogView::render_logs_report(report)?,
+        None => LogView::render_json_report(&Report::from_logs_report(report))?,
+    }
+    Ok(())
+}
+
+/// Imports every log file of the inputs, returning the summary of each file.
+fn import_files(
+    storage: &mut Storage,
+    inputs: &[String],
+    options: &ParseOptions,
+) -> Result<Vec<(String, ImportSummary)>, LogError> {
+    if inputs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut summaries = Vec::new();
+    for path in sources::resolve_inputs(inputs)? {
+        let summary = storage.import_file(&path, options)?;
+        summaries.push((path.display().to_string(), summary));
+    }
+    Ok(summaries)
+}
+
+/// Records every line of the log files in `Metrics`.
+fn collect_metrics(paths: &[PathBuf], options: &ParseOptions) -> Result<Metrics, LogError> {
+    let mut metrics = Metrics::default();
+    for path in paths {
+        metrics.record_log(&read_log_file(path)?, *options);
+    }
+    Ok(metrics)
+}
+
+/// How often a followed log is checked for new lines.
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The matches of a log being followed, parsed up to its last complete line.
+struct CatchUp {
+    feed: LiveFeed,
+    metrics: Metrics,
+    /// The length of the lines parsed.
+    read: usize,
+}
+
+/// Parses the lines already written to a log to be followed, keeping every match and recording the
+/// lines in the metrics. Lines that cannot be parsed are only counted as errors.
+fn catch_up_log(path: &Path, options: &ParseOptions) -> Result<CatchUp, LogError> {
+    let content = read_log_file(path)?;
+    let read = written_len(&content);
+    let mut feed = LiveFeed::with_history(*options);
+    let mut metrics = Metrics::default();
+    for line in content[..read].lines() {
+        metrics.record(&feed.process_line(line));
+    }
+    Ok(CatchUp {
+        feed,
+        metrics,
+        read,
+    })
+}
+
+/// Pushes the events of the lines written to a log to the live clients, recording them in the
+/// metrics and serving the matches of the feed again whenever a match ends, without reading the log
+/// again. Lines that cannot be parsed are only counted as errors.
+fn follow_log(
+    path: &Path,
+    mut feed: LiveFeed,
+    mut tail: LogTail,
+    state: &RwLock<ApiState>,
+    events: &Arc<Broadcaster>,
+    metrics: &Mutex<Metrics>,
+    aliases: &AliasMap,
+) -> Result<(), LogError> {
+    loop {
+        thread::sleep(FOLLOW_INTERVAL);
+        let mut match_ended = false;
+        for line in tail.poll()?.lines() {
+            let result = feed.process_line(line);
+            if let Ok(mut metrics) = metrics.lock() {
+                metrics.record(&result);
+            }
+            let Ok(Some(event)) = result else {
+                continue;
+            };
+            match_ended |= matches!(
+                event.event,
+                LogEvent::ShutdownGame | LogEvent::InitGame { .. }
+            );
+            events.publish(&serde_json::to_string(&event)?);
+        }
+
+        if match_ended {
+            let reloaded = ApiState::new(served_matches(&feed, path), aliases);
+            if let Ok(mut state) = state.write() {
+                *state = reloaded;
+            }
+        }
+    }
+}
+
+/// Returns the matches of a followed log parsed so far, attributed to the log.
+fn served_matches(feed: &LiveFeed, path: &Path) -> Vec<Match> {
+    let mut matches = feed.matches();
+    let file = path.display().to_string();
+    for mat in matches.iter_mut() {
+        mat.data.source_file = file.clone();
+        mat.data.source_id = mat.id;
+    }
+    matches
+}
+
+/// Returns the length of the complete lines of a log, up to its last line break.
+fn written_len(content: &str) -> usize {
+    content.rfind('\n').map_or(0, |end| end + 1)
+}
+
+/// Draws the browser and handles the keys pressed until the user quits.
+fn run_browser(terminal: &mut DefaultTerminal, browser: &mut Browser) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| BrowserView::draw(frame, browser))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let action = match key.code {
+            KeyCode::Char('q') => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Up | KeyCode::Char('k') => BrowserAction::Up,
+            KeyCode::Down | KeyCode::Char('j') => BrowserAction::Down,
+            KeyCode::Enter | KeyCode::Char('l') => BrowserAction::Select,
+            KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('h') => BrowserAction::Back,
+            KeyCode::Char('s') => BrowserAction::Sort,
+            KeyCode::Right | KeyCode::Char('.') => BrowserAction::Forward,
+            KeyCode::Left | KeyCode::Char(',') => BrowserAction::Backward,
+            KeyCode::Home | KeyCode::Char('g') => BrowserAction::Start,
+            KeyCode::End | KeyCode::Char('G') => BrowserAction::End,
+            _ => continue,
+        };
+        browser.apply(action);
+    }
+}
@@ -1,3 +1,7 @@
+pub(crate) mod api_controller;
 pub(crate) mod log_controller;
+pub(crate) mod repl_controller;
 
-pub use log_controller::LogController;
+pub use api_controller::{handle_request, ApiResponse, ApiServer, ApiState, Broadcaster};
+pub use log_controller::{DiffFormat, LogController, OutputFormat, OutputOptions, ValidateFormat};
+pub use repl_controller::ReplController;
@@ -0,0 +1,186 @@
+use std::fs;
+
+use rustyline::{
+    completion::{Completer, FilenameCompleter, Pair},
+    error::ReadlineError,
+    highlight::Highlighter,
+    hint::Hinter,
+    history::DefaultHistory,
+    validate::Validator,
+    CompletionType, Config, Context, Editor, Helper,
+};
+
+use crate::quake_log_parse::{
+    model::{
+        error::LogError,
+        identity::{get_alias_path, AliasMap},
+        log_model::{get_log_path, ParseOptions},
+        repl::{get_history_path, Completion, Completions, ReplCommand, Workspace},
+    },
+    view::repl_view::ReplView,
+};
+
+/// The prompt of the REPL.
+const PROMPT: &str = "quake> ";
+
+/// Completes the lines of the REPL with the commands, and the player names and means of death of
+/// the matches loaded (see `Completions::complete`).
+struct ReplHelper {
+    completions: Completions,
+    files: FilenameCompleter,
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        match self.completions.complete(line, pos) {
+            Completion::Path => self.files.complete(line, pos, ctx),
+            Completion::Words(start, words) => Ok((
+                start,
+                words
+                    .into_iter()
+                    .map(|word| Pair {
+                        display: word.clone(),
+                        replacement: word,
+                    })
+                    .collect(),
+            )),
+        }
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+impl Highlighter for ReplHelper {}
+impl Validator for ReplHelper {}
+impl Helper for ReplHelper {}
+
+pub struct ReplController;
+
+impl ReplController {
+    /// Runs the interactive REPL until the user quits.
+    ///
+    /// This function loads `qgames.log` from the directory of the executable when there is one, then
+    /// reads commands (`ReplCommand::parse`) with line editing, history and tab completion, and
+    /// renders their answers with `ReplView`. Mistakes in commands and errors reading logs are
+    /// rendered without leaving the REPL. The history is kept in `get_history_path()` across runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - A reference to the `ParseOptions` to parse the loaded logs with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if the
+    ///   terminal cannot be read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the alias file or the terminal cannot be read.
+    pub fn run(options: &ParseOptions) -> Result<(), LogError> {
+        let aliases = match get_alias_path() {
+            Some(path) => AliasMap::load(&path)?,
+            None => AliasMap::default(),
+        };
+        let mut workspace = Workspace::new(aliases, *options);
+        if let Ok(path) = get_log_path() {
+            match workspace.load(&[path.display().to_string()]) {
+                Ok(()) => ReplView::render_loaded(&workspace),
+                Err(err) => ReplView::render_error(&err),
+            }
+        }
+        ReplView::render_welcome();
+
+        let config = Config::builder()
+            .completion_type(CompletionType::List)
+            .auto_add_history(true)
+            .build();
+        let mut editor: Editor<ReplHelper, DefaultHistory> =
+            Editor::with_config(config).map_err(terminal_error)?;
+        editor.set_helper(Some(ReplHelper {
+            completions: workspace.completions(),
+            files: FilenameCompleter::new(),
+        }));
+        let history = get_history_path();
+        if let Some(path) = &history {
+            // There is no history on the first run.
+            let _ = editor.load_history(path);
+        }
+
+        loop {
+            let line = match editor.readline(PROMPT) {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(err) => return Err(terminal_error(err)),
+            };
+            let command = match ReplCommand::parse(&line) {
+                Ok(Some(command)) => command,
+                Ok(None) => continue,
+                Err(err) => {
+                    ReplView::render_error(&err);
+                    continue;
+                }
+            };
+            if command == ReplCommand::Quit {
+                break;
+            }
+            let loads = matches!(command, ReplCommand::Load(_));
+            if let Err(err) = execute(&mut workspace, command) {
+                ReplView::render_error(&err);
+            }
+            if loads {
+                if let Some(helper) = editor.helper_mut() {
+                    helper.completions = workspace.completions();
+                }
+            }
+        }
+
+        if let Some(path) = &history {
+            editor.save_history(path).map_err(terminal_error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs a command of the REPL other than `quit` against the matches loaded.
+fn execute(workspace: &mut Workspace, command: ReplCommand) -> Result<(), LogError> {
+    match command {
+        ReplCommand::Load(inputs) => {
+            workspace.load(&inputs)?;
+            ReplView::render_loaded(workspace);
+        }
+        ReplCommand::Matches(name) => {
+            ReplView::render_matches(&workspace.matches_with(name.as_deref()))
+        }
+        ReplCommand::Match(id) => match workspace.find_match(id) {
+            Some(game) => ReplView::render_match(game)?,
+            None => return Err(LogError::CommandError(format!("No match with id {}", id))),
+        },
+        ReplCommand::Player(name) => match workspace.player(&name) {
+            Some(player) => ReplView::render_player(&player)?,
+            None => return Err(LogError::CommandError(format!("No player named {}", name))),
+        },
+        ReplCommand::Ranking(order) => ReplView::render_ranking(&workspace.ranking(order)),
+        ReplCommand::Export(format, path) => {
+            fs::write(&path, ReplView::export(format, workspace)?).map_err(|err| {
+                LogError::WriteLogError(format!("Error writing the export {}: {}", path, err))
+            })?;
+            ReplView::render_exported(&path);
+        }
+        ReplCommand::Help => ReplView::render_help(),
+        ReplCommand::Quit => {}
+    }
+    Ok(())
+}
+
+fn terminal_error(err: ReadlineError) -> LogError {
+    LogError::ReadLogError(format!("Error reading the terminal: {}", err))
+}
@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+
+use super::{
+    error::LogError,
+    event::LogEvent,
+    live::LiveFeed,
+    log_model::{process_events_matches_with_options, Match, ParseOptions, PlayerScore},
+    parallel::match_chunks,
+    player_name::strip_colors,
+};
+
+/// A kill of the timeline of a match, with the scoreboard right after it.
+#[derive(Debug)]
+pub struct TimelineKill {
+    /// The seconds since the match started.
+    pub at: u32,
+    pub killer: String,
+    pub victim: String,
+    pub means: String,
+    /// The scoreboard of the match after the kill, as pushed to live clients (see `LiveEvent`).
+    pub scoreboard: Vec<PlayerScore>,
+}
+
+/// A row of the scoreboard of a match at a point of its timeline.
+#[derive(Debug, PartialEq)]
+pub struct ScoreRow {
+    pub name: String,
+    pub kills: i32,
+    pub deaths: i32,
+    /// The seconds the player spent in the whole match.
+    pub playtime: u32,
+}
+
+/// The column the scoreboard is sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScoreSort {
+    #[default]
+    Kills,
+    Deaths,
+    Playtime,
+    Name,
+}
+
+/// The screens of the browser.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Screen {
+    /// The list of matches.
+    #[default]
+    Matches,
+    /// The scoreboard and the kill timeline of the selected match.
+    Scoreboard,
+    /// The kills and weapons of the selected player in the selected match.
+    Player,
+}
+
+/// What the user asked the browser to do, e.g. with a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrowserAction {
+    Up,
+    Down,
+    /// Opens the selected match or player.
+    Select,
+    /// Goes back to the previous screen.
+    Back,
+    /// Sorts the scoreboard by the next column.
+    Sort,
+    /// Moves the timeline one kill forward.
+    Forward,
+    /// Moves the timeline one kill backward.
+    Backward,
+    /// Moves the timeline to the start of the match, before the first kill.
+    Start,
+    /// Moves the timeline to the end of the match.
+    End,
+}
+
+/// The state of the terminal UI browsing the matches of a log (see `BrowserView`).
+///
+/// The timeline of a match is parsed from its lines when the match is opened.
+pub struct Browser {
+    content: String,
+    matches: Vec<Match>,
+    /// The range of the lines of each match in `content`.
+    chunks: Vec<(usize, usize)>,
+    options: ParseOptions,
+    pub screen: Screen,
+    /// The index of the selected match.
+    pub selected: usize,
+    /// The index of the selected row of the scoreboard.
+    pub player: usize,
+    pub sort: ScoreSort,
+    /// The number of kills of the timeline played so far.
+    pub position: usize,
+    timeline: Vec<TimelineKill>,
+}
+
+impl ScoreSort {
+    /// Returns the column sorted by after this one.
+    pub fn next(self) -> Self {
+        match self {
+            ScoreSort::Kills => ScoreSort::Deaths,
+            ScoreSort::Deaths => ScoreSort::Playtime,
+            ScoreSort::Playtime => ScoreSort::Name,
+            ScoreSort::Name => ScoreSort::Kills,
+        }
+    }
+}
+
+impl Browser {
+    /// Parses a log to browse its matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The content of the log file.
+    /// * `options` - The `ParseOptions` to parse with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Browser, LogError>` - A `Result` with the browser on the list of matches, or an error
+    ///   (`Err`) if the log cannot be parsed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` under the same conditions as `process_events_matches`.
+    pub fn new(content: String, options: ParseOptions) -> Result<Self, LogError> {
+        let mut matches = Vec::new();
+        process_events_matches_with_options(&mut matches, &content, &options)?;
+        let chunks = match_chunks(&content)
+            .into_iter()
+            .map(|(start, chunk)| (start, start + chunk.len()))
+            .collect();
+
+        Ok(Browser {
+            content,
+            matches,
+            chunks,
+            options,
+            screen: Screen::Matches,
+            selected: 0,
+            player: 0,
+            sort: ScoreSort::default(),
+            position: 0,
+            timeline: Vec::new(),
+        })
+    }
+
+    /// Returns every match of the log.
+    pub fn matches(&self) -> &[Match] {
+        &self.matches
+    }
+
+    /// Returns the selected match, if the log has any.
+    pub fn current_match(&self) -> Option<&Match> {
+        self.matches.get(self.selected)
+    }
+
+    /// Returns the kills of the match opened, in order.
+    pub fn timeline(&self) -> &[TimelineKill] {
+        &self.timeline
+    }
+
+    /// Handles an action of the user.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The `BrowserAction` to handle.
+    pub fn apply(&mut self, action: BrowserAction) {
+        let rows = match self.screen {
+            Screen::Matches => self.matches.len(),
+            Screen::Scoreboard | Screen::Player => self.scoreboard().len(),
+        };
+        let cursor = match self.screen {
+            Screen::Matches => &mut self.selected,
+            Screen::Scoreboard | Screen::Player => &mut self.player,
+        };
+
+        match action {
+            BrowserAction::Up => *cursor = cursor.saturating_sub(1),
+            BrowserAction::Down => *cursor = (*cursor + 1).min(rows.saturating_sub(1)),
+            BrowserAction::Select => match self.screen {
+                Screen::Matches if rows > 0 => self.open_match(),
+                Screen::Scoreboard if rows > 0 => self.screen = Screen::Player,
+                _ => {}
+            },
+            BrowserAction::Back => {
+                self.screen = match self.screen {
+                    Screen::Player => Screen::Scoreboard,
+                    Screen::Scoreboard | Screen::Matches => Screen::Matches,
+                }
+            }
+            BrowserAction::Sort => self.sort = self.sort.next(),
+            BrowserAction::Forward => {
+                self.position = (self.position + 1).min(self.timeline.len());
+            }
+            BrowserAction::Backward => self.position = self.position.saturating_sub(1),
+            BrowserAction::Start => self.position = 0,
+            BrowserAction::End => self.position = self.timeline.len(),
+        }
+    }
+
+    /// Returns the scoreboard of the match opened at the current position of its timeline, sorted by
+    /// `sort`.
+    ///
+    /// Players are listed from the start of the match, with no kills until they score.
+    pub fn scoreboard(&self) -> Vec<ScoreRow> {
+        let Some(current) = self.current_match() else {
+            return Vec::new();
+        };
+        let data = &current.data;
+
+        let mut rows: HashMap<&str, ScoreRow> = HashMap::new();
+        let names = data.sessions.iter().map(|session| session.name.as_str());
+        for name in names.chain(data.players.iter().map(String::as_str)) {
+            if !name.is_empty() {
+                rows.entry(name).or_insert_with(|| ScoreRow {
+                    name: name.to_string(),
+                    kills: 0,
+                    deaths: 0,
+                    playtime: 0,
+                });
+            }
+        }
+        for session in &data.sessions {
+            if let Some(row) = rows.get_mut(session.name.as_str()) {
+                row.playtime += session.duration(data.ended_at);
+            }
+        }
+
+        let played = &self.timeline[..self.position.min(self.timeline.len())];
+        if let Some(last) = played.last() {
+            for score in &last.scoreboard {
+                if let Some(row) = rows.get_mut(score.name.as_str()) {
+                    row.kills = score.kills;
+                }
+            }
+        }
+        for kill in played {
+            if let Some(row) = rows.get_mut(kill.victim.as_str()) {
+                row.deaths += 1;
+            }
+        }
+
+        let mut rows: Vec<ScoreRow> = rows.into_values().collect();
+        rows.sort_by(|a, b| match self.sort {
+            ScoreSort::Kills => b.kills.cmp(&a.kills).then(a.name.cmp(&b.name)),
+            ScoreSort::Deaths => b.deaths.cmp(&a.deaths).then(a.name.cmp(&b.name)),
+            ScoreSort::Playtime => b.playtime.cmp(&a.playtime).then(a.name.cmp(&b.name)),
+            ScoreSort::Name => a.name.cmp(&b.name),
+        });
+        rows
+    }
+
+    /// Returns the name of the selected player of the scoreboard.
+    pub fn current_player(&self) -> Option<String> {
+        self.scoreboard()
+            .into_iter()
+            .nth(self.player)
+            .map(|row| row.name)
+    }
+
+    /// Returns the kills of the timeline played so far where the selected player is the killer or the
+    /// victim.
+    pub fn player_kills(&self) -> Vec<&TimelineKill> {
+        let Some(name) = self.current_player() else {
+            return Vec::new();
+        };
+        self.timeline[..self.position.min(self.timeline.len())]
+            .iter()
+            .filter(|kill| kill.killer == name || kill.victim == name)
+            .collect()
+    }
+
+    /// Returns the kills and deaths of the selected player by means of death, over the timeline played
+    /// so far, sorted by kills then deaths in descending order.
+    pub fn player_weapons(&self) -> Vec<(String, i32, i32)> {
+        let Some(name) = self.current_player() else {
+            return Vec::new();
+        };
+        let mut weapons: HashMap<&str, (i32, i32)> = HashMap::new();
+        for kill in self.player_kills() {
+            let counts = weapons.entry(&kill.means).or_insert((0, 0));
+            if kill.killer == name {
+                counts.0 += 1;
+            }
+            if kill.victim == name {
+                counts.1 += 1;
+            }
+        }
+
+        let mut weapons: Vec<(String, i32, i32)> = weapons
+            .into_iter()
+            .map(|(means, (kills, deaths))| (means.to_string(), kills, deaths))
+            .collect();
+        weapons.sort_by(|a, b| (b.1, b.2, &a.0).cmp(&(a.1, a.2, &b.0)));
+        weapons
+    }
+
+    /// Opens the selected match, with its timeline at the end.
+    fn open_match(&mut self) {
+        let (start, end) = self.chunks.get(self.selected).copied().unwrap_or((0, 0));
+        self.timeline = match_timeline(&self.content[start..end], self.options);
+        self.position = self.timeline.len();
+        self.player = 0;
+        self.screen = Screen::Scoreboard;
+    }
+}
+
+/// Replays the lines of a match to build its kill timeline.
+///
+/// # Arguments
+///
+/// * `chunk` - The lines of the match, from its `InitGame:` line.
+/// * `options` - The `ParseOptions` to parse with.
+///
+/// # Returns
+///
+/// * `Vec<TimelineKill>` - The kills of the match, in order. Lines that cannot be parsed are skipped.
+pub fn match_timeline(chunk: &str, options: ParseOptions) -> Vec<TimelineKill> {
+    let mut feed = LiveFeed::new(options, 0);
+    let mut started_at = 0;
+    let mut timeline = Vec::new();
+
+    for line in chunk.lines() {
+        let Ok(Some(event)) = feed.process_line(line) else {
+            continue;
+        };
+        match event.event {
+            LogEvent::InitGame { .. } => started_at = event.timestamp.unwrap_or(0),
+            LogEvent::Kill(kill) => {
+                let name = |name: Option<&str>| strip_colors(name.unwrap_or_default()).into_owned();
+                timeline.push(TimelineKill {
+                    at: event
+                        .timestamp
+                        .unwrap_or(started_at)
+                        .saturating_sub(started_at),
+                    killer: name(kill.killer),
+                    victim: name(kill.victim),
+                    means: kill.means.unwrap_or_default().to_string(),
+                    scoreboard: event.scoreboard.unwrap_or_default(),
+                });
+            }
+            _ => {}
+        }
+    }
+    timeline
+}
@@ -0,0 +1,331 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{
+    error::LogError,
+    event::LogLine,
+    input::Compression,
+    log_model::{process_line, Match, MatchData, ParseOptions},
+    session,
+};
+
+/// The number of bytes at the start of a log whose hash identifies it across runs.
+const HEAD_BYTES: u64 = 4096;
+
+/// How far a log was processed, saved after each run so the next one only processes new matches.
+///
+/// The checkpoint points after the last complete line read and keeps the match still being played
+/// then, if any, so the next run resumes it from that line.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// The log file the checkpoint belongs to.
+    pub file: String,
+    /// The inode of the file on Unix, which changes when the log is rotated by renaming it.
+    pub inode: Option<u64>,
+    /// The SHA-256 hash of the first `head_len` bytes of the file, which identify it.
+    pub head_hash: String,
+    pub head_len: u64,
+    /// The byte offset to resume from: the end of the last complete line read.
+    pub offset: u64,
+    /// The number of complete matches processed so far, used to number the next ones.
+    pub matches: i32,
+    /// The match being played when the log was read, if any.
+    #[serde(default)]
+    pub open_match: Option<OpenMatch>,
+}
+
+/// A match still being played when a run read the log, as parsed so far.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenMatch {
+    pub data: MatchData,
+    /// The state of each session of `data` that is not part of a parsed match, in the same order.
+    clients: Vec<ClientState>,
+}
+
+/// The state of a session kept while its match is being parsed (see `PlayerSession`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ClientState {
+    userinfo: HashMap<String, String>,
+    death_streak: u32,
+    last_death_at: Option<u32>,
+    self_kills: i32,
+}
+
+/// Where a run resumed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resume {
+    /// There was no checkpoint, so the log was processed from the start.
+    Start,
+    /// The log was processed from the checkpoint.
+    Checkpoint,
+    /// The log was replaced since the checkpoint (its inode or first bytes changed) and was processed
+    /// from the start.
+    Rotated,
+    /// The log is shorter than the checkpoint offset, so it was truncated and was processed from the
+    /// start.
+    Truncated,
+}
+
+/// The result of processing a log from a checkpoint.
+pub struct IncrementalRun {
+    /// The matches completed since the checkpoint.
+    pub matches: Vec<Match>,
+    /// The checkpoint to save for the next run.
+    pub checkpoint: Checkpoint,
+    pub resume: Resume,
+}
+
+impl Checkpoint {
+    /// Loads a checkpoint saved by a previous run.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the checkpoint file.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Checkpoint>, LogError>` - A `Result` with the checkpoint, `None` if the file does
+    ///   not exist yet, or an error (`Err`) if it cannot be read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the file exists but cannot be read or is not a checkpoint.
+    pub fn load(path: &Path) -> Result<Option<Self>, LogError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(LogError::ReadLogError(format!(
+                    "Error reading the checkpoint file {}: {}",
+                    path.display(),
+                    err
+                )))
+            }
+        };
+        serde_json::from_str(&content).map(Some).map_err(|err| {
+            LogError::ReadLogError(format!(
+                "Invalid checkpoint file {}: {}",
+                path.display(),
+                err
+            ))
+        })
+    }
+
+    /// Saves the checkpoint, replacing the file atomically so an interrupted run never leaves a
+    /// partial checkpoint behind.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the checkpoint file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), LogError> {
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, serde_json::to_string_pretty(self)?)
+            .and_then(|()| fs::rename(&temporary, path))
+            .map_err(|err| {
+                LogError::WriteLogError(format!(
+                    "Error writing the checkpoint file {}: {}",
+                    path.display(),
+                    err
+                ))
+            })
+    }
+}
+
+/// Processes the matches completed in a log since a checkpoint.
+///
+/// The log is parsed from the checkpoint offset, resuming the match the checkpoint kept open, as
+/// `process_events_matches` would, and every match followed by a `ShutdownGame:` line or by the next
+/// match is returned, numbered after the matches of the previous runs. The match still being played,
+/// if any, is kept in the new checkpoint for the next run; an unterminated last line is left for it.
+///
+/// The log is processed from the start instead when it was rotated (its inode or its first bytes
+/// changed) or truncated (it is shorter than the checkpoint offset).
+///
+/// # Arguments
+///
+/// * `path` - The path of the log file, which cannot be compressed.
+/// * `checkpoint` - The checkpoint of the previous run, if any.
+/// * `options` - A reference to the `ParseOptions` to parse with.
+///
+/// # Returns
+///
+/// * `Result<IncrementalRun, LogError>` - A `Result` with the new matches and the checkpoint to save,
+///   or an error (`Err`) if the log cannot be read or parsed.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if any of the following conditions are met:
+///
+/// * The log cannot be read, is compressed, or is not valid UTF-8.
+/// * A line read cannot be parsed.
+pub fn process_incremental(
+    path: &Path,
+    checkpoint: Option<&Checkpoint>,
+    options: &ParseOptions,
+) -> Result<IncrementalRun, LogError> {
+    let read_error = |err: io::Error| {
+        LogError::ReadLogError(format!(
+            "Error reading the log file {}: {}",
+            path.display(),
+            err
+        ))
+    };
+    let mut file = File::open(path).map_err(read_error)?;
+    let metadata = file.metadata().map_err(read_error)?;
+    let inode = inode(&metadata);
+
+    let mut head = Vec::new();
+    (&mut file)
+        .take(HEAD_BYTES)
+        .read_to_end(&mut head)
+        .map_err(read_error)?;
+    if Compression::detect(&head) != Compression::None {
+        return Err(LogError::ReadLogError(format!(
+            "Checkpoints need an uncompressed log file: {}",
+            path.display()
+        )));
+    }
+
+    let resume = match checkpoint {
+        None => Resume::Start,
+        Some(checkpoint) if metadata.len() < checkpoint.offset => Resume::Truncated,
+        Some(checkpoint)
+            if checkpoint.inode != inode
+                || head.len() < checkpoint.head_len as usize
+                || hash(&head[..checkpoint.head_len as usize]) != checkpoint.head_hash =>
+        {
+            Resume::Rotated
+        }
+        Some(_) => Resume::Checkpoint,
+    };
+    let (start, previous_matches) = match (resume, checkpoint) {
+        (Resume::Checkpoint, Some(checkpoint)) => (checkpoint.offset, checkpoint.matches),
+        // Matches keep being numbered after the previous ones, even when the log was replaced.
+        (_, checkpoint) => (0, checkpoint.map_or(0, |checkpoint| checkpoint.matches)),
+    };
+
+    let mut bytes = Vec::new();
+    file.seek(SeekFrom::Start(start)).map_err(read_error)?;
+    file.read_to_end(&mut bytes).map_err(read_error)?;
+    // A line still being written is only read once it is complete.
+    let complete_lines = bytes
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |end| end + 1);
+    bytes.truncate(complete_lines);
+    let content = String::from_utf8(bytes).map_err(|err| {
+        LogError::ReadLogError(format!(
+            "The log file {} is not valid UTF-8: {}",
+            path.display(),
+            err
+        ))
+    })?;
+
+    // The match the checkpoint kept open is numbered 1, as the first match a full parse would give.
+    let resumed = checkpoint
+        .filter(|_| resume == Resume::Checkpoint)
+        .and_then(|checkpoint| checkpoint.open_match.clone());
+    let mut matches: Vec<Match> = resumed
+        .into_iter()
+        .map(|open_match| Match {
+            id: 1,
+            data: open_match.resume(),
+        })
+        .collect();
+    for line in content.lines() {
+        process_line(&mut matches, LogLine::parse(line), options).map_err(|err| {
+            LogError::ReadLogError(format!(
+                "Error {:?} in the log file {}",
+                err,
+                path.display()
+            ))
+        })?;
+    }
+    let open_match = match matches.last() {
+        Some(last) if !last.data.completed => matches.pop().map(|open| OpenMatch::new(open.data)),
+        _ => None,
+    };
+    if let Some(last) = matches.last_mut() {
+        session::close_sessions(&mut last.data, options.name_policy);
+    }
+
+    let file_name = path.display().to_string();
+    for mat in matches.iter_mut() {
+        mat.id += previous_matches;
+        mat.data.source_file = file_name.clone();
+        mat.data.source_id = mat.id;
+    }
+
+    let offset = start + content.len() as u64;
+    let head_len = (head.len() as u64).min(offset);
+    let checkpoint = Checkpoint {
+        file: file_name,
+        inode,
+        head_hash: hash(&head[..head_len as usize]),
+        head_len,
+        offset,
+        matches: previous_matches + matches.len() as i32,
+        open_match,
+    };
+    Ok(IncrementalRun {
+        matches,
+        checkpoint,
+        resume,
+    })
+}
+
+impl OpenMatch {
+    /// Keeps a match being played, with the state of its sessions.
+    fn new(data: MatchData) -> Self {
+        let clients = data
+            .sessions
+            .iter()
+            .map(|session| ClientState {
+                userinfo: session.userinfo.clone(),
+                death_streak: session.death_streak,
+                last_death_at: session.last_death_at,
+                self_kills: session.self_kills,
+            })
+            .collect();
+        OpenMatch { data, clients }
+    }
+
+    /// Returns the match to resume parsing, with the state of its sessions restored.
+    fn resume(self) -> MatchData {
+        let mut data = self.data;
+        for (session, client) in data.sessions.iter_mut().zip(self.clients) {
+            session.userinfo = client.userinfo;
+            session.death_streak = client.death_streak;
+            session.last_death_at = client.last_death_at;
+            session.self_kills = client.self_kills;
+        }
+        data
+    }
+}
+
+fn hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[cfg(unix)]
+pub(crate) fn inode(metadata: &fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn inode(_metadata: &fs::Metadata) -> Option<u64> {
+    None
+}
@@ -0,0 +1,271 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::BufRead,
+    path::Path,
+};
+
+use serde::Serialize;
+
+use super::{
+    error::LogError,
+    identity::{process_identity_ranking, AliasMap},
+    input::open_log_file,
+    log_model::{Match, ParseOptions, PlayerScore},
+    report::{merge_reports, Report},
+    sources,
+};
+
+/// The differences between two reports: the matches only in one of them, the matches whose data
+/// changed, and the players whose place in the ranking changed.
+///
+/// Matches are paired by id, so two reports of the same log, e.g. before and after a change to the
+/// parser, are compared match by match.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct ReportDiff {
+    /// The matches only in the second report.
+    pub added_matches: Vec<MatchSummary>,
+    /// The matches only in the first report.
+    pub removed_matches: Vec<MatchSummary>,
+    pub changed_matches: Vec<MatchDiff>,
+    /// The players whose rank or kills changed, in the order of the second ranking, followed by the
+    /// players only in the first one.
+    pub ranking: Vec<RankingChange>,
+}
+
+/// A match only in one of the reports.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct MatchSummary {
+    pub id: i32,
+    pub map: String,
+    pub total_kills: i32,
+}
+
+/// The changes to a match present in both reports.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct MatchDiff {
+    pub id: i32,
+    pub map: String,
+    /// The total kills of the match, if they changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_kills: Option<Change>,
+    pub players_added: Vec<String>,
+    pub players_removed: Vec<String>,
+    /// The kills of each player that changed, by name; a player without kills counts as zero.
+    pub kills: Vec<CountChange>,
+    /// The kills of each means of death that changed, by name.
+    pub death_causes: Vec<CountChange>,
+}
+
+/// A value before and after.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Change {
+    pub before: i32,
+    pub after: i32,
+}
+
+/// A count before and after, named after what is counted, e.g. a player.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CountChange {
+    pub name: String,
+    pub before: i32,
+    pub after: i32,
+}
+
+/// A player whose place in the ranking changed.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RankingChange {
+    pub name: String,
+    /// The place of the player in the first ranking, `None` if they were not ranked.
+    pub before: Option<Standing>,
+    /// The place of the player in the second ranking, `None` if they are not ranked.
+    pub after: Option<Standing>,
+}
+
+/// The place of a player in a ranking.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Standing {
+    pub rank: usize,
+    pub kills: i32,
+}
+
+impl ReportDiff {
+    /// Returns `true` if the reports are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added_matches.is_empty()
+            && self.removed_matches.is_empty()
+            && self.changed_matches.is_empty()
+            && self.ranking.is_empty()
+    }
+}
+
+/// Loads the matches of a report exported with `--format=json`, or of a log file.
+///
+/// Files starting with `{` are read as reports, anything else as a log, which may be compressed.
+///
+/// # Arguments
+///
+/// * `path` - The path of the report or log file.
+/// * `options` - A reference to the `ParseOptions` to parse a log with.
+///
+/// # Returns
+///
+/// * `Result<Vec<Match>, LogError>` - A `Result` with the matches, or an error (`Err`) if the file
+///   cannot be read or parsed.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if the file cannot be read, is a report of another version
+/// (see `Report::load`), or is a log that cannot be parsed.
+pub fn load_matches(path: &Path, options: &ParseOptions) -> Result<Vec<Match>, LogError> {
+    let mut reader = open_log_file(path)?;
+    let report = reader
+        .fill_buf()
+        .map_err(|err| {
+            LogError::ReadLogError(format!("Error reading {}: {}", path.display(), err))
+        })?
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        == Some(&b'{');
+
+    if report {
+        let report = Report::load(path)?;
+        Ok(merge_reports(vec![(path.display().to_string(), report)]).matches)
+    } else {
+        sources::process_files(&[path.to_path_buf()], options, false)
+    }
+}
+
+/// Compares the matches of two reports, match by match and player by player.
+///
+/// # Arguments
+///
+/// * `before` - A slice of the `Match` structs of the first report.
+/// * `after` - A slice of the `Match` structs of the second report.
+/// * `aliases` - A reference to the `AliasMap` to rank the players with.
+///
+/// # Returns
+///
+/// * `ReportDiff` - The differences, empty if the reports are the same.
+pub fn diff_matches(before: &[Match], after: &[Match], aliases: &AliasMap) -> ReportDiff {
+    let mut diff = ReportDiff::default();
+    let old: BTreeMap<i32, &Match> = before.iter().map(|game| (game.id, game)).collect();
+    let new: BTreeMap<i32, &Match> = after.iter().map(|game| (game.id, game)).collect();
+
+    for (id, game) in &old {
+        if !new.contains_key(id) {
+            diff.removed_matches.push(summary(game));
+        }
+    }
+    for (id, game) in &new {
+        match old.get(id) {
+            None => diff.added_matches.push(summary(game)),
+            Some(previous) => {
+                if let Some(changes) = diff_match(previous, game) {
+                    diff.changed_matches.push(changes);
+                }
+            }
+        }
+    }
+
+    diff.ranking = diff_rankings(&ranking(before, aliases), &ranking(after, aliases));
+    diff
+}
+
+/// Compares the data of a match in both reports, returning `None` if it did not change.
+fn diff_match(before: &Match, after: &Match) -> Option<MatchDiff> {
+    let (old, new) = (&before.data, &after.data);
+    let changes = MatchDiff {
+        id: after.id,
+        map: map(after),
+        total_kills: (old.total_kills != new.total_kills).then_some(Change {
+            before: old.total_kills,
+            after: new.total_kills,
+        }),
+        players_added: new.players.difference(&old.players).cloned().collect(),
+        players_removed: old.players.difference(&new.players).cloned().collect(),
+        kills: diff_counts(&old.kills, &new.kills),
+        death_causes: diff_counts(&old.kills_by_means, &new.kills_by_means),
+    };
+
+    let unchanged = changes.total_kills.is_none()
+        && changes.players_added.is_empty()
+        && changes.players_removed.is_empty()
+        && changes.kills.is_empty()
+        && changes.death_causes.is_empty();
+    (!unchanged).then_some(changes)
+}
+
+/// Lists the counts that changed between two maps, by name, counting the missing ones as zero.
+fn diff_counts(before: &BTreeMap<String, i32>, after: &BTreeMap<String, i32>) -> Vec<CountChange> {
+    let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let old = before.get(name).copied().unwrap_or(0);
+            let new = after.get(name).copied().unwrap_or(0);
+            (old != new).then(|| CountChange {
+                name: name.clone(),
+                before: old,
+                after: new,
+            })
+        })
+        .collect()
+}
+
+/// Lists the players whose place changed between two rankings.
+fn diff_rankings(before: &[PlayerScore], after: &[PlayerScore]) -> Vec<RankingChange> {
+    let standing = |ranking: &[PlayerScore], name: &str| {
+        ranking
+            .iter()
+            .position(|player| player.name == name)
+            .map(|idx| Standing {
+                rank: idx + 1,
+                kills: ranking[idx].kills,
+            })
+    };
+
+    let mut changes: Vec<RankingChange> = after
+        .iter()
+        .map(|player| RankingChange {
+            name: player.name.clone(),
+            before: standing(before, &player.name),
+            after: standing(after, &player.name),
+        })
+        .filter(|change| change.before != change.after)
+        .collect();
+    changes.extend(
+        before
+            .iter()
+            .filter(|player| standing(after, &player.name).is_none())
+            .map(|player| RankingChange {
+                name: player.name.clone(),
+                before: standing(before, &player.name),
+                after: None,
+            }),
+    );
+    changes
+}
+
+/// Ranks the players of matches by identity, as the reports do.
+fn ranking(matches: &[Match], aliases: &AliasMap) -> Vec<PlayerScore> {
+    let mut ranking = Vec::new();
+    process_identity_ranking(matches, aliases, &mut ranking);
+    ranking
+}
+
+fn summary(game: &Match) -> MatchSummary {
+    MatchSummary {
+        id: game.id,
+        map: map(game),
+        total_kills: game.data.total_kills,
+    }
+}
+
+/// Returns the map of a match, or an empty string if its `InitGame:` line has none.
+fn map(game: &Match) -> String {
+    game.data
+        .settings
+        .get("mapname")
+        .cloned()
+        .unwrap_or_default()
+}
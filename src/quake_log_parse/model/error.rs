@@ -9,10 +9,50 @@ pub enum LogError {
     VictimName(String),
     EmptyPlayerName(String),
     InsertKillMeanError(String),
+    ScoreError(String),
+    SessionError(String),
+    AliasFileError(String),
+    WriteLogError(String),
+    StorageError(String),
+    ServerError(String),
+    FilterError(String),
+    QueryError(String),
     JsonError(String),
+    CommandError(String),
+}
+impl LogError {
+    /// Returns the name of the kind of error, e.g. `KillError`, as used to label metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LogError::ReadLogError(_) => "ReadLogError",
+            LogError::ExePathError(_) => "ExePathError",
+            LogError::InitGameError(_) => "InitGameError",
+            LogError::KillError(_) => "KillError",
+            LogError::ClientUserinfoChangedError(_) => "ClientUserinfoChangedError",
+            LogError::KillerName(_) => "KillerName",
+            LogError::VictimName(_) => "VictimName",
+            LogError::EmptyPlayerName(_) => "EmptyPlayerName",
+            LogError::InsertKillMeanError(_) => "InsertKillMeanError",
+            LogError::ScoreError(_) => "ScoreError",
+            LogError::SessionError(_) => "SessionError",
+            LogError::AliasFileError(_) => "AliasFileError",
+            LogError::WriteLogError(_) => "WriteLogError",
+            LogError::StorageError(_) => "StorageError",
+            LogError::ServerError(_) => "ServerError",
+            LogError::FilterError(_) => "FilterError",
+            LogError::QueryError(_) => "QueryError",
+            LogError::JsonError(_) => "JsonError",
+            LogError::CommandError(_) => "CommandError",
+        }
+    }
 }
 impl From<serde_json::Error> for LogError {
     fn from(err: serde_json::Error) -> Self {
         LogError::JsonError(err.to_string())
     }
 }
+impl From<rusqlite::Error> for LogError {
+    fn from(err: rusqlite::Error) -> Self {
+        LogError::StorageError(err.to_string())
+    }
+}
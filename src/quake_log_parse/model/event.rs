@@ -0,0 +1,207 @@
+use serde::Serialize;
+
+use super::log_model::parse_timestamp;
+
+/// A line of a Quake 3 log, parsed without copying: every field borrows from the log content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogLine<'a> {
+    /// The whole line, as read from the log.
+    pub text: &'a str,
+    /// The time of the line in seconds (see `parse_timestamp`), if it starts with one.
+    pub timestamp: Option<u32>,
+    pub event: LogEvent<'a>,
+}
+
+/// The event logged by a line.
+///
+/// Fields that cannot be parsed from a malformed line are `None`; the parser reports those lines as
+/// errors when it processes the event.
+///
+/// Events serialise to JSON objects tagged with their `type`, e.g. `{"type": "client_begin",
+/// "client_id": 2}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LogEvent<'a> {
+    /// `InitGame:` - a new match starts, with the server settings, e.g. `\sv_hostname\Code Miner...`.
+    InitGame { settings: &'a str },
+    /// `ClientUserinfoChanged:` - a client sets its name and user info, e.g. `2 n\Isgalamido\t\0\...`.
+    ClientUserinfoChanged {
+        client_id: Option<i32>,
+        name: Option<&'a str>,
+    },
+    /// `Kill:` - a player is killed by another player or by the world.
+    Kill(KillEvent<'a>),
+    /// `ClientConnect:` - a client connects to the server.
+    ClientConnect { client_id: Option<i32> },
+    /// `ClientBegin:` - a connected client enters the game.
+    ClientBegin { client_id: Option<i32> },
+    /// `ClientDisconnect:` - a client leaves the server.
+    ClientDisconnect { client_id: Option<i32> },
+    /// `ShutdownGame:` - the match is over.
+    ShutdownGame,
+    /// `score:` - the final score of a client, logged when the match ends.
+    Score,
+    /// `Item:` - a client picks up an item, e.g. `2 weapon_rocketlauncher`.
+    Item {
+        client_id: Option<i32>,
+        item: Option<&'a str>,
+    },
+    /// `say:` or `sayteam:` - a chat message, e.g. `say: Isgalamido: gg`.
+    Say {
+        /// Whether the message was only sent to the team of the player (`sayteam:`).
+        team: bool,
+        name: Option<&'a str>,
+        message: &'a str,
+    },
+    /// Any other line, which the parser ignores.
+    Other,
+}
+
+/// A `Kill:` line, e.g. `20:54 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct KillEvent<'a> {
+    /// The client id of the killer, `1022` for the world.
+    pub killer_id: Option<i32>,
+    pub victim_id: Option<i32>,
+    /// The name of the killer as written in the line, `<world>` for the world.
+    pub killer: Option<&'a str>,
+    pub victim: Option<&'a str>,
+    /// The last word of the line, normally the means of death (e.g. `MOD_RAILGUN`).
+    pub means: Option<&'a str>,
+}
+
+impl<'a> LogLine<'a> {
+    /// Parses a line of the log.
+    ///
+    /// Lines are recognised by the first tag they contain, in the order of the `LogEvent` variants, so
+    /// e.g. a player named `Kill:` does not turn its `ClientUserinfoChanged` line into a kill. Chat
+    /// lines are recognised first, by the tag that follows the timestamp, so a message cannot be
+    /// mistaken for another event.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string containing the log line.
+    ///
+    /// # Returns
+    ///
+    /// * `LogLine` - The parsed line, borrowing from `text`.
+    pub fn parse(text: &'a str) -> Self {
+        let tag = text.split_whitespace().nth(1);
+        let event = match text {
+            s if matches!(tag, Some("say:" | "sayteam:")) => parse_say(s, tag == Some("sayteam:")),
+            s if s.contains("InitGame:") => LogEvent::InitGame {
+                settings: s
+                    .split_once("InitGame:")
+                    .map_or("", |(_, settings)| settings.trim()),
+            },
+            s if s.contains("ClientUserinfoChanged") => LogEvent::ClientUserinfoChanged {
+                client_id: parse_client_id(s, "ClientUserinfoChanged:"),
+                name: parse_userinfo_name(s),
+            },
+            s if s.contains("Kill:") => LogEvent::Kill(KillEvent::parse(s)),
+            s if s.contains("ClientConnect:") => LogEvent::ClientConnect {
+                client_id: parse_client_id(s, "ClientConnect:"),
+            },
+            s if s.contains("ClientBegin:") => LogEvent::ClientBegin {
+                client_id: parse_client_id(s, "ClientBegin:"),
+            },
+            s if s.contains("ClientDisconnect:") => LogEvent::ClientDisconnect {
+                client_id: parse_client_id(s, "ClientDisconnect:"),
+            },
+            s if s.contains("ShutdownGame:") => LogEvent::ShutdownGame,
+            _ if tag == Some("score:") => LogEvent::Score,
+            s if tag == Some("Item:") => {
+                let mut fields = s
+                    .split_once("Item:")
+                    .map_or("", |(_, rest)| rest)
+                    .split_whitespace();
+                LogEvent::Item {
+                    client_id: fields.next().and_then(|id| id.parse().ok()),
+                    item: fields.next(),
+                }
+            }
+            _ => LogEvent::Other,
+        };
+
+        LogLine {
+            text,
+            timestamp: parse_timestamp(text),
+            event,
+        }
+    }
+}
+
+impl<'a> KillEvent<'a> {
+    /// Parses a `Kill:` line.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string containing the kill line.
+    ///
+    /// # Returns
+    ///
+    /// * `KillEvent` - The parsed kill, borrowing from `line`, with `None` for any missing part.
+    pub fn parse(line: &'a str) -> Self {
+        let Some((_, rest)) = line.split_once("Kill:") else {
+            return KillEvent::default();
+        };
+        let mut ids = rest.split_whitespace();
+        let killer_id = ids.next().and_then(|id| id.parse().ok());
+        let victim_id = ids.next().and_then(|id| id.parse().ok());
+
+        // The description follows the ids, e.g. `Isgalamido killed Zeh by MOD_RAILGUN`.
+        let description = rest.split_once(": ").map(|(_, description)| description);
+        let (killer, victim) = match description.and_then(|d| d.split_once(" killed ")) {
+            Some((killer, rest)) => (Some(killer), rest.rsplit_once(" by ").map(|(v, _)| v)),
+            None => (None, None),
+        };
+
+        KillEvent {
+            killer_id,
+            victim_id,
+            killer,
+            victim,
+            means: line.split_whitespace().last(),
+        }
+    }
+}
+
+/// Extracts the client id that follows `tag` in a line.
+pub fn parse_client_id(line: &str, tag: &str) -> Option<i32> {
+    line.split_once(tag)
+        .and_then(|(_, rest)| rest.split_whitespace().next())
+        .and_then(|id| id.parse().ok())
+}
+
+/// Extracts the raw player name of a `ClientUserinfoChanged` line, between `n\` and `\t\`.
+pub fn parse_userinfo_name(line: &str) -> Option<&str> {
+    let start = line.find("n\\")?;
+    let end = line.find("\\t\\")?;
+    line.get(start + 2..end)
+}
+
+/// Parses a `say:` or `sayteam:` line, e.g. `  2:13 say: Isgalamido: gg`.
+fn parse_say(line: &str, team: bool) -> LogEvent<'_> {
+    let tag = if team { "sayteam:" } else { "say:" };
+    let rest = line
+        .split_once(tag)
+        .map_or("", |(_, rest)| rest.trim_start());
+    match rest.split_once(": ") {
+        Some((name, message)) => LogEvent::Say {
+            team,
+            name: Some(name),
+            message,
+        },
+        None => LogEvent::Say {
+            team,
+            name: None,
+            message: rest,
+        },
+    }
+}
+
+/// Iterates over the backslash separated key/value pairs of the settings of an `InitGame:` line.
+pub fn parse_settings(settings: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut fields = settings.strip_prefix('\\').unwrap_or(settings).split('\\');
+    std::iter::from_fn(move || Some((fields.next()?, fields.next()?)))
+}
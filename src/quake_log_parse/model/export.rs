@@ -0,0 +1,185 @@
+use std::io::{BufRead, Write};
+
+use serde::Serialize;
+
+use super::{
+    error::LogError,
+    event::LogEvent,
+    live::{session_name, LiveFeed},
+    log_model::ParseOptions,
+};
+
+/// An event of a log, as exported on a line of its own (JSON Lines).
+///
+/// The fields of the typed `LogEvent` are flattened into the object, next to where it was read and
+/// the names of the players it concerns, resolved from their client ids, e.g. `{"file": "games.log",
+/// "line": 12, "match": 1, "timestamp": 70, "type": "kill", "killer_id": 2, ..., "killer_name":
+/// "Zeh", "victim_name": "Mal"}`.
+#[derive(Debug, Serialize)]
+pub struct ExportedEvent<'a> {
+    pub file: &'a str,
+    /// The number of the line in the file, from 1.
+    pub line: usize,
+    #[serde(rename = "match")]
+    pub match_id: i32,
+    pub timestamp: Option<u32>,
+    #[serde(flatten)]
+    pub event: LogEvent<'a>,
+    /// The name of the client of a connect, user info, begin, disconnect, item or score event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player: Option<String>,
+    /// The name of the killer of a kill event, unless killed by the world.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub killer_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub victim_name: Option<String>,
+}
+
+/// Turns the lines of a log into `ExportedEvent`s, keeping only the match being played, so a log of
+/// any size is exported in constant memory.
+///
+/// The lines are parsed by a `LiveFeed`, so the events and the names of the players are the ones
+/// pushed to live clients.
+pub struct EventExporter {
+    feed: LiveFeed,
+}
+
+/// The events written by `export_events`.
+#[derive(Debug, PartialEq)]
+pub struct ExportSummary {
+    /// The number of events written.
+    pub events: usize,
+    /// The lines that could not be parsed, with their number in the file, skipped without an event.
+    pub skipped: Vec<(usize, LogError)>,
+}
+
+impl EventExporter {
+    /// Creates an exporter for a log that follows `previous_matches` matches, e.g. of earlier files,
+    /// so the matches are numbered as in the report of all the files.
+    pub fn new(options: ParseOptions, previous_matches: i32) -> Self {
+        EventExporter {
+            feed: LiveFeed::new(options, previous_matches),
+        }
+    }
+
+    /// Returns the number of matches started so far, including those before the exporter was created.
+    pub fn matches(&self) -> i32 {
+        self.feed.started()
+    }
+
+    /// Processes a line of the log, returning its event, if any.
+    ///
+    /// Lines before the first `InitGame:` and lines of no interest (`LogEvent::Other`) have no event.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The name of the log file, as written in the event.
+    /// * `number` - The number of the line in the file, from 1.
+    /// * `line` - A string containing the log line.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<ExportedEvent>, LogError>` - A `Result` with the event of the line, or an
+    ///   error (`Err`) if the line cannot be parsed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` under the same conditions as `process_events_matches`.
+    pub fn process_line<'a>(
+        &mut self,
+        file: &'a str,
+        number: usize,
+        line: &'a str,
+    ) -> Result<Option<ExportedEvent<'a>>, LogError> {
+        let Some((parsed, current)) = self.feed.advance(line)? else {
+            return Ok(None);
+        };
+        let mut event = ExportedEvent {
+            file,
+            line: number,
+            match_id: current.id,
+            timestamp: parsed.timestamp,
+            event: parsed.event,
+            player: None,
+            killer_name: None,
+            victim_name: None,
+        };
+        match parsed.event {
+            LogEvent::ClientConnect { client_id }
+            | LogEvent::ClientBegin { client_id }
+            | LogEvent::ClientDisconnect { client_id }
+            | LogEvent::ClientUserinfoChanged { client_id, .. }
+            | LogEvent::Item { client_id, .. } => {
+                event.player = session_name(&current.data, client_id);
+            }
+            LogEvent::Kill(kill) => {
+                event.killer_name = session_name(&current.data, kill.killer_id);
+                event.victim_name = session_name(&current.data, kill.victim_id);
+            }
+            LogEvent::Score => {
+                event.player = current.data.scores.last().map(|score| score.name.clone());
+            }
+            _ => {}
+        }
+        Ok(Some(event))
+    }
+}
+
+/// Writes every event of a log as JSON Lines, one object per line, reading the log line by line.
+///
+/// A line that cannot be parsed is skipped, as `LiveFeed::replay` does, so one damaged line does not
+/// leave the export half written; the lines skipped are returned with their number.
+///
+/// # Arguments
+///
+/// * `reader` - The log to read, e.g. from `input::open_log_file`.
+/// * `file` - The name of the log file, as written in the events and the errors.
+/// * `exporter` - The `EventExporter` numbering the matches.
+/// * `out` - The writer to write the events to.
+///
+/// # Returns
+///
+/// * `Result<ExportSummary, LogError>` - A `Result` with the number of events written and the lines
+///   skipped, or an error (`Err`) if the log cannot be read or the events cannot be written.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if the log cannot be read or the events cannot be written.
+pub fn export_events(
+    mut reader: impl BufRead,
+    file: &str,
+    exporter: &mut EventExporter,
+    out: &mut impl Write,
+) -> Result<ExportSummary, LogError> {
+    let mut buffer = Vec::new();
+    let mut number = 0;
+    let mut summary = ExportSummary {
+        events: 0,
+        skipped: Vec::new(),
+    };
+
+    loop {
+        buffer.clear();
+        let read = reader.read_until(b'\n', &mut buffer).map_err(|err| {
+            LogError::ReadLogError(format!("Error reading the log file {}: {}", file, err))
+        })?;
+        if read == 0 {
+            return Ok(summary);
+        }
+        number += 1;
+
+        let line = String::from_utf8_lossy(&buffer);
+        let line = line.trim_end_matches(['\n', '\r']);
+        match exporter.process_line(file, number, line) {
+            Ok(Some(event)) => {
+                serde_json::to_writer(&mut *out, &event)?;
+                out.write_all(b"\n").map_err(|err| {
+                    LogError::WriteLogError(format!("Error writing the events: {}", err))
+                })?;
+                summary.events += 1;
+            }
+            Ok(None) => {}
+            Err(err) => summary.skipped.push((number, err)),
+        }
+    }
+}
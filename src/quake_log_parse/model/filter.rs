@@ -0,0 +1,162 @@
+use super::{
+    error::LogError,
+    log_model::{parse_timestamp, Match},
+};
+
+/// The names of the criteria of a `MatchFilter`, as given to `MatchFilter::set`: the options of the
+/// command line (e.g. `--map=q3dm17`) and the parameters of the HTTP API (e.g. `?map=q3dm17`).
+pub const FILTER_NAMES: &[&str] = &[
+    "map",
+    "gametype",
+    "hostname",
+    "player",
+    "ids",
+    "time",
+    "min-players",
+    "completed",
+];
+
+/// Criteria selecting matches; every criterion that is set must hold for a match to be selected.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MatchFilter {
+    /// A player that took part in the match, by name (any of the names it used in the match).
+    pub player: Option<String>,
+    /// The map, as in the `mapname` server setting (e.g. `q3dm17`).
+    pub map: Option<String>,
+    /// The game type, as in the `g_gametype` server setting (e.g. `0` for free for all).
+    pub gametype: Option<String>,
+    /// The server, as in the `sv_hostname` server setting (e.g. `Code Miner Server`).
+    pub hostname: Option<String>,
+    /// The smallest match id selected.
+    pub min_id: Option<i32>,
+    /// The largest match id selected.
+    pub max_id: Option<i32>,
+    /// The earliest start of the matches selected, in seconds since the server started.
+    pub min_started_at: Option<u32>,
+    /// The latest start of the matches selected, in seconds since the server started.
+    pub max_started_at: Option<u32>,
+    /// The smallest number of players in the match.
+    pub min_players: Option<usize>,
+    /// Whether only the matches that logged a `ShutdownGame:` line are selected.
+    pub completed: bool,
+}
+
+impl MatchFilter {
+    /// Sets a criterion of the filter from its name and a textual value.
+    ///
+    /// `ids` takes an inclusive range, e.g. `3..7`, `3..` or `..7`, or a single id; `time` takes a
+    /// range of the start of the matches in the `minutes:seconds` of the log lines, e.g. `10:00..25:00`.
+    /// Logs carry no calendar dates, only the time elapsed since the server started, so a range of
+    /// days is selected by the log files read instead. `completed` takes `true`, `false` or no value,
+    /// which means `true`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the criterion, one of `FILTER_NAMES`.
+    /// * `value` - The value of the criterion.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if the
+    ///   criterion is unknown or its value is invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `LogError::FilterError` if `name` is not one of `FILTER_NAMES` or `value` cannot be
+    /// parsed.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), LogError> {
+        let invalid = || LogError::FilterError(format!("Invalid value for {}: {}", name, value));
+        let id = |id: &str| match id {
+            "" => Ok(None),
+            id => id.parse().map(Some).map_err(|_| invalid()),
+        };
+
+        match name {
+            "map" => self.map = Some(value.to_string()),
+            "gametype" => self.gametype = Some(value.to_string()),
+            "hostname" => self.hostname = Some(value.to_string()),
+            "player" => self.player = Some(value.to_string()),
+            "ids" => {
+                let (min, max) = value.split_once("..").unwrap_or((value, value));
+                self.min_id = id(min)?;
+                self.max_id = id(max)?;
+            }
+            "time" => {
+                let time = |time: &str| match time {
+                    "" => Ok(None),
+                    time => parse_timestamp(time).map(Some).ok_or_else(invalid),
+                };
+                let (min, max) = value.split_once("..").unwrap_or((value, value));
+                self.min_started_at = time(min)?;
+                self.max_started_at = time(max)?;
+            }
+            "min-players" => self.min_players = Some(value.parse().map_err(|_| invalid())?),
+            "completed" => {
+                self.completed = match value {
+                    "" | "true" => true,
+                    "false" => false,
+                    _ => return Err(invalid()),
+                }
+            }
+            _ => return Err(LogError::FilterError(format!("Unknown filter: {}", name))),
+        }
+        Ok(())
+    }
+
+    /// Returns whether a match meets every criterion of the filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `mat` - A reference to the `Match` to check.
+    pub fn matches(&self, mat: &Match) -> bool {
+        let data = &mat.data;
+        let setting_is = |key: &str, expected: &Option<String>| {
+            expected
+                .as_ref()
+                .is_none_or(|expected| data.settings.get(key) == Some(expected))
+        };
+
+        setting_is("mapname", &self.map)
+            && setting_is("g_gametype", &self.gametype)
+            && setting_is("sv_hostname", &self.hostname)
+            && self.min_id.is_none_or(|min| mat.id >= min)
+            && self.max_id.is_none_or(|max| mat.id <= max)
+            && self.min_started_at.is_none_or(|min| data.started_at >= min)
+            && self.max_started_at.is_none_or(|max| data.started_at <= max)
+            && self.min_players.is_none_or(|min| data.players.len() >= min)
+            && (!self.completed || data.completed)
+            && self.player.as_ref().is_none_or(|player| {
+                data.players.contains(player)
+                    || data.kills.contains_key(player)
+                    || data
+                        .sessions
+                        .iter()
+                        .any(|session| session.names.iter().any(|name| name.name == *player))
+            })
+    }
+}
+
+/// Selects the matches that meet a filter, in their original order.
+///
+/// # Arguments
+///
+/// * `matches` - A slice of `Match` structs to select from.
+/// * `filter` - A reference to the `MatchFilter` to apply.
+///
+/// # Returns
+///
+/// * `Vec<&Match>` - The selected matches.
+pub fn filter_matches<'a>(matches: &'a [Match], filter: &MatchFilter) -> Vec<&'a Match> {
+    matches.iter().filter(|mat| filter.matches(mat)).collect()
+}
+
+/// Keeps only the matches that meet a filter, e.g. before ranking the players, so the report only
+/// covers those matches. Matches keep their ids.
+///
+/// # Arguments
+///
+/// * `matches` - A mutable reference to the vector of `Match` structs to filter.
+/// * `filter` - A reference to the `MatchFilter` to apply.
+pub fn retain_matches(matches: &mut Vec<Match>, filter: &MatchFilter) {
+    matches.retain(|mat| filter.matches(mat));
+}
@@ -0,0 +1,396 @@
+use std::io::{self, Write};
+
+use super::log_model::means_of_death_id;
+
+/// Means of death of the kills caused by the world.
+const WORLD_MEANS: [&str; 4] = ["MOD_TRIGGER_HURT", "MOD_FALLING", "MOD_LAVA", "MOD_CRUSH"];
+/// Share of the kills caused by the world, in percent.
+const WORLD_KILL_PERCENT: u64 = 15;
+/// Client id used by the server for kills caused by the world.
+const WORLD_CLIENT_ID: usize = 1022;
+/// Client id of the first player; lower slots are usually taken by bots or spectators.
+const FIRST_CLIENT_ID: usize = 2;
+/// Length of a match that reaches the time limit, in seconds.
+const TIME_LIMIT_SECS: u64 = 15 * 60;
+const SEPARATOR: &str = "------------------------------------------------------------";
+const PLAYER_NAMES: [&str; 12] = [
+    "Isgalamido",
+    "Zeh",
+    "Dono da Bola",
+    "Assasinu Credi",
+    "Mocinha",
+    "Oootsimo",
+    "Chessus",
+    "Mal",
+    "UnnamedPlayer",
+    "Maluquinho",
+    "Fasano Again",
+    "Sarge",
+];
+const MODELS: [&str; 6] = [
+    "sarge",
+    "xian/default",
+    "uriel/zael",
+    "razor/id",
+    "major",
+    "visor",
+];
+const ITEMS: [&str; 8] = [
+    "weapon_rocketlauncher",
+    "weapon_railgun",
+    "weapon_shotgun",
+    "item_armor_body",
+    "item_health_large",
+    "ammo_rockets",
+    "item_quad",
+    "weapon_plasmagun",
+];
+
+/// Options of the synthetic log generator.
+///
+/// The same options, seed included, always generate the same log.
+#[derive(Debug, Clone)]
+pub struct GeneratorOptions {
+    /// Seed of the pseudo-random generator.
+    pub seed: u64,
+    /// Number of matches.
+    pub matches: usize,
+    /// Number of distinct players, each with its own name and model.
+    pub players: usize,
+    /// Maximum number of players in a match.
+    pub players_per_match: usize,
+    /// Average number of kills in a match.
+    pub kills_per_match: usize,
+    /// Game types (`g_gametype`) picked for the matches, e.g. `0` for free for all and `4` for CTF.
+    pub game_types: Vec<u32>,
+    pub maps: Vec<String>,
+    /// Means of death of the kills between players, e.g. `MOD_RAILGUN`. Unknown names get the id `0`.
+    pub weapons: Vec<String>,
+    /// Probability that a player renames themselves during a match.
+    pub rename_rate: f64,
+    /// Probability that a player disconnects before the end of a match.
+    pub disconnect_rate: f64,
+    /// Probability that a match is cut short, without scores nor `ShutdownGame:`, as when the server
+    /// crashes. The server clock then restarts from zero.
+    pub truncate_rate: f64,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions {
+            seed: 0,
+            matches: 20,
+            players: 8,
+            players_per_match: 6,
+            kills_per_match: 40,
+            game_types: vec![0, 1, 3, 4],
+            maps: ["q3dm17", "q3dm6", "q3tourney2", "q3ctf1", "q3dm4"]
+                .map(String::from)
+                .to_vec(),
+            weapons: [
+                "MOD_SHOTGUN",
+                "MOD_GAUNTLET",
+                "MOD_MACHINEGUN",
+                "MOD_GRENADE",
+                "MOD_GRENADE_SPLASH",
+                "MOD_ROCKET",
+                "MOD_ROCKET_SPLASH",
+                "MOD_PLASMA",
+                "MOD_PLASMA_SPLASH",
+                "MOD_RAILGUN",
+                "MOD_LIGHTNING",
+                "MOD_BFG_SPLASH",
+            ]
+            .map(String::from)
+            .to_vec(),
+            rename_rate: 0.05,
+            disconnect_rate: 0.1,
+            truncate_rate: 0.05,
+        }
+    }
+}
+
+/// Generates a synthetic Quake 3 log.
+///
+/// # Arguments
+///
+/// * `options` - A reference to the `GeneratorOptions` describing the log.
+///
+/// # Returns
+///
+/// * `String` - The content of the log.
+pub fn generate_log(options: &GeneratorOptions) -> String {
+    let mut log = Vec::new();
+    write_log(options, &mut log).expect("writing to a Vec cannot fail");
+    String::from_utf8(log).expect("the generated log is UTF-8")
+}
+
+/// Generates a synthetic Quake 3 log into a writer, one match at a time.
+///
+/// Matches look like the ones of a real server: players connect, set their user info and enter the
+/// game, kill each other or get killed by the world, pick up items, may rename themselves or leave,
+/// and the match ends with the final scores, which agree with the kills.
+///
+/// # Arguments
+///
+/// * `options` - A reference to the `GeneratorOptions` describing the log.
+/// * `out` - The writer the log is written to.
+///
+/// # Errors
+///
+/// Returns the error of the writer, if any.
+pub fn write_log<W: Write>(options: &GeneratorOptions, out: &mut W) -> io::Result<()> {
+    let mut rng = Rng::new(options.seed);
+    let mut clock = 0;
+
+    for _ in 0..options.matches {
+        clock = write_match(options, &mut rng, clock, out)?;
+    }
+    Ok(())
+}
+
+/// A player taking part in a generated match.
+struct Client {
+    id: usize,
+    name: String,
+    model: &'static str,
+    team: u32,
+    score: i32,
+    rename_at: Option<usize>,
+    disconnect_at: Option<usize>,
+    connected: bool,
+}
+
+/// Writes one match starting at `clock` and returns the clock of the next one.
+fn write_match<W: Write>(
+    options: &GeneratorOptions,
+    rng: &mut Rng,
+    mut clock: u64,
+    out: &mut W,
+) -> io::Result<u64> {
+    let game_type = pick(rng, &options.game_types).copied().unwrap_or(0);
+    let map = pick(rng, &options.maps).map_or("q3dm17", String::as_str);
+    let kills =
+        options.kills_per_match / 2 + rng.below(options.kills_per_match as u64 + 1) as usize;
+    let truncated_at = rng
+        .chance(options.truncate_rate)
+        .then(|| rng.below(kills as u64 + 1) as usize);
+
+    line(out, clock, SEPARATOR)?;
+    line(
+        out,
+        clock,
+        &format!(
+            "InitGame: \\sv_floodProtect\\1\\sv_maxPing\\0\\sv_minPing\\0\\sv_maxRate\\10000\\sv_minRate\\0\\sv_hostname\\Code Miner Server\\g_gametype\\{}\\sv_privateClients\\2\\sv_maxclients\\16\\sv_allowDownload\\0\\dmflags\\0\\fraglimit\\20\\timelimit\\15\\g_maxGameClients\\0\\capturelimit\\8\\version\\ioq3 1.36 linux-x86_64 Apr 12 2009\\protocol\\68\\mapname\\{}\\gamename\\baseq3\\g_needpass\\0",
+            game_type, map
+        ),
+    )?;
+
+    let mut clients = pick_clients(options, rng, game_type, kills);
+    for client in &clients {
+        line(out, clock, &format!("ClientConnect: {}", client.id))?;
+        line(out, clock, &userinfo(client))?;
+        line(out, clock, &format!("ClientBegin: {}", client.id))?;
+    }
+    clock += 1;
+
+    let step = (TIME_LIMIT_SECS / kills.max(1) as u64).max(1);
+    for kill in 0..kills {
+        if truncated_at == Some(kill) {
+            return Ok(0);
+        }
+        clock += 1 + rng.below(step);
+
+        for client in clients.iter_mut().filter(|client| client.connected) {
+            if client.rename_at == Some(kill) {
+                client.name = format!("{}_{}", client.name, rng.below(100));
+                line(out, clock, &userinfo(client))?;
+            }
+            if client.disconnect_at == Some(kill) {
+                client.connected = false;
+                line(out, clock, &format!("ClientDisconnect: {}", client.id))?;
+            }
+        }
+
+        let present: Vec<usize> = (0..clients.len())
+            .filter(|&idx| clients[idx].connected)
+            .collect();
+        if present.len() < 2 {
+            break;
+        }
+        let victim = present[rng.below(present.len() as u64) as usize];
+        if rng.below(100) < WORLD_KILL_PERCENT {
+            let means = WORLD_MEANS[rng.below(WORLD_MEANS.len() as u64) as usize];
+            clients[victim].score -= 1;
+            line(
+                out,
+                clock,
+                &format!(
+                    "Kill: {} {} {}: <world> killed {} by {}",
+                    WORLD_CLIENT_ID,
+                    clients[victim].id,
+                    means_id(means),
+                    clients[victim].name,
+                    means
+                ),
+            )?;
+        } else {
+            let others: Vec<usize> = present
+                .iter()
+                .copied()
+                .filter(|&idx| idx != victim)
+                .collect();
+            let killer = others[rng.below(others.len() as u64) as usize];
+            let means = pick(rng, &options.weapons).map_or("MOD_UNKNOWN", String::as_str);
+            clients[killer].score += 1;
+            line(
+                out,
+                clock,
+                &format!(
+                    "Kill: {} {} {}: {} killed {} by {}",
+                    clients[killer].id,
+                    clients[victim].id,
+                    means_id(means),
+                    clients[killer].name,
+                    clients[victim].name,
+                    means
+                ),
+            )?;
+        }
+        if rng.chance(0.5) {
+            let client = &clients[present[rng.below(present.len() as u64) as usize]];
+            let item = ITEMS[rng.below(ITEMS.len() as u64) as usize];
+            line(out, clock, &format!("Item: {} {}", client.id, item))?;
+        }
+    }
+    if let Some(truncated_at) = truncated_at {
+        if truncated_at >= kills {
+            return Ok(0);
+        }
+    }
+
+    clock += 1;
+    line(out, clock, "Exit: Timelimit hit.")?;
+    for client in clients.iter().filter(|client| client.connected) {
+        line(
+            out,
+            clock,
+            &format!(
+                "score: {}  ping: {}  client: {} {}",
+                client.score,
+                rng.below(120),
+                client.id,
+                client.name
+            ),
+        )?;
+    }
+    line(out, clock, "ShutdownGame:")?;
+    line(out, clock, SEPARATOR)?;
+    Ok(clock + 5)
+}
+
+/// Picks the players of a match among the player pool, each on its own client slot.
+fn pick_clients(
+    options: &GeneratorOptions,
+    rng: &mut Rng,
+    game_type: u32,
+    kills: usize,
+) -> Vec<Client> {
+    let pool = options.players.max(2);
+    let max = options.players_per_match.clamp(2, pool);
+    let count = 2 + rng.below((max - 1) as u64) as usize;
+
+    // A partial Fisher-Yates shuffle of the pool.
+    let mut players: Vec<usize> = (0..pool).collect();
+    for idx in 0..count {
+        let other = idx + rng.below((pool - idx) as u64) as usize;
+        players.swap(idx, other);
+    }
+
+    players[..count]
+        .iter()
+        .enumerate()
+        .map(|(slot, &player)| Client {
+            id: FIRST_CLIENT_ID + slot,
+            name: player_name(player),
+            model: MODELS[player % MODELS.len()],
+            team: if game_type >= 3 {
+                1 + slot as u32 % 2
+            } else {
+                0
+            },
+            score: 0,
+            rename_at: rng
+                .chance(options.rename_rate)
+                .then(|| rng.below(kills as u64 + 1) as usize),
+            disconnect_at: rng
+                .chance(options.disconnect_rate)
+                .then(|| rng.below(kills as u64 + 1) as usize),
+            connected: true,
+        })
+        .collect()
+}
+
+/// The name of the n-th player of the pool.
+fn player_name(player: usize) -> String {
+    let name = PLAYER_NAMES[player % PLAYER_NAMES.len()];
+    match player / PLAYER_NAMES.len() {
+        0 => name.to_string(),
+        round => format!("{} {}", name, round + 1),
+    }
+}
+
+fn userinfo(client: &Client) -> String {
+    format!(
+        "ClientUserinfoChanged: {} n\\{}\\t\\{}\\model\\{}\\hmodel\\{}\\g_redteam\\\\g_blueteam\\\\c1\\4\\c2\\5\\hc\\100\\w\\0\\l\\0\\tt\\0\\tl\\0",
+        client.id, client.name, client.team, client.model, client.model
+    )
+}
+
+/// Returns the id written in `Kill:` lines for a means of death, `0` (`MOD_UNKNOWN`) for unknown ones.
+fn means_id(means: &str) -> i32 {
+    means_of_death_id(means).unwrap_or(0)
+}
+
+/// Writes a log line, prefixed with the time as the server does, e.g. ` 20:34 ClientConnect: 2`.
+fn line<W: Write>(out: &mut W, clock: u64, text: &str) -> io::Result<()> {
+    writeln!(out, "{:>3}:{:02} {}", clock / 60, clock % 60, text)
+}
+
+fn pick<'a, T>(rng: &mut Rng, items: &'a [T]) -> Option<&'a T> {
+    if items.is_empty() {
+        return None;
+    }
+    items.get(rng.below(items.len() as u64) as usize)
+}
+
+/// A small SplitMix64 generator, so the generated logs do not depend on any external crate.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`, or `0` if `bound` is `0`.
+    fn below(&mut self, bound: u64) -> u64 {
+        if bound == 0 {
+            0
+        } else {
+            self.next() % bound
+        }
+    }
+
+    /// `true` with the given probability.
+    fn chance(&mut self, probability: f64) -> bool {
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+}
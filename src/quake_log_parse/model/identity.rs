@@ -0,0 +1,460 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    env,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    error::LogError,
+    intern::{Interner, Symbol},
+    log_model::{Match, PlayerScore},
+};
+
+/// User info keys that carry a stable client identifier, on the engines and mods that log one.
+const GUID_KEYS: [&str; 3] = ["cl_guid", "guid", "id"];
+/// User info keys that together describe how a player looks in game.
+const FINGERPRINT_KEYS: [&str; 4] = ["model", "hmodel", "c1", "c2"];
+/// Shortest name prefix that links two names sharing the same look (e.g. "Isga" and "Isgalamido").
+const MIN_PREFIX_LEN: usize = 3;
+/// Models players get when they do not choose one, which say nothing about who is playing.
+const DEFAULT_MODELS: [&str; 2] = ["sarge", "sarge/default"];
+/// Names of the alias files looked up next to the executable, in order of preference.
+const ALIAS_FILE_NAMES: [&str; 2] = ["aliases.toml", "aliases.json"];
+
+/// Contents of a user supplied alias file, mapping each canonical name to the aliases of the player.
+///
+/// In TOML:
+///
+/// ```toml
+/// [aliases]
+/// Isgalamido = ["Isga", "Isga_BR"]
+/// ```
+///
+/// In JSON: `{ "aliases": { "Isgalamido": ["Isga", "Isga_BR"] } }`.
+#[derive(Debug, Default, Deserialize)]
+pub struct AliasFile {
+    #[serde(default)]
+    pub aliases: HashMap<String, Vec<String>>,
+}
+
+/// Lookup from any known alias to the canonical name of a player.
+#[derive(Debug, Clone, Default)]
+pub struct AliasMap {
+    canonical: HashMap<String, String>,
+}
+
+/// A player as identified across matches, with every name it was seen with.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PlayerIdentity {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub guids: Vec<String>,
+    pub kills: i32,
+}
+
+/// A player seen in one match, either as a client session or only as a name in the kills.
+///
+/// Names are interned, as a player is seen under the same few names in most of the matches.
+struct Sighting<'a> {
+    /// The index of the match in the slice of matches.
+    match_idx: usize,
+    names: Vec<Symbol>,
+    guid: Option<&'a str>,
+    fingerprint: Option<String>,
+    kills: i32,
+}
+
+impl AliasMap {
+    /// Builds the lookup from a parsed alias file.
+    pub fn from_file(file: AliasFile) -> Self {
+        let mut canonical = HashMap::new();
+        for (name, aliases) in file.aliases {
+            for alias in aliases {
+                canonical.insert(alias, name.clone());
+            }
+            canonical.insert(name.clone(), name);
+        }
+        AliasMap { canonical }
+    }
+
+    /// Parses an alias file in TOML format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the content is not a valid alias file.
+    pub fn from_toml(content: &str) -> Result<Self, LogError> {
+        toml::from_str(content)
+            .map(Self::from_file)
+            .map_err(|err| LogError::AliasFileError(format!("Invalid alias file: {}", err)))
+    }
+
+    /// Parses an alias file in JSON format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the content is not a valid alias file.
+    pub fn from_json(content: &str) -> Result<Self, LogError> {
+        serde_json::from_str(content)
+            .map(Self::from_file)
+            .map_err(|err| LogError::AliasFileError(format!("Invalid alias file: {}", err)))
+    }
+
+    /// Reads an alias file, choosing the format from its extension (`.json`, otherwise TOML).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the file cannot be read or is not a valid alias file.
+    pub fn load(path: &Path) -> Result<Self, LogError> {
+        let content = read_to_string(path).map_err(|err| {
+            LogError::AliasFileError(format!("Error reading the alias file: {}", err))
+        })?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&content),
+            _ => Self::from_toml(&content),
+        }
+    }
+
+    /// Returns the canonical name for `name`, or `name` itself if it is not a known alias.
+    pub fn canonical<'a>(&'a self, name: &'a str) -> &'a str {
+        self.canonical.get(name).map_or(name, String::as_str)
+    }
+
+    /// Returns `true` if `name` is a canonical name declared in the alias file.
+    fn is_canonical(&self, name: &str) -> bool {
+        self.canonical
+            .get(name)
+            .is_some_and(|canonical| canonical == name)
+    }
+}
+
+/// Retrieves the path of the optional alias file.
+///
+/// # Returns
+///
+/// * `Option<PathBuf>` - The path of `aliases.toml` or `aliases.json` located in the same directory as
+///   the executable, or `None` if there is no alias file.
+pub fn get_alias_path() -> Option<PathBuf> {
+    let mut current_exe = env::current_exe().ok()?;
+    current_exe.pop();
+
+    ALIAS_FILE_NAMES
+        .iter()
+        .map(|file_name| current_exe.join(file_name))
+        .find(|path| path.exists())
+}
+
+/// Links the players seen in every match into identities.
+///
+/// Sightings of a player are linked when any of the following holds:
+///
+/// * They share a GUID-like user info key (`cl_guid`, `guid` or `id`).
+/// * They were the same client slot in a match, across renames.
+/// * They share a name (after applying `aliases`), unless they carry different GUIDs, in which case they
+///   are different people using the same name.
+/// * They share the same model and colours, other than the default model, one name is a prefix of the
+///   other, and they never played in the same match, so e.g. "Zeh" and "Zeh 2" playing each other
+///   stay two players.
+///
+/// Identities are named after the canonical name from the alias file when there is one, otherwise
+/// after the name seen in most matches.
+///
+/// # Arguments
+///
+/// * `matches` - A slice of `Match` structs containing the sessions and kills of each match.
+/// * `aliases` - A reference to the `AliasMap` supplied by the user.
+///
+/// # Returns
+///
+/// * `Vec<PlayerIdentity>` - The identities sorted in descending order of kills, ties broken by name.
+pub fn resolve_identities(matches: &[Match], aliases: &AliasMap) -> Vec<PlayerIdentity> {
+    let (names, sightings) = collect_sightings(matches, aliases);
+    let mut parents: Vec<usize> = (0..sightings.len()).collect();
+
+    let mut by_guid: HashMap<&str, usize> = HashMap::new();
+    // The sightings of each name, indexed by its symbol.
+    let mut by_name: Vec<Vec<usize>> = vec![Vec::new(); names.len()];
+    for (idx, sighting) in sightings.iter().enumerate() {
+        if let Some(guid) = sighting.guid {
+            let first = *by_guid.entry(guid).or_insert(idx);
+            union(&mut parents, first, idx);
+        }
+        for name in &sighting.names {
+            by_name[name.index()].push(idx);
+        }
+    }
+
+    for indexes in &by_name {
+        let guids: BTreeSet<&str> = indexes
+            .iter()
+            .filter_map(|&idx| sightings[idx].guid)
+            .collect();
+        let linked: Vec<usize> = if guids.len() <= 1 {
+            indexes.clone()
+        } else {
+            indexes
+                .iter()
+                .copied()
+                .filter(|&idx| sightings[idx].guid.is_none())
+                .collect()
+        };
+        for pair in linked.windows(2) {
+            union(&mut parents, pair[0], pair[1]);
+        }
+    }
+
+    link_by_fingerprint(&names, &sightings, &mut parents);
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for idx in 0..sightings.len() {
+        let root = find(&mut parents, idx);
+        groups.entry(root).or_default().push(idx);
+    }
+
+    let mut identities: Vec<PlayerIdentity> = groups
+        .into_values()
+        .map(|indexes| build_identity(&names, &sightings, &indexes, aliases))
+        .collect();
+    disambiguate_names(&mut identities);
+    identities.sort_by(|a, b| b.kills.cmp(&a.kills).then_with(|| a.name.cmp(&b.name)));
+    identities
+}
+
+/// Process player rankings keyed by player identity.
+///
+/// Works like `process_ranking`, except that players are merged by identity (see `resolve_identities`)
+/// rather than by exact name, and each entry is named after the canonical name of the player.
+///
+/// # Arguments
+///
+/// * `matches` - A slice of `Match` structs containing match data.
+/// * `aliases` - A reference to the `AliasMap` supplied by the user.
+/// * `ranking` - A mutable reference to a vector of `PlayerScore` structs representing player rankings.
+pub fn process_identity_ranking(
+    matches: &[Match],
+    aliases: &AliasMap,
+    ranking: &mut Vec<PlayerScore>,
+) {
+    ranking.extend(
+        resolve_identities(matches, aliases)
+            .into_iter()
+            .map(|identity| PlayerScore {
+                name: identity.name,
+                kills: identity.kills,
+            }),
+    );
+}
+
+/// Gathers one sighting per named session, plus one for each name in the kills without a session,
+/// with the interner of their names.
+fn collect_sightings<'a>(
+    matches: &'a [Match],
+    aliases: &'a AliasMap,
+) -> (Interner<'a>, Vec<Sighting<'a>>) {
+    let mut names = Interner::new();
+    let mut sightings = Vec::new();
+
+    for (match_idx, mat) in matches.iter().enumerate() {
+        let mut session_kills: HashMap<&str, (i32, usize)> = HashMap::new();
+
+        for session in mat.data.sessions.iter().filter(|s| !s.name.is_empty()) {
+            let mut session_names: Vec<&str> = session
+                .names
+                .iter()
+                .map(|name_use| aliases.canonical(&name_use.name))
+                .collect();
+            session_names.push(aliases.canonical(&session.name));
+            session_names.sort();
+            session_names.dedup();
+
+            let entry = session_kills
+                .entry(session.name.as_str())
+                .or_insert((0, sightings.len()));
+            entry.0 += session.kills;
+            sightings.push(Sighting {
+                match_idx,
+                names: session_names
+                    .into_iter()
+                    .map(|name| names.intern(name))
+                    .collect(),
+                guid: GUID_KEYS
+                    .iter()
+                    .filter_map(|key| session.userinfo.get(*key))
+                    .find(|guid| !guid.is_empty())
+                    .map(String::as_str),
+                fingerprint: fingerprint(&session.userinfo),
+                kills: session.kills,
+            });
+        }
+
+        // Kills attributed by name (e.g. to players without a session) are not kept on the sessions.
+        for (name, kills) in &mat.data.kills {
+            match session_kills.get(name.as_str()) {
+                Some(&(attributed, idx)) => {
+                    sightings[idx].kills += kills - attributed;
+                }
+                _ => sightings.push(Sighting {
+                    match_idx,
+                    names: vec![names.intern(aliases.canonical(name))],
+                    guid: None,
+                    fingerprint: None,
+                    kills: *kills,
+                }),
+            }
+        }
+    }
+    (names, sightings)
+}
+
+/// Describes how a player looks in game, if the user info includes a model other than the default.
+fn fingerprint(userinfo: &HashMap<String, String>) -> Option<String> {
+    let model = userinfo.get("model")?;
+    if DEFAULT_MODELS.contains(&model.to_lowercase().as_str()) {
+        return None;
+    }
+    let values: Vec<&str> = FINGERPRINT_KEYS
+        .iter()
+        .map(|key| userinfo.get(*key).map_or("", String::as_str))
+        .collect();
+    Some(values.join("\\"))
+}
+
+/// Links sightings that look the same in game and whose names are prefixes of one another, as long
+/// as the players they are already linked to never met in a match.
+///
+/// Links are made greedily, so the fingerprints are walked in order for the same log to always link
+/// the same sightings.
+fn link_by_fingerprint(names: &Interner, sightings: &[Sighting], parents: &mut [usize]) {
+    // The matches played by each group of linked sightings, by the root of the group.
+    let mut played: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+    for (idx, sighting) in sightings.iter().enumerate() {
+        let root = find(parents, idx);
+        played.entry(root).or_default().insert(sighting.match_idx);
+    }
+
+    let mut by_fingerprint: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (idx, sighting) in sightings.iter().enumerate() {
+        if let Some(fingerprint) = &sighting.fingerprint {
+            by_fingerprint.entry(fingerprint).or_default().push(idx);
+        }
+    }
+
+    for indexes in by_fingerprint.values() {
+        for (pos, &a) in indexes.iter().enumerate() {
+            for &b in &indexes[pos + 1..] {
+                let compatible_guids = match (sightings[a].guid, sightings[b].guid) {
+                    (Some(guid_a), Some(guid_b)) => guid_a == guid_b,
+                    _ => true,
+                };
+                let prefixed = sightings[a].names.iter().any(|name_a| {
+                    sightings[b].names.iter().any(|&name_b| {
+                        is_prefix_alias(names.resolve(*name_a), names.resolve(name_b))
+                    })
+                });
+                let (root_a, root_b) = (find(parents, a), find(parents, b));
+                if !compatible_guids || !prefixed || root_a == root_b {
+                    continue;
+                }
+                if played[&root_a].is_disjoint(&played[&root_b]) {
+                    let mut matches = played.remove(&root_a).unwrap_or_default();
+                    matches.extend(played.remove(&root_b).unwrap_or_default());
+                    union(parents, a, b);
+                    played.insert(find(parents, a), matches);
+                }
+            }
+        }
+    }
+}
+
+/// Returns `true` if the shorter name is a case insensitive prefix of the longer one.
+fn is_prefix_alias(a: &str, b: &str) -> bool {
+    let (a, b) = (a.to_lowercase(), b.to_lowercase());
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    short.chars().count() >= MIN_PREFIX_LEN && long.starts_with(&short)
+}
+
+/// Builds the identity of a group of linked sightings.
+fn build_identity(
+    names: &Interner,
+    sightings: &[Sighting],
+    indexes: &[usize],
+    aliases: &AliasMap,
+) -> PlayerIdentity {
+    // Names in order of first sighting, with the number of sightings of each.
+    let mut seen: Vec<(Symbol, usize)> = Vec::new();
+    let mut guids = BTreeSet::new();
+    let mut kills = 0;
+    for &idx in indexes {
+        for &name in &sightings[idx].names {
+            match seen.iter_mut().find(|(seen_name, _)| *seen_name == name) {
+                Some((_, count)) => *count += 1,
+                None => seen.push((name, 1)),
+            }
+        }
+        guids.extend(sightings[idx].guid.map(str::to_string));
+        kills += sightings[idx].kills;
+    }
+    let seen: Vec<(&str, usize)> = seen
+        .into_iter()
+        .map(|(name, count)| (names.resolve(name), count))
+        .collect();
+
+    // `max_by_key` keeps the last maximum, so reverse to break ties by first sighting.
+    let name = seen
+        .iter()
+        .find(|(name, _)| aliases.is_canonical(name))
+        .or_else(|| seen.iter().rev().max_by_key(|(_, count)| *count))
+        .map_or("", |(name, _)| *name)
+        .to_string();
+    let mut other_names: Vec<String> = seen
+        .into_iter()
+        .filter(|(alias, _)| *alias != name)
+        .map(|(alias, _)| alias.to_string())
+        .collect();
+    other_names.sort();
+
+    PlayerIdentity {
+        name,
+        aliases: other_names,
+        guids: guids.into_iter().collect(),
+        kills,
+    }
+}
+
+/// Appends the GUID to the name of different players that would otherwise share the same name.
+fn disambiguate_names(identities: &mut [PlayerIdentity]) {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for identity in identities.iter() {
+        *counts.entry(identity.name.clone()).or_insert(0) += 1;
+    }
+    for identity in identities.iter_mut() {
+        if counts[&identity.name] > 1 {
+            if let Some(guid) = identity.guids.first() {
+                identity.name = format!("{} [{}]", identity.name, guid);
+            }
+        }
+    }
+}
+
+fn find(parents: &mut [usize], idx: usize) -> usize {
+    let mut root = idx;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    let mut current = idx;
+    while parents[current] != root {
+        let next = parents[current];
+        parents[current] = root;
+        current = next;
+    }
+    root
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (root_a, root_b) = (find(parents, a), find(parents, b));
+    if root_a != root_b {
+        parents[root_b.max(root_a)] = root_a.min(root_b);
+    }
+}
@@ -0,0 +1,107 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+
+use super::error::LogError;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const BZIP2_MAGIC: &[u8] = b"BZh";
+
+/// Compression format of a log file, detected from its first bytes rather than its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    /// Detects the compression format from the magic bytes at the start of a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The first bytes of the file; at least four are needed to recognise every format.
+    pub fn detect(header: &[u8]) -> Self {
+        match header {
+            _ if header.starts_with(GZIP_MAGIC) => Compression::Gzip,
+            _ if header.starts_with(ZSTD_MAGIC) => Compression::Zstd,
+            _ if header.starts_with(BZIP2_MAGIC) => Compression::Bzip2,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Opens a log file for reading, transparently decompressing gzip, zstd and bzip2 content.
+///
+/// The content is decompressed as it is read, so large archives never need to be unpacked on disk.
+/// Files made of several concatenated compressed streams (e.g. appended by log rotation) are read
+/// to the end.
+///
+/// # Arguments
+///
+/// * `path` - The path of the log file.
+///
+/// # Returns
+///
+/// * `Result<Box<dyn BufRead + Send>, LogError>` - A `Result` with a reader of the decompressed content,
+///   or an error (`Err`) if the file cannot be opened.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if the file cannot be opened or its header cannot be read.
+pub fn open_log_file(path: &Path) -> Result<Box<dyn BufRead + Send>, LogError> {
+    let read_error = |err: std::io::Error| {
+        LogError::ReadLogError(format!(
+            "Error reading the log file {}: {}",
+            path.display(),
+            err
+        ))
+    };
+
+    let mut reader = BufReader::new(File::open(path).map_err(read_error)?);
+    let compression = Compression::detect(reader.fill_buf().map_err(read_error)?);
+
+    Ok(match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(reader))),
+        Compression::Zstd => Box::new(BufReader::new(
+            zstd::Decoder::with_buffer(reader).map_err(read_error)?,
+        )),
+        Compression::Bzip2 => Box::new(BufReader::new(MultiBzDecoder::new(reader))),
+    })
+}
+
+/// Reads a log file, decompressing it if needed, and returns its content as a string.
+///
+/// # Arguments
+///
+/// * `path` - The path of the log file.
+///
+/// # Returns
+///
+/// * `Result<String, LogError>` - A `Result` with the log file content as a string, or an error (`Err`)
+///   if a problem is encountered during file reading.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if the file cannot be read, is corrupted, or is not valid UTF-8.
+pub fn read_log_file(path: &Path) -> Result<String, LogError> {
+    let mut content = String::new();
+    open_log_file(path)?
+        .read_to_string(&mut content)
+        .map_err(|err| {
+            LogError::ReadLogError(format!(
+                "Error reading the log file {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+    Ok(content)
+}
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+/// The compact id of a string interned in an `Interner`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Returns the position of the string in the order it was first interned, to index side tables.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Maps strings borrowed from the parsed data (e.g. player names) to compact ids.
+///
+/// Each distinct string is stored once, without being copied, so the same name seen in thousands of
+/// matches is compared and hashed once and then handled as a `Symbol`.
+#[derive(Debug, Default)]
+pub struct Interner<'a> {
+    ids: HashMap<&'a str, Symbol>,
+    strings: Vec<&'a str>,
+}
+
+impl<'a> Interner<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of a string, interning it if it was not seen before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` distinct strings are interned.
+    pub fn intern(&mut self, string: &'a str) -> Symbol {
+        if let Some(&symbol) = self.ids.get(string) {
+            return symbol;
+        }
+        let symbol = Symbol(u32::try_from(self.strings.len()).expect("too many interned strings"));
+        self.ids.insert(string, symbol);
+        self.strings.push(string);
+        symbol
+    }
+
+    /// Returns the id of a string already interned.
+    pub fn get(&self, string: &str) -> Option<Symbol> {
+        self.ids.get(string).copied()
+    }
+
+    /// Returns the string of an id.
+    pub fn resolve(&self, symbol: Symbol) -> &'a str {
+        self.strings[symbol.index()]
+    }
+
+    /// Iterates over the interned strings and their ids, in the order they were first interned.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &'a str)> + '_ {
+        self.strings
+            .iter()
+            .enumerate()
+            .map(|(idx, string)| (Symbol(idx as u32), *string))
+    }
+
+    /// Returns the number of distinct strings interned.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
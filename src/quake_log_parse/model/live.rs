@@ -0,0 +1,300 @@
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use super::{
+    checkpoint::inode,
+    error::LogError,
+    event::{LogEvent, LogLine},
+    log_model::{
+        add_count, final_scoreboard, process_line, Match, MatchData, ParseOptions, PlayerScore,
+    },
+    session,
+};
+
+/// An event of a log being played, as pushed to live clients.
+///
+/// The fields of the typed `LogEvent` are flattened into the object, next to the match it belongs to
+/// and, when known, the player it concerns and the scoreboard it changed, e.g.
+/// `{"match": 3, "timestamp": 1254, "type": "kill", "killer": "Zeh", ..., "scoreboard": [...]}`.
+#[derive(Debug, Serialize)]
+pub struct LiveEvent<'a> {
+    #[serde(rename = "match")]
+    pub match_id: i32,
+    pub timestamp: Option<u32>,
+    #[serde(flatten)]
+    pub event: LogEvent<'a>,
+    /// The name of the client of a connect, user info, begin, disconnect or item event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player: Option<String>,
+    /// The scoreboard of the match after a kill or a `score:` line.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scoreboard: Option<Vec<PlayerScore>>,
+}
+
+/// Parses the lines of a log as they are written, keeping only the match being played unless created
+/// with `LiveFeed::with_history`.
+pub struct LiveFeed {
+    options: ParseOptions,
+    /// The current match, if one started, preceded by the earlier ones when they are kept.
+    matches: Vec<Match>,
+    /// Whether the matches that ended are kept.
+    history: bool,
+    /// The number of matches started, including those before the feed was created.
+    started: i32,
+}
+
+/// Follows a log file as it grows, like `tail -f`.
+pub struct LogTail {
+    path: PathBuf,
+    offset: u64,
+    inode: Option<u64>,
+}
+
+impl LiveFeed {
+    /// Creates a feed for a log that already had `previous_matches` matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The `ParseOptions` to parse with.
+    /// * `previous_matches` - The number of matches before the first one the feed will see, so the
+    ///   matches are numbered as in the report of the whole log.
+    pub fn new(options: ParseOptions, previous_matches: i32) -> Self {
+        LiveFeed {
+            options,
+            matches: Vec::new(),
+            history: false,
+            started: previous_matches,
+        }
+    }
+
+    /// Creates a feed for a whole log that keeps every match, e.g. to serve them as they are played
+    /// without reading the log again (see `matches`).
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The `ParseOptions` to parse with.
+    pub fn with_history(options: ParseOptions) -> Self {
+        LiveFeed {
+            history: true,
+            ..LiveFeed::new(options, 0)
+        }
+    }
+
+    /// Returns the matches parsed so far, as `process_events_matches` would parse the lines processed:
+    /// only the match being played when the feed does not keep the others.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Match>` - A copy of the matches, with the sessions of the last one closed.
+    pub fn matches(&self) -> Vec<Match> {
+        let mut matches = self.matches.clone();
+        if let Some(last) = matches.last_mut() {
+            session::close_sessions(&mut last.data, self.options.name_policy);
+        }
+        matches
+    }
+
+    /// Processes a line of the log, returning the event to push to live clients, if any.
+    ///
+    /// Lines before the first `InitGame:` and lines of no interest (`LogEvent::Other`) are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A string containing the log line.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<LiveEvent>, LogError>` - A `Result` with the event of the line, or an error
+    ///   (`Err`) if the line cannot be parsed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` under the same conditions as `process_events_matches`.
+    pub fn process_line<'a>(&mut self, line: &'a str) -> Result<Option<LiveEvent<'a>>, LogError> {
+        let Some((parsed, current)) = self.advance(line)? else {
+            return Ok(None);
+        };
+        let client = match parsed.event {
+            LogEvent::ClientConnect { client_id }
+            | LogEvent::ClientBegin { client_id }
+            | LogEvent::ClientDisconnect { client_id }
+            | LogEvent::ClientUserinfoChanged { client_id, .. }
+            | LogEvent::Item { client_id, .. } => client_id,
+            _ => None,
+        };
+        let player = session_name(&current.data, client);
+        let scoreboard = matches!(parsed.event, LogEvent::Kill(_) | LogEvent::Score)
+            .then(|| running_scoreboard(&current.data));
+
+        Ok(Some(LiveEvent {
+            match_id: current.id,
+            timestamp: parsed.timestamp,
+            event: parsed.event,
+            player,
+            scoreboard,
+        }))
+    }
+
+    /// Returns the number of matches started so far, including those before the feed was created.
+    pub fn started(&self) -> i32 {
+        self.started
+    }
+
+    /// Parses a line and applies it to the current match, returning the parsed line with the match it
+    /// belongs to, or `None` for lines before the first `InitGame:` and lines of no interest.
+    ///
+    /// Lines of no interest are still applied, as their timestamps end the match like in
+    /// `process_events_matches`, e.g. the `-----` line of a match cut short.
+    pub(crate) fn advance<'a>(
+        &mut self,
+        line: &'a str,
+    ) -> Result<Option<(LogLine<'a>, &Match)>, LogError> {
+        let parsed = LogLine::parse(line);
+        let starts_match = matches!(parsed.event, LogEvent::InitGame { .. });
+        if self.matches.is_empty() && !starts_match {
+            return Ok(None);
+        }
+
+        let other = matches!(parsed.event, LogEvent::Other);
+        process_line(&mut self.matches, parsed, &self.options)?;
+        if starts_match {
+            self.started += 1;
+            if !self.history {
+                self.matches.drain(..self.matches.len() - 1);
+            }
+            if let Some(current) = self.matches.last_mut() {
+                current.id = self.started;
+            }
+        }
+        if other {
+            return Ok(None);
+        }
+        Ok(self.matches.last().map(|current| (parsed, current)))
+    }
+
+    /// Processes lines already in the log to catch up with the match being played, without producing
+    /// events. Lines that cannot be parsed are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - A string containing the lines to catch up with.
+    pub fn replay(&mut self, content: &str) {
+        for line in content.lines() {
+            let _ = self.process_line(line);
+        }
+    }
+}
+
+impl LogTail {
+    /// Starts following a log file from a byte offset, e.g. its current length.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the log file.
+    /// * `offset` - The offset of the first byte not read yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the file cannot be opened.
+    pub fn new(path: &Path, offset: u64) -> Result<Self, LogError> {
+        let metadata = path.metadata().map_err(|err| read_error(path, err))?;
+        Ok(LogTail {
+            path: path.to_path_buf(),
+            offset,
+            inode: inode(&metadata),
+        })
+    }
+
+    /// Reads the lines written since the last call.
+    ///
+    /// Only complete lines are returned; a line still being written is read by a later call. When the
+    /// file was truncated or replaced by a new one (log rotation), it is read again from the start.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, LogError>` - A `Result` with the new lines, empty when nothing was written, or
+    ///   an error (`Err`) if the file cannot be read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the file cannot be read or the new lines are not valid
+    /// UTF-8.
+    pub fn poll(&mut self) -> Result<String, LogError> {
+        let path = self.path.as_path();
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            // The log is being rotated; the new file will be read once it is created.
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(String::new()),
+            Err(err) => return Err(read_error(path, err)),
+        };
+        let metadata = file.metadata().map_err(|err| read_error(path, err))?;
+        let inode = inode(&metadata);
+        if inode != self.inode || metadata.len() < self.offset {
+            self.inode = inode;
+            self.offset = 0;
+        }
+
+        let mut bytes = Vec::new();
+        file.seek(SeekFrom::Start(self.offset))
+            .and_then(|_| file.read_to_end(&mut bytes))
+            .map_err(|err| read_error(path, err))?;
+        let complete_lines = bytes
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |end| end + 1);
+        bytes.truncate(complete_lines);
+        self.offset += complete_lines as u64;
+
+        String::from_utf8(bytes).map_err(|err| {
+            LogError::ReadLogError(format!(
+                "The log file {} is not valid UTF-8: {}",
+                path.display(),
+                err
+            ))
+        })
+    }
+}
+
+/// Builds the scoreboard of a match being played.
+///
+/// Kills are only credited to the names of the players once a match is over (see
+/// `session::close_sessions`), so until then they are added up from the sessions.
+fn running_scoreboard(match_data: &MatchData) -> Vec<PlayerScore> {
+    if !match_data.scores.is_empty() {
+        return final_scoreboard(match_data);
+    }
+
+    let mut kills = match_data.kills.clone();
+    for session in match_data.sessions.iter().filter(|s| !s.name.is_empty()) {
+        add_count(&mut kills, &session.name, session.kills);
+    }
+    let mut scoreboard: Vec<PlayerScore> = kills
+        .into_iter()
+        .map(|(name, kills)| PlayerScore { name, kills })
+        .collect();
+    scoreboard.sort_by(|a, b| b.kills.cmp(&a.kills).then_with(|| a.name.cmp(&b.name)));
+    scoreboard
+}
+
+/// Returns the name of the player in a client slot of a match, if any.
+pub(crate) fn session_name(data: &MatchData, client_id: Option<i32>) -> Option<String> {
+    let client_id = client_id?;
+    data.sessions
+        .iter()
+        .rev()
+        .find(|session| session.client_id == client_id && !session.name.is_empty())
+        .map(|session| session.name.clone())
+}
+
+fn read_error(path: &Path, err: io::Error) -> LogError {
+    LogError::ReadLogError(format!(
+        "Error reading the log file {}: {}",
+        path.display(),
+        err
+    ))
+}
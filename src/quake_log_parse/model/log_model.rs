@@ -1,32 +1,80 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet},
     env,
-    fs::read_to_string,
     path::PathBuf,
 };
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use super::error::LogError;
+use super::{
+    error::LogError,
+    event::{parse_settings, parse_userinfo_name, KillEvent, LogEvent, LogLine},
+    filter::{retain_matches, MatchFilter},
+    identity::{self, AliasMap},
+    input::read_log_file,
+    intern::Interner,
+    player_name::{strip_colors, PlayerName},
+    session::{self, NamePolicy, PlayerSession},
+};
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct MatchData {
     pub total_kills: i32,
-    pub players: HashSet<String>,
-    pub kills: HashMap<String, i32>,
-    pub kills_by_means: HashMap<String, i32>,
+    pub players: BTreeSet<String>,
+    pub kills: BTreeMap<String, i32>,
+    pub kills_by_means: BTreeMap<String, i32>,
+    pub scores: Vec<ServerScore>,
+    pub started_at: u32,
+    pub ended_at: u32,
+    pub sessions: Vec<PlayerSession>,
+    pub source_file: String,
+    pub source_id: i32,
+    /// The server settings of the match, from its `InitGame:` line (e.g. `mapname`, `g_gametype`).
+    pub settings: BTreeMap<String, String>,
+    /// Whether the match logged a `ShutdownGame:` line, rather than being cut short by the next match
+    /// or the end of the log.
+    pub completed: bool,
+    /// The kills of each player by themselves, counted in `kills` like any other kill although the
+    /// server takes a point off the score for them.
+    #[serde(default)]
+    pub self_kills: BTreeMap<String, i32>,
+}
+/// Options controlling how a log is parsed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    /// The name reported for players that renamed themselves during a match.
+    pub name_policy: NamePolicy,
 }
-#[derive(Serialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Match {
     pub id: i32,
     pub data: MatchData,
 }
-#[derive(Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PlayerScore {
     pub name: String,
     pub kills: i32,
 }
+/// A `score:` line written by the server when a match ends.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerScore {
+    pub client_id: i32,
+    pub name: String,
+    pub raw_name: String,
+    pub score: i32,
+    pub ping: i32,
+}
+/// A player whose score computed from `Kill:` events differs from the one reported by the server.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScoreDiscrepancy {
+    pub name: String,
+    pub computed: i32,
+    pub reported: i32,
+}
+/// The means of death of Quake 3, in the order of `meansOfDeath_t`, so the discriminant of each
+/// variant is the id written in `Kill:` lines.
 #[derive(Debug, PartialEq)]
+#[allow(clippy::enum_variant_names)]
 enum MeansOfDeath {
     ModUnknown,
     ModShotgun,
@@ -95,15 +143,30 @@ impl MeansOfDeath {
         }
     }
 }
+
+/// Returns the id of a means of death, e.g. `10` for `MOD_RAILGUN`, or `None` if the name is not a
+/// means of death of Quake 3.
+pub fn means_of_death_id(name: &str) -> Option<i32> {
+    MeansOfDeath::from_str(name).map(|means| means as i32)
+}
 /// Struct containing methods for working with log data.
 pub struct LogModel {}
 impl LogModel {
     /// Retrieves match data and player rankings from the matches log.
     ///
     /// This function reads the matches log file, processes its contents, and extracts information about
-    /// matches and player rankings. It returns the collected data as a tuple containing a vector
-    /// of `Match` structs representing the list of matches and a vector of `PlayerScore` structs
-    /// representing player rankings.
+    /// matches and player rankings. Players are ranked by identity, merging their aliases with the
+    /// optional alias file (see `identity::get_alias_path`). It returns the collected data as a tuple
+    /// containing a vector of `Match` structs representing the list of matches and a vector of
+    /// `PlayerScore` structs representing player rankings.
+    ///
+    /// Only the matches that meet `filter` are kept, before the players are ranked.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - A reference to the `MatchFilter` selecting the matches to report.
+    ///
+    /// * `options` - A reference to the `ParseOptions` to parse the log with.
     ///
     /// # Returns
     ///
@@ -116,13 +179,23 @@ impl LogModel {
     ///
     /// * An error occurs while reading the matches log file (`read_log()`).
     ///
+    /// * The alias file exists but cannot be read or parsed (`AliasMap::load`).
+    ///
     /// * An error occurs during event processing (`process_events_matches`) if there are issues with
     ///   parsing and updating match data.
-    pub fn process_log() -> Result<(Vec<Match>, Vec<PlayerScore>), LogError> {
+    pub fn process_log(
+        filter: &MatchFilter,
+        options: &ParseOptions,
+    ) -> Result<(Vec<Match>, Vec<PlayerScore>), LogError> {
         let mut matchs = Vec::new();
         let mut player_rank = Vec::new();
-        self::process_events_matches(&mut matchs, &read_log()?)?;
-        self::process_ranking(&mut matchs, &mut player_rank);
+        let aliases = match identity::get_alias_path() {
+            Some(path) => AliasMap::load(&path)?,
+            None => AliasMap::default(),
+        };
+        process_events_matches_with_options(&mut matchs, &read_log()?, options)?;
+        retain_matches(&mut matchs, filter);
+        identity::process_identity_ranking(&matchs, &aliases, &mut player_rank);
         Ok((matchs, player_rank))
     }
 }
@@ -130,12 +203,12 @@ impl LogModel {
 /// Processes events in a log file and updates the list of matches.
 ///
 /// This function iterates through log file lines and handles match initialization, player kills,
-/// and changes in player information, updating the list of matches accordingly.
+/// changes in player information and client sessions, updating the list of matches accordingly.
 ///
 /// # Arguments
 ///
 /// * `matches` - A mutable reference to a vector of `Match` structs representing the list of matches
-///              to be updated during event processing.
+///   to be updated during event processing.
 ///
 /// * `file_content` - A string containing the content of the log file to be processed for events.
 ///
@@ -151,48 +224,196 @@ impl LogModel {
 /// * An error occurs during match initialization (`process_init_game`).
 /// * An error occurs during processing a client connection line (`process_client_changed_line`).
 /// * An error occurs during processing a kill line (`process_kill_line`).
+/// * An error occurs during processing a final score line (`process_score_line`).
+/// * An error occurs during processing a connect, begin or disconnect line (`session`).
 pub fn process_events_matches(
     matches: &mut Vec<Match>,
     file_content: &str,
+) -> Result<(), LogError> {
+    process_events_matches_with_options(matches, file_content, &ParseOptions::default())
+}
+
+/// Processes events in a log file and updates the list of matches, using the given options.
+///
+/// Behaves like `process_events_matches`, with `options` deciding e.g. which name is reported for
+/// players that renamed themselves mid-match.
+///
+/// # Arguments
+///
+/// * `matches` - A mutable reference to a vector of `Match` structs representing the list of matches
+///   to be updated during event processing.
+///
+/// * `file_content` - A string containing the content of the log file to be processed for events.
+///
+/// * `options` - A reference to the `ParseOptions` to parse with.
+///
+/// # Returns
+///
+/// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if a problem
+///   is encountered during event processing.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` under the same conditions as `process_events_matches`.
+pub fn process_events_matches_with_options(
+    matches: &mut Vec<Match>,
+    file_content: &str,
+    options: &ParseOptions,
+) -> Result<(), LogError> {
+    process_events_chunk(matches, file_content, options)
+}
+
+/// Processes the events of a chunk of a log file, which may be followed by more matches.
+///
+/// A match ends with its own lines, so the result does not depend on where the log was split.
+pub(crate) fn process_events_chunk(
+    matches: &mut Vec<Match>,
+    file_content: &str,
+    options: &ParseOptions,
 ) -> Result<(), LogError> {
     for line in file_content.lines() {
-        match line {
-            s if s.contains("InitGame:") => {
-                if let Err(err) = process_init_game(matches) {
-                    return Err(LogError::InitGameError(format!(
+        process_line(matches, LogLine::parse(line), options)?;
+    }
+    if let Some(last) = matches.last_mut() {
+        session::close_sessions(&mut last.data, options.name_policy);
+    }
+    Ok(())
+}
+
+/// Processes a parsed line of a log file, updating the last match or starting a new one.
+///
+/// Sessions of a match are only closed when the next one starts; the caller closes the sessions of
+/// the last match once it has no more lines. A match ends at its `ShutdownGame:` line or, when cut
+/// short, at its last line, never at the `InitGame:` line of the next match.
+pub(crate) fn process_line(
+    matches: &mut Vec<Match>,
+    line: LogLine,
+    options: &ParseOptions,
+) -> Result<(), LogError> {
+    let LogLine {
+        text: s,
+        timestamp,
+        event,
+    } = line;
+    if let (Some(current), Some(timestamp)) = (matches.last_mut(), timestamp) {
+        if !current.data.completed && !matches!(event, LogEvent::InitGame { .. }) {
+            current.data.ended_at = timestamp;
+        }
+    }
+
+    match event {
+        LogEvent::InitGame { settings } => {
+            if let Some(previous) = matches.last_mut() {
+                session::close_sessions(&mut previous.data, options.name_policy);
+            }
+            if let Err(err) = process_init_game(matches) {
+                return Err(LogError::InitGameError(format!(
+                    "Error {:?} on line  {}",
+                    err, s
+                )));
+            }
+            if let Some(current) = matches.last_mut() {
+                current.data.settings = parse_settings(settings)
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect();
+                if let Some(timestamp) = timestamp {
+                    current.data.started_at = timestamp;
+                    current.data.ended_at = timestamp;
+                }
+            }
+        }
+        // Like the other events, user info and kills logged before the first `InitGame:` belong to
+        // no match and are ignored (`validate::validate_log` reports them).
+        LogEvent::ClientUserinfoChanged { .. } => {
+            if let Some(current) = matches.last_mut() {
+                if let Err(err) = process_client_changed_line(s, &mut current.data) {
+                    return Err(LogError::ClientUserinfoChangedError(format!(
                         "Error {:?} on line  {}",
                         err, s
                     )));
                 }
             }
-            s if s.contains("ClientUserinfoChanged") => {
-                let idx = matches.len();
-                if let Err(err) = process_client_changed_line(s, &mut matches[idx - 1].data) {
-                    return Err(LogError::ClientUserinfoChangedError(format!(
+        }
+        LogEvent::Kill(kill) => {
+            if let Some(current) = matches.last_mut() {
+                if let Err(err) = process_kill_event(s, &kill, timestamp, &mut current.data) {
+                    return Err(LogError::KillError(format!(
                         "Error {:?} on line  {}",
                         err, s
                     )));
                 }
             }
-            s if s.contains("Kill:") => {
-                let idx = matches.len();
-                if let Err(err) = process_kill_line(s, &mut matches[idx - 1].data) {
-                    return Err(LogError::KillError(format!(
+        }
+        LogEvent::ClientConnect { .. } => {
+            if let Some(current) = matches.last_mut() {
+                if let Err(err) = session::process_connect_line(s, &mut current.data) {
+                    return Err(LogError::SessionError(format!(
                         "Error {:?} on line  {}",
                         err, s
                     )));
                 }
             }
-
-            _ => {}
         }
+        LogEvent::ClientBegin { .. } => {
+            if let Some(current) = matches.last_mut() {
+                if let Err(err) = session::process_begin_line(s, &mut current.data) {
+                    return Err(LogError::SessionError(format!(
+                        "Error {:?} on line  {}",
+                        err, s
+                    )));
+                }
+            }
+        }
+        LogEvent::ClientDisconnect { .. } => {
+            if let Some(current) = matches.last_mut() {
+                if let Err(err) = session::process_disconnect_line(s, &mut current.data) {
+                    return Err(LogError::SessionError(format!(
+                        "Error {:?} on line  {}",
+                        err, s
+                    )));
+                }
+            }
+        }
+        LogEvent::Score => {
+            if let Some(current) = matches.last_mut() {
+                if let Err(err) = process_score_line(s, &mut current.data) {
+                    return Err(LogError::ScoreError(format!(
+                        "Error {:?} on line  {}",
+                        err, s
+                    )));
+                }
+            }
+        }
+        LogEvent::ShutdownGame => {
+            if let Some(current) = matches.last_mut() {
+                current.data.completed = true;
+            }
+        }
+        LogEvent::Item { .. } | LogEvent::Say { .. } | LogEvent::Other => {}
     }
     Ok(())
 }
+/// Parses the timestamp at the beginning of a log line.
+///
+/// Quake 3 prefixes every line with the time elapsed since the server started, as `minutes:seconds`
+/// (e.g. ` 20:37 ClientBegin: 2`). Minutes are not limited to 59.
+///
+/// # Arguments
+///
+/// * `line` - A string containing the log line.
+///
+/// # Returns
+///
+/// * `Option<u32>` - The timestamp in seconds, or `None` if the line does not start with one.
+pub fn parse_timestamp(line: &str) -> Option<u32> {
+    let (minutes, seconds) = line.split_whitespace().next()?.split_once(':')?;
+    Some(minutes.parse::<u32>().ok()? * 60 + seconds.parse::<u32>().ok()?)
+}
 /// Reads the content of the log file and returns it as a string.
 ///
 /// This function reads the content of the log file named "qgames.log" located in the same
-/// directory as the executable and returns it as a string.
+/// directory as the executable and returns it as a string. The file may be compressed with gzip,
+/// zstd or bzip2 (see `input::read_log_file`).
 ///
 /// # Returns
 ///
@@ -205,13 +426,7 @@ pub fn process_events_matches(
 ///
 /// * The log file does not exist or cannot be read.
 pub fn read_log() -> Result<String, LogError> {
-    match read_to_string(get_log_path()?) {
-        Ok(file_content) => Ok(file_content),
-        Err(err) => Err(LogError::ReadLogError(format!(
-            "Error reading the log file: {}",
-            err
-        ))),
-    }
+    read_log_file(&get_log_path()?)
 }
 /// Retrieves the path of the log file.
 ///
@@ -241,8 +456,8 @@ pub fn get_log_path() -> Result<PathBuf, LogError> {
     path_log.push("qgames.log");
 
     match path_log.exists() {
-        true =>  return Ok(path_log),
-        false =>  Err(LogError::ReadLogError(format!("Error while retrieving the log directory, please check if the qgames.log file is present in the directory: {}",current_exe.to_string_lossy().to_string()) )),
+        true =>  Ok(path_log),
+        false =>  Err(LogError::ReadLogError(format!("Error while retrieving the log directory, please check if the qgames.log file is present in the directory: {}",current_exe.to_string_lossy()) )),
     }
 }
 /// Processes the initialization of a new match and adds it to the list of matches.
@@ -293,17 +508,18 @@ pub fn process_init_game(matches: &mut Vec<Match>) -> Result<(), LogError> {
 /// * The line does not contain the expected format, e.g., "n\player_name\t\".
 /// * The extracted player name is empty.
 pub fn process_client_changed_line(line: &str, match_data: &mut MatchData) -> Result<(), LogError> {
-    if let Some(start) = line.find("n\\") {
-        if let Some(end) = line.find("\\t\\") {
-            let player_name = &line[start + 2..end];
-
-            if !player_name.is_empty() {
-                match_data.players.insert(player_name.to_string());
-            } else {
-                return Err(LogError::EmptyPlayerName(format!(
-                    "Empty player name in the client changed line."
-                )));
+    if let Some(name) = parse_userinfo_name(line) {
+        let player_name = PlayerName::new(name);
+
+        if !player_name.raw.is_empty() {
+            if !match_data.players.contains(&player_name.clean) {
+                match_data.players.insert(player_name.clean.clone());
             }
+            session::name_session(line, &player_name, match_data);
+        } else {
+            return Err(LogError::EmptyPlayerName(
+                "Empty player name in the client changed line.".to_string(),
+            ));
         }
     }
 
@@ -328,22 +544,13 @@ pub fn process_client_changed_line(line: &str, match_data: &mut MatchData) -> Re
 ///
 /// * The victim in the kill line is not found in the matches kill data.
 pub fn parse_world_kill(line: &str, match_data: &mut MatchData) -> Result<(), LogError> {
-    if let Some(victim) = match_data
-        .players
-        .iter()
-        .find(|&victim| line.contains(&format!("<world> killed {} by", victim)))
-        .map(|victim| victim)
-    {
-        match_data
-            .kills
-            .entry(victim.to_owned())
-            .and_modify(|e| *e -= 1)
-            .or_insert(-1);
-    } else {
-        return Err(LogError::VictimName(format!(
-            "Unable to find the Victim Name"
-        )));
-    }
+    let victim = find_player(
+        &match_data.players,
+        KillEvent::parse(line).victim,
+        |victim| mentions(line, "<world> killed ", victim, " by"),
+    )
+    .ok_or_else(|| LogError::VictimName("Unable to find the Victim Name".to_string()))?;
+    add_count(&mut match_data.kills, victim, -1);
 
     Ok(())
 }
@@ -369,24 +576,50 @@ pub fn parse_world_kill(line: &str, match_data: &mut MatchData) -> Result<(), Lo
 ///
 /// * Unable to find the Killer Name
 pub fn parse_player_kill(line: &str, match_data: &mut MatchData) -> Result<(), LogError> {
-    if let Some(nome) = match_data
-        .players
-        .iter()
-        .find(|&nome| line.contains(&format!("{} killed", nome)))
-        .map(|nome| nome)
-    {
-        match_data
-            .kills
-            .entry(nome.to_string())
-            .and_modify(|e| *e += 1)
-            .or_insert(1);
-    } else {
-        return Err(LogError::KillerName(format!(
-            "Unable to find the Killer Name"
-        )));
+    let killer = find_player(
+        &match_data.players,
+        KillEvent::parse(line).killer,
+        |killer| mentions(line, "", killer, " killed"),
+    )
+    .ok_or_else(|| LogError::KillerName("Unable to find the Killer Name".to_string()))?;
+    add_count(&mut match_data.kills, killer, 1);
+    if KillEvent::parse(line).victim == Some(killer) {
+        add_count(&mut match_data.self_kills, killer, 1);
     }
+
     Ok(())
 }
+
+/// Finds a player of the match by the name parsed from a kill line, or else by a player mentioned in
+/// the line (for names the line cannot be split on, e.g. containing ` killed `).
+fn find_player<'p>(
+    players: &'p BTreeSet<String>,
+    parsed: Option<&str>,
+    mentioned: impl Fn(&str) -> bool,
+) -> Option<&'p str> {
+    parsed
+        .and_then(|name| players.get(name))
+        .or_else(|| players.iter().find(|player| mentioned(player)))
+        .map(String::as_str)
+}
+
+/// Returns `true` if the line contains `name` between `prefix` and `suffix`, without building the
+/// searched string.
+fn mentions(line: &str, prefix: &str, name: &str, suffix: &str) -> bool {
+    line.match_indices(name).any(|(idx, _)| {
+        line[..idx].ends_with(prefix) && line[idx + name.len()..].starts_with(suffix)
+    })
+}
+
+/// Adds `delta` to the count of `key`, only allocating the key the first time it is counted.
+pub(crate) fn add_count(counts: &mut BTreeMap<String, i32>, key: &str, delta: i32) {
+    match counts.get_mut(key) {
+        Some(count) => *count += delta,
+        None => {
+            counts.insert(key.to_string(), delta);
+        }
+    }
+}
 /// Inserts or increments the count of kills by means in a match.
 ///
 /// This function takes a log line and extracts the "mean" (typically a weapon or cause) of a kill from
@@ -411,7 +644,16 @@ pub fn parse_player_kill(line: &str, match_data: &mut MatchData) -> Result<(), L
 ///
 /// Returns an error of type `LogError` if the extracted word is not a valid 'mean'.
 pub fn insert_kills_by_means(line: &str, match_data: &mut MatchData) -> Result<(), LogError> {
-    let last_word = match line.split_whitespace().last() {
+    count_means(line, line.split_whitespace().last(), match_data)
+}
+
+/// Counts the means of death of a kill line, already extracted as the last word of the line.
+fn count_means(
+    line: &str,
+    means: Option<&str>,
+    match_data: &mut MatchData,
+) -> Result<(), LogError> {
+    let last_word = match means {
         Some(last_word) => last_word,
         None => {
             return Err(LogError::InsertKillMeanError(format!(
@@ -422,13 +664,7 @@ pub fn insert_kills_by_means(line: &str, match_data: &mut MatchData) -> Result<(
     };
 
     match MeansOfDeath::from_str(last_word) {
-        Some(_) => {
-            match_data
-                .kills_by_means
-                .entry(last_word.to_string())
-                .and_modify(|e| *e += 1)
-                .or_insert(1);
-        }
+        Some(_) => add_count(&mut match_data.kills_by_means, last_word, 1),
         None => {
             return Err(LogError::InsertKillMeanError(format!(
                 r#"Mean '{}' not recognized as a valid means of death: {}"#,
@@ -442,9 +678,11 @@ pub fn insert_kills_by_means(line: &str, match_data: &mut MatchData) -> Result<(
 /// Process a kill line from the match log.
 ///
 /// This function is responsible for processing a line from the match log that represents a kill event.
-/// It determines whether the kill event is a player kill or a kill by the world (e.g., environmental damage).
-/// Depending on the type of kill event, it calls the corresponding parsing function (`parse_world_kill` or
-/// `parse_player_kill`) to extract relevant information about the kill. It also updates the kills statistics
+/// Kills between client slots with a known session are attributed to the slots by client id
+/// (`session::attribute_kill`), so renamed players keep their stats. Otherwise it determines whether the
+/// kill event is a player kill or a kill by the world (e.g., environmental damage) and calls the
+/// corresponding parsing function (`parse_world_kill` or `parse_player_kill`) to extract relevant
+/// information about the kill. It also updates the kills statistics
 /// in the `MatchData` struct and records the means of death for later analysis.
 ///
 /// # Arguments
@@ -464,23 +702,33 @@ pub fn insert_kills_by_means(line: &str, match_data: &mut MatchData) -> Result<(
 /// Returns an error of type `LogError` if there are any issues with processing the kill event or updating
 /// the match data.
 pub fn process_kill_line(line: &str, match_data: &mut MatchData) -> Result<(), LogError> {
-    match line {
-        _ if line.contains("<world> killed") => {
-            if let Err(err) = parse_world_kill(line, match_data) {
-                return Err(err);
-            }
-        }
-        _ => {
-            if let Err(err) = parse_player_kill(line, match_data) {
-                return Err(err);
-            }
-        }
-    };
+    process_kill_event(
+        line,
+        &KillEvent::parse(line),
+        parse_timestamp(line),
+        match_data,
+    )
+}
 
-    if let Err(err) = insert_kills_by_means(line, match_data) {
-        return Err(err);
+/// Processes a kill line already parsed into a `KillEvent`, see `process_kill_line`.
+fn process_kill_event(
+    line: &str,
+    kill: &KillEvent,
+    timestamp: Option<u32>,
+    match_data: &mut MatchData,
+) -> Result<(), LogError> {
+    if !session::attribute_kill(kill, timestamp, match_data) {
+        // Players are known by their names without colour codes.
+        let clean_line = strip_colors(line);
+        if clean_line.contains("<world> killed") {
+            parse_world_kill(&clean_line, match_data)?;
+        } else {
+            parse_player_kill(&clean_line, match_data)?;
+        }
     }
 
+    count_means(line, kill.means, match_data)?;
+
     match_data.total_kills += 1;
 
     Ok(())
@@ -499,27 +747,155 @@ pub fn process_kill_line(line: &str, match_data: &mut MatchData) -> Result<(), L
 /// * `matches` - A mutable reference to a vector of `Match` structs containing match data.
 ///
 /// * `ranking` - A mutable reference to a vector of `PlayerScore` structs representing player rankings.
-pub fn process_ranking(matches: &mut Vec<Match>, ranking: &mut Vec<PlayerScore>) {
-    let mut player_set: HashSet<&str> = HashSet::new();
+pub fn process_ranking(matches: &mut [Match], ranking: &mut Vec<PlayerScore>) {
+    // Players are interned in order of appearance, so their totals live in a table indexed by symbol.
+    let mut players = Interner::new();
+    let mut totals: Vec<i32> = Vec::new();
 
     for mat in matches.iter() {
         for (player, kills) in &mat.data.kills {
-            if player_set.contains(player.as_str()) {
-                // The player is already in the ranking, update their score.
-                if let Some(player_entry) = ranking.iter_mut().find(|entry| entry.name == *player) {
-                    player_entry.kills += kills;
-                }
-            } else {
-                // The player is not in the ranking, add them.
-                ranking.push(PlayerScore {
-                    name: player.clone(),
-                    kills: *kills,
-                });
-                player_set.insert(player.as_str());
+            let symbol = players.intern(player);
+            if symbol.index() == totals.len() {
+                totals.push(0);
             }
+            totals[symbol.index()] += kills;
         }
     }
 
-    // Sorts the Vec in descending order of kills.
-    ranking.sort_by(|a, b| b.kills.cmp(&a.kills));
+    ranking.extend(players.iter().map(|(symbol, name)| PlayerScore {
+        name: name.to_string(),
+        kills: totals[symbol.index()],
+    }));
+
+    // Sorts the Vec in descending order of kills, and players with the same kills by name.
+    ranking.sort_by(|a, b| b.kills.cmp(&a.kills).then_with(|| a.name.cmp(&b.name)));
+}
+
+/// Parses a final `score:` line and stores the server's score for the player.
+///
+/// At the end of a match the server logs one line per connected client, e.g.
+/// `20:37 score: 20  ping: 4  client: 5 Assasinu Credi`.
+///
+/// # Arguments
+///
+/// * `line` - A string containing the log line to be parsed.
+/// * `match_data` - A mutable reference to the `MatchData` struct representing the match state.
+///
+/// # Returns
+///
+/// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if a problem
+///   is encountered during parsing.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if any of the following conditions are met:
+///
+/// * The line does not contain the `score:`, `ping:` and `client:` fields.
+/// * The score, ping or client id is not a number.
+/// * The player name is empty.
+pub fn process_score_line(line: &str, match_data: &mut MatchData) -> Result<(), LogError> {
+    let malformed = || LogError::ScoreError(format!("Malformed score line: {}", line));
+
+    let start = line.find("score:").ok_or_else(malformed)?;
+    let (score, rest) = line[start + 6..]
+        .split_once("ping:")
+        .ok_or_else(malformed)?;
+    let (ping, rest) = rest.split_once("client:").ok_or_else(malformed)?;
+    let (client_id, name) = rest.trim_start().split_once(' ').ok_or_else(malformed)?;
+
+    let parse_number = |value: &str| value.trim().parse::<i32>().map_err(|_| malformed());
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(LogError::EmptyPlayerName(
+            "Empty player name in the score line.".to_string(),
+        ));
+    }
+
+    let name = PlayerName::new(name);
+    match_data.scores.push(ServerScore {
+        client_id: parse_number(client_id)?,
+        name: name.clean,
+        raw_name: name.raw,
+        score: parse_number(score)?,
+        ping: parse_number(ping)?,
+    });
+
+    Ok(())
+}
+
+/// Builds the final scoreboard of a match.
+///
+/// The scores reported by the server in the `score:` lines are authoritative, so they are used when
+/// present. Matches that ended without them (e.g. the server was shut down mid-game) fall back to the
+/// kills computed from the `Kill:` events.
+///
+/// # Arguments
+///
+/// * `match_data` - A reference to the `MatchData` struct representing the match.
+///
+/// # Returns
+///
+/// * `Vec<PlayerScore>` - The scoreboard sorted in descending order of score, ties broken by name.
+pub fn final_scoreboard(match_data: &MatchData) -> Vec<PlayerScore> {
+    let mut scoreboard: Vec<PlayerScore> = if match_data.scores.is_empty() {
+        match_data
+            .kills
+            .iter()
+            .map(|(name, kills)| PlayerScore {
+                name: name.clone(),
+                kills: *kills,
+            })
+            .collect()
+    } else {
+        match_data
+            .scores
+            .iter()
+            .map(|score| PlayerScore {
+                name: score.name.clone(),
+                kills: score.score,
+            })
+            .collect()
+    };
+
+    scoreboard.sort_by(|a, b| b.kills.cmp(&a.kills).then_with(|| a.name.cmp(&b.name)));
+    scoreboard
+}
+
+/// Cross-checks the score computed from `Kill:` events against the score reported by the server.
+///
+/// Only players present in the server scoreboard are compared, since players that disconnected before
+/// the end of the match are not reported. Players are matched by client slot when the match has
+/// sessions, and by name otherwise. A player without any computed kill counts as zero.
+///
+/// The computed score counts self-kills as the server does, taking a point off instead of adding one.
+///
+/// # Arguments
+///
+/// * `match_data` - A reference to the `MatchData` struct representing the match.
+///
+/// # Returns
+///
+/// * `Vec<ScoreDiscrepancy>` - One entry for each player whose scores differ, in scoreboard order.
+pub fn reconcile_scores(match_data: &MatchData) -> Vec<ScoreDiscrepancy> {
+    match_data
+        .scores
+        .iter()
+        .filter_map(|score| {
+            // Renamed players are credited under the name picked by the `NamePolicy`, which may not be
+            // the one shown by the server, so look them up by client slot first.
+            let name = match_data
+                .sessions
+                .iter()
+                .rev()
+                .find(|s| s.client_id == score.client_id && s.disconnected_at.is_none())
+                .map_or(score.name.as_str(), |session| session.name.as_str());
+            let self_kills = match_data.self_kills.get(name).copied().unwrap_or(0);
+            let computed = match_data.kills.get(name).copied().unwrap_or(0) - 2 * self_kills;
+            (computed != score.score).then(|| ScoreDiscrepancy {
+                name: score.name.clone(),
+                computed,
+                reported: score.score,
+            })
+        })
+        .collect()
 }
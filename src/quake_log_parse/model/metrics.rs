@@ -0,0 +1,134 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{
+    error::LogError,
+    event::{parse_settings, LogEvent},
+    live::{LiveEvent, LiveFeed},
+    log_model::ParseOptions,
+};
+
+/// The labels of a series: the server and the map of the match a line belongs to, from the
+/// `sv_hostname` and `mapname` settings of its `InitGame:` line. Both are empty before the first
+/// match of a log.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ServerLabels {
+    pub hostname: String,
+    pub map: String,
+}
+
+/// Counters of the lines processed, for monitoring game servers (see `MetricsView`).
+///
+/// Every series is labelled by the `ServerLabels` of the match being played.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    pub lines_processed: BTreeMap<ServerLabels, u64>,
+    pub matches_started: BTreeMap<ServerLabels, u64>,
+    /// Matches that logged a `ShutdownGame:` line.
+    pub matches_finished: BTreeMap<ServerLabels, u64>,
+    /// Matches followed by the next one without a `ShutdownGame:` line, e.g. when the server crashed.
+    pub matches_aborted: BTreeMap<ServerLabels, u64>,
+    /// The `ClientConnect:` lines.
+    pub player_connections: BTreeMap<ServerLabels, u64>,
+    /// The clients connected to the match being played on each server.
+    pub players_connected: BTreeMap<ServerLabels, u64>,
+    /// The kills by means of death.
+    pub kills: BTreeMap<(ServerLabels, String), u64>,
+    /// The lines that could not be parsed, by `LogError::kind`.
+    pub parse_errors: BTreeMap<(ServerLabels, &'static str), u64>,
+    /// The labels of the match being played.
+    server: ServerLabels,
+    /// Whether the match being played has not logged a `ShutdownGame:` line yet.
+    playing: bool,
+    /// The client ids connected to the match being played.
+    clients: BTreeSet<i32>,
+}
+
+impl Metrics {
+    /// Records a line of a log, as processed by a `LiveFeed`.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of `LiveFeed::process_line` for the line.
+    pub fn record(&mut self, result: &Result<Option<LiveEvent>, LogError>) {
+        match result {
+            Ok(Some(event)) => self.record_event(&event.event),
+            Ok(None) => {}
+            Err(err) => *counter(&mut self.parse_errors, (self.server.clone(), err.kind())) += 1,
+        }
+        *counter(&mut self.lines_processed, self.server.clone()) += 1;
+    }
+
+    /// Records every line of a log, which is processed as a whole.
+    ///
+    /// The match played at the end of the log is neither finished nor aborted, so the metrics of
+    /// several logs, e.g. of different servers, can be recorded one after the other.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - A string containing the content of the log file.
+    /// * `options` - The `ParseOptions` to parse with.
+    pub fn record_log(&mut self, content: &str, options: ParseOptions) {
+        let mut feed = LiveFeed::new(options, 0);
+        self.server = ServerLabels::default();
+        self.playing = false;
+        self.clients.clear();
+        for line in content.lines() {
+            self.record(&feed.process_line(line));
+        }
+    }
+
+    fn record_event(&mut self, event: &LogEvent) {
+        match event {
+            LogEvent::InitGame { settings } => {
+                if self.playing {
+                    *counter(&mut self.matches_aborted, self.server.clone()) += 1;
+                }
+                self.players_connected.remove(&self.server);
+                self.clients.clear();
+
+                let mut server = ServerLabels::default();
+                for (key, value) in parse_settings(settings) {
+                    match key {
+                        "sv_hostname" => server.hostname = value.to_string(),
+                        "mapname" => server.map = value.to_string(),
+                        _ => {}
+                    }
+                }
+                self.server = server;
+                self.playing = true;
+                *counter(&mut self.matches_started, self.server.clone()) += 1;
+                self.players_connected.insert(self.server.clone(), 0);
+            }
+            LogEvent::ShutdownGame => {
+                if self.playing {
+                    *counter(&mut self.matches_finished, self.server.clone()) += 1;
+                }
+                self.playing = false;
+            }
+            LogEvent::ClientConnect { client_id } => {
+                *counter(&mut self.player_connections, self.server.clone()) += 1;
+                if let Some(client_id) = client_id {
+                    self.clients.insert(*client_id);
+                }
+                self.players_connected
+                    .insert(self.server.clone(), self.clients.len() as u64);
+            }
+            LogEvent::ClientDisconnect { client_id } => {
+                if let Some(client_id) = client_id {
+                    self.clients.remove(client_id);
+                }
+                self.players_connected
+                    .insert(self.server.clone(), self.clients.len() as u64);
+            }
+            LogEvent::Kill(kill) => {
+                let means = kill.means.unwrap_or_default().to_string();
+                *counter(&mut self.kills, (self.server.clone(), means)) += 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn counter<K: Ord>(counters: &mut BTreeMap<K, u64>, key: K) -> &mut u64 {
+    counters.entry(key).or_insert(0)
+}
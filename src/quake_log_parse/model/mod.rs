@@ -1,2 +1,24 @@
 pub mod log_model;
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod session;
+pub mod identity;
+pub mod player_name;
+pub mod sources;
+pub mod input;
+pub mod parallel;
+pub mod event;
+pub mod intern;
+pub mod generator;
+pub mod storage;
+pub mod checkpoint;
+pub mod filter;
+pub mod stats;
+pub mod live;
+pub mod metrics;
+pub mod query;
+pub mod browser;
+pub mod repl;
+pub mod report;
+pub mod export;
+pub mod diff;
+pub mod validate;
\ No newline at end of file
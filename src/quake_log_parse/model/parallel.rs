@@ -0,0 +1,163 @@
+use std::thread;
+
+use super::{
+    error::LogError,
+    event::{LogEvent, LogLine},
+    log_model::{process_events_chunk, process_events_matches_with_options, Match, ParseOptions},
+};
+
+/// Splits the content of a log into at most `parts` chunks of similar size, each starting at a match.
+///
+/// The first chunk starts at the beginning of the log and every other one at an `InitGame:` line as
+/// recognised by `LogLine::parse`, not e.g. a chat message mentioning it, so no match is ever split
+/// between two chunks. Concatenating the chunks gives back the whole log. A log with fewer matches
+/// than `parts` is split into fewer chunks.
+///
+/// # Arguments
+///
+/// * `content` - A string containing the content of the log file.
+/// * `parts` - The number of chunks wanted.
+///
+/// # Returns
+///
+/// * `Vec<&str>` - The chunks of the log, in order.
+pub fn split_at_matches(content: &str, parts: usize) -> Vec<&str> {
+    let mut bounds = vec![0];
+
+    for part in 1..parts {
+        let target = content.len() / parts * part;
+        let last = *bounds.last().unwrap_or(&0);
+        if target <= last {
+            continue;
+        }
+        match next_match_start(content, target) {
+            Some(start) if start > last => bounds.push(start),
+            Some(_) => {}
+            None => break,
+        }
+    }
+    bounds.push(content.len());
+
+    bounds
+        .windows(2)
+        .map(|bound| &content[bound[0]..bound[1]])
+        .filter(|chunk| !chunk.is_empty())
+        .collect()
+}
+
+/// Splits the content of a log into the text of each match, with its byte offset in the content.
+///
+/// Each match goes from its `InitGame:` line to the next one, or to the end of the content. Lines
+/// before the first match are not part of any.
+///
+/// # Arguments
+///
+/// * `content` - A string containing the content of the log file.
+///
+/// # Returns
+///
+/// * `Vec<(usize, &str)>` - The offset and text of each match, in order.
+pub fn match_chunks(content: &str) -> Vec<(usize, &str)> {
+    let mut starts = Vec::new();
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if matches!(LogLine::parse(line).event, LogEvent::InitGame { .. }) {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(idx, &start)| {
+            let end = starts.get(idx + 1).copied().unwrap_or(content.len());
+            (start, &content[start..end])
+        })
+        .collect()
+}
+
+/// Processes events in a log file on several threads and updates the list of matches.
+///
+/// The log is split at match boundaries (`split_at_matches`) and each chunk is parsed on its own
+/// thread. Matches do not share any state, so the chunks are merged back in order and renumbered, and
+/// the result is exactly the one of `process_events_matches_with_options`, including the error
+/// returned and the matches parsed before it when a line cannot be parsed.
+///
+/// # Arguments
+///
+/// * `matches` - A mutable reference to a vector of `Match` structs representing the list of matches
+///   to be updated during event processing.
+///
+/// * `file_content` - A string containing the content of the log file to be processed for events.
+///
+/// * `options` - A reference to the `ParseOptions` to parse with.
+///
+/// * `threads` - The number of threads to parse with.
+///
+/// # Returns
+///
+/// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if a problem
+///   is encountered during event processing.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` under the same conditions as `process_events_matches`; when
+/// several chunks fail, the error of the first line in the log is returned.
+pub fn process_events_matches_parallel(
+    matches: &mut Vec<Match>,
+    file_content: &str,
+    options: &ParseOptions,
+    threads: usize,
+) -> Result<(), LogError> {
+    let chunks = split_at_matches(file_content, threads);
+    let Some((first, rest)) = chunks.split_first() else {
+        return process_events_matches_with_options(matches, file_content, options);
+    };
+
+    // The first chunk continues the matches given by the caller, the others start new lists.
+    let (first_result, rest_results) = thread::scope(|scope| {
+        let handles: Vec<_> = rest
+            .iter()
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut chunk_matches = Vec::new();
+                    let result = process_events_chunk(&mut chunk_matches, chunk, options);
+                    (chunk_matches, result)
+                })
+            })
+            .collect();
+        let first_result = process_events_chunk(matches, first, options);
+        let rest_results: Vec<_> = handles
+            .into_iter()
+            .map(|handle| handle.join().expect("log parsing thread panicked"))
+            .collect();
+        (first_result, rest_results)
+    });
+
+    first_result?;
+    for (chunk_matches, result) in rest_results {
+        for mut mat in chunk_matches {
+            mat.id = matches.len() as i32 + 1;
+            matches.push(mat);
+        }
+        result?;
+    }
+    Ok(())
+}
+
+/// Finds the start of the first `InitGame:` line after the line holding the byte `from`.
+fn next_match_start(content: &str, from: usize) -> Option<usize> {
+    let newline = content.as_bytes()[from..]
+        .iter()
+        .position(|&b| b == b'\n')?;
+    let mut start = from + newline + 1;
+
+    for line in content[start..].split_inclusive('\n') {
+        if matches!(LogLine::parse(line).event, LogEvent::InitGame { .. }) {
+            return Some(start);
+        }
+        start += line.len();
+    }
+    None
+}
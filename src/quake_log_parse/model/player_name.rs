@@ -0,0 +1,178 @@
+use std::borrow::Cow;
+
+use serde::Serialize;
+
+/// Character that starts a colour code in Quake 3 strings, e.g. `^1Red^7Player`.
+const COLOR_ESCAPE: char = '^';
+
+/// The eight colours of the Quake 3 palette, indexed by the digit of a `^N` colour code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum QuakeColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Cyan,
+    Magenta,
+    White,
+}
+
+/// A player name as written in the log, along with the name without colour codes.
+///
+/// The clean name identifies the player everywhere in the reports, so the same player using different
+/// colours is not split. The raw name is kept to render the colours.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct PlayerName {
+    pub raw: String,
+    pub clean: String,
+}
+
+impl QuakeColor {
+    /// Returns the colour selected by the character following `^`.
+    ///
+    /// Like the game, only the low three bits of the character are used, so `^9` is the same as `^1`.
+    pub fn from_code(code: char) -> Self {
+        match (code as u32).wrapping_sub('0' as u32) & 7 {
+            0 => QuakeColor::Black,
+            1 => QuakeColor::Red,
+            2 => QuakeColor::Green,
+            3 => QuakeColor::Yellow,
+            4 => QuakeColor::Blue,
+            5 => QuakeColor::Cyan,
+            6 => QuakeColor::Magenta,
+            _ => QuakeColor::White,
+        }
+    }
+
+    /// Returns the ANSI SGR foreground code of the colour.
+    pub fn ansi_code(self) -> u8 {
+        30 + self as u8
+    }
+
+    /// Returns the CSS colour of the colour, as drawn by the game.
+    pub fn css(self) -> &'static str {
+        match self {
+            QuakeColor::Black => "#000000",
+            QuakeColor::Red => "#ff0000",
+            QuakeColor::Green => "#00ff00",
+            QuakeColor::Yellow => "#ffff00",
+            QuakeColor::Blue => "#0000ff",
+            QuakeColor::Cyan => "#00ffff",
+            QuakeColor::Magenta => "#ff00ff",
+            QuakeColor::White => "#ffffff",
+        }
+    }
+}
+
+impl PlayerName {
+    /// Normalises a raw name from the log.
+    ///
+    /// A name made only of colour codes keeps its raw form as the clean name, so it never becomes empty.
+    pub fn new(raw: &str) -> Self {
+        let clean = strip_colors(raw);
+        PlayerName {
+            raw: raw.to_string(),
+            clean: if clean.is_empty() {
+                raw.to_string()
+            } else {
+                clean.into_owned()
+            },
+        }
+    }
+
+    /// Splits the raw name into runs of text sharing the same colour.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(Option<QuakeColor>, String)>` - Each run with its colour, `None` for the text before the
+    ///   first colour code.
+    pub fn segments(&self) -> Vec<(Option<QuakeColor>, String)> {
+        let mut segments: Vec<(Option<QuakeColor>, String)> = Vec::new();
+        let mut color = None;
+        let mut chars = self.raw.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match chars.peek() {
+                Some(&code) if c == COLOR_ESCAPE && code.is_ascii_alphanumeric() => {
+                    chars.next();
+                    color = Some(QuakeColor::from_code(code));
+                }
+                _ => match segments.last_mut() {
+                    Some((last_color, text)) if *last_color == color => text.push(c),
+                    _ => segments.push((color, c.to_string())),
+                },
+            }
+        }
+        segments
+    }
+
+    /// Renders the name with ANSI escape sequences for a terminal.
+    pub fn to_ansi(&self) -> String {
+        let segments = self.segments();
+        let mut rendered = String::new();
+        for (color, text) in &segments {
+            match color {
+                Some(color) => rendered.push_str(&format!("\x1b[{}m{}", color.ansi_code(), text)),
+                None => rendered.push_str(text),
+            }
+        }
+        if segments.iter().any(|(color, _)| color.is_some()) {
+            rendered.push_str("\x1b[0m");
+        }
+        rendered
+    }
+
+    /// Renders the name as HTML, with a `<span>` for each coloured run of text.
+    pub fn to_html(&self) -> String {
+        self.segments()
+            .iter()
+            .map(|(color, text)| match color {
+                Some(color) => format!(
+                    r#"<span style="color: {}">{}</span>"#,
+                    color.css(),
+                    escape_html(text)
+                ),
+                None => escape_html(text),
+            })
+            .collect()
+    }
+}
+
+/// Removes the `^N` colour codes from a string.
+///
+/// A `^` followed by a letter or digit is a colour code; any other `^` is kept as text.
+///
+/// # Arguments
+///
+/// * `text` - A string that may contain colour codes, e.g. `^1Red^7Player`.
+///
+/// # Returns
+///
+/// * `Cow<str>` - The text without colour codes, e.g. `RedPlayer`, borrowed from `text` when it has
+///   none, which is the case of most log lines.
+pub fn strip_colors(text: &str) -> Cow<'_, str> {
+    if !text.contains(COLOR_ESCAPE) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut clean = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(code) if c == COLOR_ESCAPE && code.is_ascii_alphanumeric() => {
+                chars.next();
+            }
+            _ => clean.push(c),
+        }
+    }
+    Cow::Owned(clean)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
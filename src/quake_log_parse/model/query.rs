@@ -0,0 +1,809 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use super::{
+    error::LogError,
+    event::{parse_settings, LogEvent, LogLine},
+    log_model::ParseOptions,
+    session::{NamePolicy, NameUse, PlayerSession},
+};
+
+/// The fields of every event: its match, the server settings of the match and when it happened.
+///
+/// `time` is the number of seconds since the match started and `minute` the number of whole minutes.
+const MATCH_FIELDS: [&str; 6] = ["match", "map", "gametype", "hostname", "time", "minute"];
+/// The fields holding numbers, which can be summed.
+const NUMERIC_FIELDS: [&str; 3] = ["match", "time", "minute"];
+
+/// The events a query reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// `kills` - the `Kill:` lines, with the fields `killer`, `victim` and `means`.
+    Kills,
+    /// `items` - the `Item:` lines, with the fields `player` and `item`.
+    Items,
+    /// `chat` - the `say:` and `sayteam:` lines, with the fields `player`, `message` and `team`.
+    Chat,
+}
+
+/// A comparison operator of a `where` clause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// `~` - the value contains the text, ignoring case.
+    Contains,
+}
+
+/// The condition of a `where` clause.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    Compare {
+        field: &'static str,
+        operator: Operator,
+        value: String,
+    },
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+/// A value computed for each group of events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    /// `count` - the number of events.
+    Count,
+    /// `sum(field)` - the sum of a numeric field.
+    Sum(&'static str),
+}
+
+/// A query over the events of logs, e.g.
+/// `kills where means = MOD_RAILGUN and killer = Zeh and map = q3dm17 and minute >= 5 count`.
+///
+/// A query names its `Source` and is followed by any of these clauses:
+///
+/// * `where CONDITION` - Keeps the events meeting the condition: comparisons of a field with a value
+///   (`=`, `!=`, `<`, `<=`, `>`, `>=` and `~` for "contains"), combined with `and`, `or`, `not` and
+///   parentheses. Values with spaces or symbols are written in double quotes, e.g. `"<world>"`.
+/// * `group by FIELD, ...` - Groups the events by the values of the fields.
+/// * `count` and `sum(FIELD)` - Computes the number of events or the sum of a numeric field, of each
+///   group or of all the events. A `group by` alone counts the events of each group.
+/// * `order by COLUMN [asc|desc]` - Sorts the rows by a field, or by `count` or `sum(FIELD)` when the
+///   events are grouped.
+/// * `limit N` - Keeps the first `N` rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    pub source: Source,
+    pub filter: Option<Condition>,
+    pub group_by: Vec<&'static str>,
+    pub aggregates: Vec<Aggregate>,
+    /// The column to sort by, and whether in descending order.
+    pub order_by: Option<(String, bool)>,
+    pub limit: Option<usize>,
+}
+
+/// A value of a field or of an aggregate.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QueryValue {
+    Number(i64),
+    Text(String),
+}
+
+/// The rows answering a query.
+#[derive(Debug, PartialEq)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<QueryValue>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Operator(Operator),
+    Open,
+    Close,
+    Comma,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    source: Source,
+}
+
+/// The server settings of a match, shared by its events.
+#[derive(Clone, Copy, Default)]
+struct MatchInfo<'a> {
+    id: i32,
+    map: &'a str,
+    gametype: &'a str,
+    hostname: &'a str,
+    started_at: u32,
+}
+
+/// An event read by a query, with the match it belongs to.
+struct Record<'a> {
+    info: MatchInfo<'a>,
+    timestamp: Option<u32>,
+    event: LogEvent<'a>,
+    /// The name of the client of an item event, from its last `ClientUserinfoChanged:` line.
+    player: Option<&'a str>,
+}
+
+/// A client slot of the match being read, from its first `ClientUserinfoChanged:` line until it
+/// disconnects or the match ends.
+#[derive(Default)]
+struct ClientSlot<'a> {
+    /// The names taken by the slot, for `PlayerSession::reported_name`.
+    session: PlayerSession,
+    /// The names of `session` as written in the log.
+    raw_names: Vec<&'a str>,
+    /// The records naming the slot, by index, with the field holding its name.
+    records: Vec<(usize, Role)>,
+}
+
+/// The field of a record holding the name of a client slot.
+#[derive(Clone, Copy)]
+enum Role {
+    Killer,
+    Victim,
+    Player,
+}
+
+impl Source {
+    fn parse(word: &str) -> Option<Self> {
+        match word {
+            "kills" => Some(Source::Kills),
+            "items" => Some(Source::Items),
+            "chat" => Some(Source::Chat),
+            _ => None,
+        }
+    }
+
+    /// Returns the fields of the events, which are also the columns of the rows when they are not
+    /// grouped.
+    pub fn fields(self) -> Vec<&'static str> {
+        let own: &[&'static str] = match self {
+            Source::Kills => &["killer", "victim", "means"],
+            Source::Items => &["player", "item"],
+            Source::Chat => &["player", "message", "team"],
+        };
+        MATCH_FIELDS.iter().chain(own).copied().collect()
+    }
+}
+
+impl Operator {
+    fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            Operator::Eq => ordering == Ordering::Equal,
+            Operator::Ne => ordering != Ordering::Equal,
+            Operator::Lt => ordering == Ordering::Less,
+            Operator::Le => ordering != Ordering::Greater,
+            Operator::Gt => ordering == Ordering::Greater,
+            Operator::Ge => ordering != Ordering::Less,
+            Operator::Contains => false,
+        }
+    }
+}
+
+impl Condition {
+    fn holds(&self, record: &Record) -> bool {
+        match self {
+            Condition::Compare {
+                field,
+                operator,
+                value,
+            } => {
+                let Some(actual) = record.value(field) else {
+                    return false;
+                };
+                if *operator == Operator::Contains {
+                    return actual
+                        .to_string()
+                        .to_lowercase()
+                        .contains(&value.to_lowercase());
+                }
+                let ordering = match (&actual, value.parse::<i64>()) {
+                    (QueryValue::Number(number), Ok(expected)) => number.cmp(&expected),
+                    _ => actual.to_string().as_str().cmp(value),
+                };
+                operator.accepts(ordering)
+            }
+            Condition::And(left, right) => left.holds(record) && right.holds(record),
+            Condition::Or(left, right) => left.holds(record) || right.holds(record),
+            Condition::Not(condition) => !condition.holds(record),
+        }
+    }
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Aggregate::Count => write!(f, "count"),
+            Aggregate::Sum(field) => write!(f, "sum({})", field),
+        }
+    }
+}
+
+impl fmt::Display for QueryValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryValue::Number(number) => write!(f, "{}", number),
+            QueryValue::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl Query {
+    /// Parses a query (see `Query` for its syntax). Keywords and fields are not case sensitive.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string containing the query.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Query, LogError>` - A `Result` with the query, or an error (`Err`) if it is invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `LogError::QueryError` describing the first problem found, e.g. an unknown field or
+    /// a missing value.
+    pub fn parse(text: &str) -> Result<Self, LogError> {
+        let tokens = tokenize(text)?;
+        let source = match tokens.first() {
+            Some(Token::Word(word)) => Source::parse(&word.to_lowercase()),
+            _ => None,
+        }
+        .ok_or_else(|| query_error("A query starts with kills, items or chat"))?;
+
+        let mut parser = Parser {
+            tokens,
+            position: 1,
+            source,
+        };
+        let mut query = Query {
+            source,
+            filter: None,
+            group_by: Vec::new(),
+            aggregates: Vec::new(),
+            order_by: None,
+            limit: None,
+        };
+        while let Some(token) = parser.next() {
+            let keyword = match token {
+                Token::Word(word) => word.to_lowercase(),
+                token => return Err(query_error(&format!("Unexpected {:?}", token))),
+            };
+            match keyword.as_str() {
+                "where" if query.filter.is_none() => query.filter = Some(parser.or()?),
+                "group" if query.group_by.is_empty() => {
+                    parser.keyword("by")?;
+                    query.group_by.push(parser.field()?);
+                    while parser.peek() == Some(&Token::Comma) {
+                        parser.position += 1;
+                        query.group_by.push(parser.field()?);
+                    }
+                }
+                "count" => query.aggregates.push(Aggregate::Count),
+                "sum" => query.aggregates.push(Aggregate::Sum(parser.sum_field()?)),
+                "order" if query.order_by.is_none() => {
+                    parser.keyword("by")?;
+                    let column = match parser.next() {
+                        Some(Token::Word(word)) if word.eq_ignore_ascii_case("sum") => {
+                            Aggregate::Sum(parser.sum_field()?).to_string()
+                        }
+                        Some(Token::Word(word)) => word.to_lowercase(),
+                        _ => return Err(query_error("Expected a column after order by")),
+                    };
+                    let descending = match parser.peek() {
+                        Some(Token::Word(word)) if word.eq_ignore_ascii_case("desc") => true,
+                        Some(Token::Word(word)) if word.eq_ignore_ascii_case("asc") => false,
+                        _ => {
+                            query.order_by = Some((column, false));
+                            continue;
+                        }
+                    };
+                    parser.position += 1;
+                    query.order_by = Some((column, descending));
+                }
+                "limit" if query.limit.is_none() => {
+                    query.limit = match parser.next() {
+                        Some(Token::Word(word)) => word.parse().ok(),
+                        _ => None,
+                    };
+                    if query.limit.is_none() {
+                        return Err(query_error("Expected a number after limit"));
+                    }
+                }
+                "where" | "group" | "order" | "limit" => {
+                    return Err(query_error(&format!("Repeated clause: {}", keyword)));
+                }
+                _ => return Err(query_error(&format!("Unexpected {}", keyword))),
+            }
+        }
+
+        if let Some((column, _)) = &query.order_by {
+            if !query.columns().contains(column) {
+                return Err(query_error(&format!("Cannot order by {}", column)));
+            }
+        }
+        Ok(query)
+    }
+
+    /// Returns the columns of the rows answering the query.
+    pub fn columns(&self) -> Vec<String> {
+        if !self.is_grouped() {
+            return self.source.fields().iter().map(|f| f.to_string()).collect();
+        }
+        let aggregates = match self.aggregates.as_slice() {
+            [] => vec![Aggregate::Count],
+            aggregates => aggregates.to_vec(),
+        };
+        self.group_by
+            .iter()
+            .map(|field| field.to_string())
+            .chain(aggregates.iter().map(Aggregate::to_string))
+            .collect()
+    }
+
+    /// Answers the query over the events of logs.
+    ///
+    /// Matches are numbered from 1 across the logs, as in the report of several log files, and the
+    /// events before the first match of a log are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `logs` - The contents of the log files, in order.
+    /// * `options` - The `ParseOptions` picking the name reported for players that renamed themselves.
+    ///
+    /// # Returns
+    ///
+    /// * `QueryResult` - The rows answering the query: one per event, or one per group when the
+    ///   events are grouped or aggregated.
+    pub fn run<'a>(
+        &self,
+        logs: impl IntoIterator<Item = &'a str>,
+        options: &ParseOptions,
+    ) -> QueryResult {
+        let mut records = Vec::new();
+        let mut matches = 0;
+        for content in logs {
+            matches = read_records(content, self.source, matches, options, &mut records);
+        }
+        records.retain(|record| self.filter.as_ref().is_none_or(|c| c.holds(record)));
+
+        let columns = self.columns();
+        let mut rows: Vec<Vec<QueryValue>> = if self.is_grouped() {
+            self.aggregate(&records)
+        } else {
+            records
+                .iter()
+                .map(|record| {
+                    columns
+                        .iter()
+                        .map(|column| {
+                            record
+                                .value(column)
+                                .unwrap_or(QueryValue::Text(String::new()))
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+
+        if let Some((column, descending)) = &self.order_by {
+            if let Some(idx) = columns.iter().position(|name| name == column) {
+                rows.sort_by(|a, b| match descending {
+                    true => b[idx].cmp(&a[idx]),
+                    false => a[idx].cmp(&b[idx]),
+                });
+            }
+        }
+        if let Some(limit) = self.limit {
+            rows.truncate(limit);
+        }
+        QueryResult { columns, rows }
+    }
+
+    fn is_grouped(&self) -> bool {
+        !self.group_by.is_empty() || !self.aggregates.is_empty()
+    }
+
+    /// Computes the aggregates of each group, sorted by the values of the group.
+    fn aggregate(&self, records: &[Record]) -> Vec<Vec<QueryValue>> {
+        let aggregates = match self.aggregates.as_slice() {
+            [] => vec![Aggregate::Count],
+            aggregates => aggregates.to_vec(),
+        };
+        let mut groups: BTreeMap<Vec<QueryValue>, Vec<i64>> = BTreeMap::new();
+        if self.group_by.is_empty() {
+            groups.insert(Vec::new(), vec![0; aggregates.len()]);
+        }
+
+        for record in records {
+            let key = self
+                .group_by
+                .iter()
+                .map(|field| {
+                    record
+                        .value(field)
+                        .unwrap_or(QueryValue::Text(String::new()))
+                })
+                .collect();
+            let totals = groups
+                .entry(key)
+                .or_insert_with(|| vec![0; aggregates.len()]);
+            for (total, aggregate) in totals.iter_mut().zip(&aggregates) {
+                *total += match aggregate {
+                    Aggregate::Count => 1,
+                    Aggregate::Sum(field) => match record.value(field) {
+                        Some(QueryValue::Number(number)) => number,
+                        _ => 0,
+                    },
+                };
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(mut row, totals)| {
+                row.extend(totals.into_iter().map(QueryValue::Number));
+                row
+            })
+            .collect()
+    }
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), LogError> {
+        if !self.peek_keyword(keyword) {
+            return Err(query_error(&format!("Expected {}", keyword)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    /// Reads the name of a field of the source.
+    fn field(&mut self) -> Result<&'static str, LogError> {
+        let name = match self.next() {
+            Some(Token::Word(word)) => word.to_lowercase(),
+            _ => return Err(query_error("Expected a field")),
+        };
+        self.source
+            .fields()
+            .into_iter()
+            .find(|field| *field == name)
+            .ok_or_else(|| query_error(&format!("Unknown field: {}", name)))
+    }
+
+    /// Reads the `(field)` of a `sum`.
+    fn sum_field(&mut self) -> Result<&'static str, LogError> {
+        if self.next() != Some(Token::Open) {
+            return Err(query_error("Expected sum(FIELD)"));
+        }
+        let field = self.field()?;
+        if self.next() != Some(Token::Close) {
+            return Err(query_error("Expected sum(FIELD)"));
+        }
+        if !NUMERIC_FIELDS.contains(&field) {
+            return Err(query_error(&format!("Cannot sum {}", field)));
+        }
+        Ok(field)
+    }
+
+    fn or(&mut self) -> Result<Condition, LogError> {
+        let mut condition = self.and()?;
+        while self.peek_keyword("or") {
+            self.position += 1;
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, LogError> {
+        let mut condition = self.not()?;
+        while self.peek_keyword("and") {
+            self.position += 1;
+            condition = Condition::And(Box::new(condition), Box::new(self.not()?));
+        }
+        Ok(condition)
+    }
+
+    fn not(&mut self) -> Result<Condition, LogError> {
+        if self.peek_keyword("not") {
+            self.position += 1;
+            return Ok(Condition::Not(Box::new(self.not()?)));
+        }
+        if self.peek() == Some(&Token::Open) {
+            self.position += 1;
+            let condition = self.or()?;
+            if self.next() != Some(Token::Close) {
+                return Err(query_error("Expected )"));
+            }
+            return Ok(condition);
+        }
+
+        let field = self.field()?;
+        let Some(Token::Operator(operator)) = self.next() else {
+            return Err(query_error(&format!(
+                "Expected an operator after {}",
+                field
+            )));
+        };
+        let value = match self.next() {
+            Some(Token::Word(value) | Token::Quoted(value)) => value,
+            _ => return Err(query_error(&format!("Expected a value after {}", field))),
+        };
+        Ok(Condition::Compare {
+            field,
+            operator,
+            value,
+        })
+    }
+}
+
+impl Record<'_> {
+    fn value(&self, field: &str) -> Option<QueryValue> {
+        let text = |text: &str| Some(QueryValue::Text(text.to_string()));
+        let time = self
+            .timestamp
+            .map(|timestamp| i64::from(timestamp.saturating_sub(self.info.started_at)));
+        match (field, &self.event) {
+            ("match", _) => Some(QueryValue::Number(i64::from(self.info.id))),
+            ("map", _) => text(self.info.map),
+            ("gametype", _) => text(self.info.gametype),
+            ("hostname", _) => text(self.info.hostname),
+            ("time", _) => time.map(QueryValue::Number),
+            ("minute", _) => time.map(|time| QueryValue::Number(time / 60)),
+            ("killer", LogEvent::Kill(kill)) => kill.killer.and_then(text),
+            ("victim", LogEvent::Kill(kill)) => kill.victim.and_then(text),
+            ("means", LogEvent::Kill(kill)) => kill.means.and_then(text),
+            ("player", LogEvent::Item { .. }) => self.player.and_then(text),
+            ("item", LogEvent::Item { item, .. }) => item.and_then(text),
+            ("player", LogEvent::Say { name, .. }) => name.and_then(text),
+            ("message", LogEvent::Say { message, .. }) => text(message),
+            ("team", LogEvent::Say { team, .. }) => text(if *team { "true" } else { "false" }),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the events of a source in a log, numbering its matches after `matches`, and returns the
+/// number of matches read so far.
+///
+/// The killers, victims and item players that renamed themselves are named under `options.name_policy`.
+fn read_records<'a>(
+    content: &'a str,
+    source: Source,
+    matches: i32,
+    options: &ParseOptions,
+    records: &mut Vec<Record<'a>>,
+) -> i32 {
+    let mut info: Option<MatchInfo> = None;
+    let mut names: HashMap<i32, &str> = HashMap::new();
+    let mut slots: HashMap<i32, ClientSlot> = HashMap::new();
+    let mut match_end = 0;
+    let mut id = matches;
+
+    for line in content.lines() {
+        let LogLine {
+            timestamp, event, ..
+        } = LogLine::parse(line);
+        if matches!(event, LogEvent::InitGame { .. }) {
+            for (_, slot) in slots.drain() {
+                rename_records(slot, options.name_policy, match_end, records);
+            }
+        }
+        if let Some(timestamp) = timestamp {
+            match_end = timestamp;
+        }
+        match event {
+            LogEvent::InitGame { settings } => {
+                id += 1;
+                let mut current = MatchInfo {
+                    id,
+                    started_at: timestamp.unwrap_or(0),
+                    ..Default::default()
+                };
+                for (key, value) in parse_settings(settings) {
+                    match key {
+                        "mapname" => current.map = value,
+                        "g_gametype" => current.gametype = value,
+                        "sv_hostname" => current.hostname = value,
+                        _ => {}
+                    }
+                }
+                info = Some(current);
+                names.clear();
+            }
+            LogEvent::ClientConnect {
+                client_id: Some(client_id),
+            } => {
+                if let Some(slot) = slots.remove(&client_id) {
+                    rename_records(slot, options.name_policy, match_end, records);
+                }
+            }
+            LogEvent::ClientDisconnect {
+                client_id: Some(client_id),
+            } => {
+                if let Some(mut slot) = slots.remove(&client_id) {
+                    slot.session.disconnected_at = timestamp;
+                    rename_records(slot, options.name_policy, match_end, records);
+                }
+            }
+            LogEvent::ClientUserinfoChanged {
+                client_id: Some(client_id),
+                name: Some(name),
+            } => {
+                names.insert(client_id, name);
+                let slot = slots.entry(client_id).or_default();
+                if slot.raw_names.last() != Some(&name) {
+                    slot.raw_names.push(name);
+                    slot.session.names.push(NameUse {
+                        name: name.to_string(),
+                        since: match_end,
+                    });
+                }
+            }
+            LogEvent::Kill(_) | LogEvent::Item { .. } | LogEvent::Say { .. } => {
+                let selected = matches!(
+                    (source, &event),
+                    (Source::Kills, LogEvent::Kill(_))
+                        | (Source::Items, LogEvent::Item { .. })
+                        | (Source::Chat, LogEvent::Say { .. })
+                );
+                let Some(info) = info.filter(|_| selected) else {
+                    continue;
+                };
+                let roles = match &event {
+                    LogEvent::Kill(kill) => vec![
+                        (kill.killer_id, Role::Killer),
+                        (kill.victim_id, Role::Victim),
+                    ],
+                    LogEvent::Item { client_id, .. } => vec![(*client_id, Role::Player)],
+                    _ => Vec::new(),
+                };
+                for (client_id, role) in roles {
+                    if let Some(slot) = client_id.and_then(|client_id| slots.get_mut(&client_id)) {
+                        slot.records.push((records.len(), role));
+                    }
+                }
+                let player = match event {
+                    LogEvent::Item {
+                        client_id: Some(client_id),
+                        ..
+                    } => names.get(&client_id).copied(),
+                    _ => None,
+                };
+                records.push(Record {
+                    info,
+                    timestamp,
+                    event,
+                    player,
+                });
+            }
+            _ => {}
+        }
+    }
+    for (_, slot) in slots.drain() {
+        rename_records(slot, options.name_policy, match_end, records);
+    }
+    id
+}
+
+/// Writes the name reported for a client slot under `policy` into the records naming it.
+///
+/// Kill lines carry the name the player had at that moment and item lines the name from the last
+/// `ClientUserinfoChanged:` line, so a player that renamed themselves is reported under a single name,
+/// as in the report.
+///
+/// # Arguments
+///
+/// * `slot` - The client slot, once it disconnected or its match ended.
+/// * `policy` - The `NamePolicy` used to pick one of the names of the slot.
+/// * `match_end` - The timestamp of the last line of the match the slot belongs to.
+/// * `records` - The records read so far, including those of `slot`.
+fn rename_records<'a>(
+    slot: ClientSlot<'a>,
+    policy: NamePolicy,
+    match_end: u32,
+    records: &mut [Record<'a>],
+) {
+    let Some(reported) = slot.session.reported_name(policy, match_end) else {
+        return;
+    };
+    let Some(name) = slot
+        .raw_names
+        .iter()
+        .copied()
+        .find(|name| *name == reported)
+    else {
+        return;
+    };
+    for (idx, role) in slot.records {
+        let record = &mut records[idx];
+        match (role, &mut record.event) {
+            (Role::Killer, LogEvent::Kill(kill)) => kill.killer = Some(name),
+            (Role::Victim, LogEvent::Kill(kill)) => kill.victim = Some(name),
+            (Role::Player, _) => record.player = Some(name),
+            _ => {}
+        }
+    }
+}
+
+/// Splits a query into words, quoted strings, operators, parentheses and commas.
+fn tokenize(text: &str) -> Result<Vec<Token>, LogError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            '~' => Token::Operator(Operator::Contains),
+            '=' => Token::Operator(Operator::Eq),
+            '!' | '<' | '>' => {
+                let equals = chars.next_if_eq(&'=').is_some();
+                Token::Operator(match (c, equals) {
+                    ('!', true) => Operator::Ne,
+                    ('<', false) => Operator::Lt,
+                    ('<', true) => Operator::Le,
+                    ('>', false) => Operator::Gt,
+                    ('>', true) => Operator::Ge,
+                    _ => return Err(query_error("Expected != instead of !")),
+                })
+            }
+            '"' => {
+                let mut quoted = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => quoted.extend(chars.next()),
+                        Some(c) => quoted.push(c),
+                        None => return Err(query_error("Unterminated quoted value")),
+                    }
+                }
+                Token::Quoted(quoted)
+            }
+            c => {
+                let mut word = c.to_string();
+                while let Some(c) =
+                    chars.next_if(|c| !c.is_whitespace() && !"()=!<>~,\"".contains(*c))
+                {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn query_error(message: &str) -> LogError {
+    LogError::QueryError(message.to_string())
+}
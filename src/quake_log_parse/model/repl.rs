@@ -0,0 +1,343 @@
+use std::{cmp::Reverse, collections::BTreeSet, env, path::PathBuf};
+
+use super::{
+    error::LogError,
+    identity::{process_identity_ranking, AliasMap},
+    log_model::{Match, ParseOptions, PlayerScore},
+    session::{playtime_by_player, PlayerPlaytime},
+    sources,
+    stats::{player_stats, PlayerStats},
+};
+
+/// The commands of the REPL, in the order `help` lists them.
+pub const COMMANDS: [&str; 8] = [
+    "load", "matches", "match", "player", "ranking", "export", "help", "quit",
+];
+
+/// The name of the file the REPL keeps its history in, next to the executable.
+const HISTORY_FILE_NAME: &str = ".quake_log_parse_history";
+
+/// The order of the players of `ranking`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RankingOrder {
+    #[default]
+    Kills,
+    Playtime,
+    Matches,
+    Name,
+}
+
+/// The formats of `export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// The matches, player ranking and playtime, as in the JSON report.
+    Json,
+    /// One row of statistics per player, in ranking order.
+    Csv,
+}
+
+/// A command typed in the REPL.
+#[derive(Debug, PartialEq)]
+pub enum ReplCommand {
+    /// Replaces the data with the matches of log files, directories or glob patterns.
+    Load(Vec<String>),
+    /// Lists the matches, or only those where a player or a means of death appears.
+    Matches(Option<String>),
+    /// Shows a match by id.
+    Match(i32),
+    /// Shows the statistics of a player, by name or alias.
+    Player(String),
+    Ranking(RankingOrder),
+    Export(ExportFormat, String),
+    Help,
+    Quit,
+}
+
+/// The matches loaded in the REPL.
+#[derive(Default)]
+pub struct Workspace {
+    /// The log files the matches were read from.
+    pub files: Vec<String>,
+    pub matches: Vec<Match>,
+    aliases: AliasMap,
+    options: ParseOptions,
+}
+
+/// The player names and means of death a line of the REPL can be completed with.
+#[derive(Debug, Default, Clone)]
+pub struct Completions {
+    pub players: Vec<String>,
+    pub means: Vec<String>,
+}
+
+/// What the word under the cursor completes to.
+#[derive(Debug, PartialEq)]
+pub enum Completion {
+    /// The candidates replacing the line from the given byte position.
+    Words(usize, Vec<String>),
+    /// A file path, e.g. the argument of `load`.
+    Path,
+}
+
+impl RankingOrder {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "kills" => Some(RankingOrder::Kills),
+            "playtime" => Some(RankingOrder::Playtime),
+            "matches" => Some(RankingOrder::Matches),
+            "name" => Some(RankingOrder::Name),
+            _ => None,
+        }
+    }
+}
+
+impl ReplCommand {
+    /// Parses a line typed in the REPL.
+    ///
+    /// Player names may contain spaces, so the argument of `player` and `matches` is the rest of the
+    /// line.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The line typed, e.g. `ranking by playtime`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<ReplCommand>, LogError>` - A `Result` with the command, `None` for a blank
+    ///   line, or an error (`Err`) if the line is not a valid command.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `LogError::CommandError` describing the problem if the command is unknown or its
+    /// arguments are missing or invalid.
+    pub fn parse(line: &str) -> Result<Option<Self>, LogError> {
+        let line = line.trim();
+        let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let error = |message: String| Err(LogError::CommandError(message));
+
+        let command = match name {
+            "" => return Ok(None),
+            "load" if rest.is_empty() => return error("Usage: load FILE...".to_string()),
+            "load" => ReplCommand::Load(rest.split_whitespace().map(str::to_string).collect()),
+            "matches" if rest.is_empty() => ReplCommand::Matches(None),
+            "matches" => ReplCommand::Matches(Some(rest.to_string())),
+            "match" => match rest.parse() {
+                Ok(id) => ReplCommand::Match(id),
+                Err(_) => return error("Usage: match ID".to_string()),
+            },
+            "player" if rest.is_empty() => return error("Usage: player NAME".to_string()),
+            "player" => ReplCommand::Player(rest.to_string()),
+            "ranking" => {
+                let words: Vec<&str> = rest.split_whitespace().collect();
+                let order = match words[..] {
+                    [] => Some(RankingOrder::default()),
+                    ["by", order] => RankingOrder::parse(order),
+                    _ => None,
+                };
+                match order {
+                    Some(order) => ReplCommand::Ranking(order),
+                    None => {
+                        return error("Usage: ranking [by kills|playtime|matches|name]".to_string())
+                    }
+                }
+            }
+            "export" => {
+                let (format, path) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                let format = match format {
+                    "json" => ExportFormat::Json,
+                    "csv" => ExportFormat::Csv,
+                    _ => return error("Usage: export json|csv PATH".to_string()),
+                };
+                if path.trim().is_empty() {
+                    return error("Usage: export json|csv PATH".to_string());
+                }
+                ReplCommand::Export(format, path.trim().to_string())
+            }
+            "help" | "?" => ReplCommand::Help,
+            "quit" | "exit" => ReplCommand::Quit,
+            name => return error(format!("Unknown command: {} (type help)", name)),
+        };
+        Ok(Some(command))
+    }
+}
+
+impl Workspace {
+    /// Creates a workspace with no matches, whose players are identified with `aliases` and whose logs
+    /// are parsed with `options`.
+    pub fn new(aliases: AliasMap, options: ParseOptions) -> Self {
+        Workspace {
+            aliases,
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// Replaces the matches with those of the inputs, numbered from 1 across the files.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The log files, directories or glob patterns to read.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if the
+    ///   inputs cannot be read, in which case the previous matches are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if any input cannot be resolved, read or parsed.
+    pub fn load(&mut self, inputs: &[String]) -> Result<(), LogError> {
+        let paths = sources::resolve_inputs(inputs)?;
+        self.matches = sources::process_files(&paths, &self.options, true)?;
+        self.files = paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        Ok(())
+    }
+
+    /// Returns the match with the given id.
+    pub fn find_match(&self, id: i32) -> Option<&Match> {
+        self.matches.iter().find(|mat| mat.id == id)
+    }
+
+    /// Returns the matches where `name` is a player or a means of death, or every match for `None`.
+    pub fn matches_with(&self, name: Option<&str>) -> Vec<&Match> {
+        self.matches
+            .iter()
+            .filter(|mat| {
+                name.is_none_or(|name| {
+                    mat.data.players.contains(name) || mat.data.kills_by_means.contains_key(name)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the statistics of every player, in the given order.
+    ///
+    /// Players with the same value are kept in ranking order.
+    pub fn ranking(&self, order: RankingOrder) -> Vec<PlayerStats> {
+        let mut stats = player_stats(&self.matches, &self.aliases);
+        match order {
+            RankingOrder::Kills => {}
+            RankingOrder::Playtime => stats.sort_by_key(|player| Reverse(player.playtime)),
+            RankingOrder::Matches => stats.sort_by_key(|player| Reverse(player.matches.len())),
+            RankingOrder::Name => stats.sort_by(|a, b| a.name.cmp(&b.name)),
+        }
+        stats
+    }
+
+    /// Returns the statistics of a player, found by name or alias, ignoring case.
+    pub fn player(&self, name: &str) -> Option<PlayerStats> {
+        self.ranking(RankingOrder::Kills)
+            .into_iter()
+            .find(|player| {
+                player
+                    .aliases
+                    .iter()
+                    .any(|alias| alias.eq_ignore_ascii_case(name))
+            })
+    }
+
+    /// Returns the ranking of the `Player Ranking` of the report, with players identified across
+    /// their names.
+    pub fn player_ranking(&self) -> Vec<PlayerScore> {
+        let mut ranking = Vec::new();
+        process_identity_ranking(&self.matches, &self.aliases, &mut ranking);
+        ranking
+    }
+
+    /// Returns the time each player spent in the matches.
+    pub fn playtime(&self) -> Vec<PlayerPlaytime> {
+        playtime_by_player(&self.matches)
+    }
+
+    /// Returns the player names and means of death of the matches, to complete lines with.
+    pub fn completions(&self) -> Completions {
+        let mut players = BTreeSet::new();
+        let mut means = BTreeSet::new();
+        for mat in &self.matches {
+            players.extend(mat.data.players.iter().filter(|name| !name.is_empty()));
+            means.extend(mat.data.kills_by_means.keys());
+        }
+        Completions {
+            players: players.into_iter().cloned().collect(),
+            means: means.into_iter().cloned().collect(),
+        }
+    }
+}
+
+impl Completions {
+    /// Completes the word under the cursor.
+    ///
+    /// The first word completes to a command; the argument of `player` to a player name and the
+    /// argument of `matches` to a player name or a means of death, both from the start of the argument
+    /// so names with spaces complete at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The line typed so far.
+    /// * `pos` - The byte position of the cursor in `line`.
+    ///
+    /// # Returns
+    ///
+    /// * `Completion` - The candidates starting with the word typed, or `Completion::Path` when a file
+    ///   path is expected.
+    pub fn complete(&self, line: &str, pos: usize) -> Completion {
+        let line = &line[..pos];
+        let start = line.len() - line.trim_start().len();
+        let Some((command, _)) = line[start..].split_once(char::is_whitespace) else {
+            return words(start, &line[start..], COMMANDS.iter().copied());
+        };
+        let arg_start = line.len() - line[start + command.len()..].trim_start().len();
+        let arg = &line[arg_start..];
+
+        match command {
+            "load" => Completion::Path,
+            "export" if arg.contains(char::is_whitespace) => Completion::Path,
+            "export" => words(arg_start, arg, ["json", "csv"]),
+            "ranking" => words(
+                arg_start,
+                arg,
+                ["by kills", "by playtime", "by matches", "by name"],
+            ),
+            "player" => words(arg_start, arg, self.players.iter().map(String::as_str)),
+            "matches" => words(
+                arg_start,
+                arg,
+                self.players.iter().chain(&self.means).map(String::as_str),
+            ),
+            _ => Completion::Words(pos, Vec::new()),
+        }
+    }
+}
+
+/// Returns the candidates starting with `prefix`, ignoring case.
+fn words<'a>(
+    start: usize,
+    prefix: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Completion {
+    let prefix = prefix.to_lowercase();
+    Completion::Words(
+        start,
+        candidates
+            .into_iter()
+            .filter(|candidate| candidate.to_lowercase().starts_with(&prefix))
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Retrieves the path of the history file of the REPL.
+///
+/// # Returns
+///
+/// * `Option<PathBuf>` - The path of `.quake_log_parse_history` in the same directory as the executable,
+///   or `None` if the directory cannot be found.
+pub fn get_history_path() -> Option<PathBuf> {
+    let mut current_exe = env::current_exe().ok()?;
+    current_exe.pop();
+    Some(current_exe.join(HISTORY_FILE_NAME))
+}
@@ -0,0 +1,369 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    checkpoint::{Checkpoint, Resume},
+    error::LogError,
+    log_model::{
+        final_scoreboard, reconcile_scores, Match, MatchData, PlayerScore, ScoreDiscrepancy,
+        ServerScore,
+    },
+    session::{playtime_by_player, PlayerPlaytime, PlayerSession},
+    sources::LogsReport,
+};
+
+/// The version of the JSON report, bumped on every incompatible change to its shape.
+///
+/// Version 1 is the legacy report, without a version, written as several documents with the rankings
+/// as `{name: kills}` objects.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// The JSON Schema of the report, as shipped in `schema/report.schema.json`.
+pub const REPORT_SCHEMA: &str = include_str!("../../../schema/report.schema.json");
+
+/// A report as a single JSON document, described by `REPORT_SCHEMA`.
+///
+/// Every list is in a stable order and every map is sorted by key, so the same logs always give the
+/// same document.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Report {
+    pub schema_version: u32,
+    /// Where an incremental run resumed from, for reports of the matches since the last run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<ReportCheckpoint>,
+    /// The log files read, for reports of several log files or of a database.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileSummary>,
+    pub matches: Vec<MatchReport>,
+    pub ranking: Vec<RankedPlayer>,
+    pub playtime: Vec<PlayerPlaytime>,
+}
+
+/// Where an incremental run resumed from and how far the log has been processed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReportCheckpoint {
+    pub file: String,
+    pub resumed_from: Resume,
+    pub offset: u64,
+    pub matches: i32,
+}
+
+/// A log file of a report, with the ids of its matches and the ranking of its players.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileSummary {
+    pub file: String,
+    pub matches: Vec<i32>,
+    pub ranking: Vec<RankedPlayer>,
+}
+
+/// A player of a ranking, with their position from 1.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RankedPlayer {
+    pub rank: usize,
+    pub name: String,
+    pub kills: i32,
+}
+
+/// A match of a report.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatchReport {
+    pub id: i32,
+    pub total_kills: i32,
+    /// The names of the players, sorted.
+    pub players: Vec<String>,
+    pub kills: BTreeMap<String, i32>,
+    /// The kills of players by themselves, included in `kills`, for the score discrepancies.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub self_kills: BTreeMap<String, i32>,
+    pub death_causes: BTreeMap<String, i32>,
+    pub scoreboard: Vec<PlayerScore>,
+    pub score_discrepancies: Vec<ScoreDiscrepancy>,
+    pub sessions: Vec<PlayerSession>,
+    pub settings: BTreeMap<String, String>,
+    pub completed: bool,
+    pub started_at: u32,
+    pub ended_at: u32,
+    /// The log file the match was read from, for matches of one of several files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<MatchSource>,
+}
+
+/// The log file a match was read from and the number of the match within it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatchSource {
+    pub file: String,
+    #[serde(rename = "match")]
+    pub id: i32,
+}
+
+impl Report {
+    /// Builds the report of the matches of a single log.
+    ///
+    /// # Arguments
+    ///
+    /// * `matches` - A vector of the `Match` structs to report.
+    ///
+    /// * `ranking` - A slice of the `PlayerScore` structs ranking the players, in order.
+    ///
+    /// # Returns
+    ///
+    /// * `Report` - The report, with the playtime of the players computed from their sessions.
+    pub fn from_matches(matches: Vec<Match>, ranking: &[PlayerScore]) -> Self {
+        let playtime = playtime_by_player(&matches);
+        Report {
+            schema_version: SCHEMA_VERSION,
+            checkpoint: None,
+            files: Vec::new(),
+            matches: matches.into_iter().map(MatchReport::from).collect(),
+            ranking: rank(ranking),
+            playtime,
+        }
+    }
+
+    /// Builds the report of several log files, with the matches of every file in a single list.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - The `LogsReport` with the reports of each file and the combined data.
+    ///
+    /// # Returns
+    ///
+    /// * `Report` - The report, listing the ids of the matches of each file.
+    pub fn from_logs_report(report: LogsReport) -> Self {
+        let mut files = Vec::new();
+        let mut matches = Vec::new();
+        for file in report.files {
+            files.push(FileSummary {
+                file: file.file,
+                matches: file.matches.iter().map(|mat| mat.id).collect(),
+                ranking: rank(&file.ranking),
+            });
+            matches.extend(file.matches.into_iter().map(MatchReport::from));
+        }
+        Report {
+            schema_version: SCHEMA_VERSION,
+            checkpoint: None,
+            files,
+            matches,
+            ranking: rank(&report.ranking),
+            playtime: report.playtime,
+        }
+    }
+
+    /// Loads a report written by `--format=json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the report file.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Report, LogError>` - A `Result` with the report, or an error (`Err`) if it cannot be
+    ///   read or is not a report of this `SCHEMA_VERSION`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the file cannot be read, is not valid JSON, or holds a
+    /// report of another version, such as the legacy one.
+    pub fn load(path: &Path) -> Result<Self, LogError> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            LogError::ReadLogError(format!(
+                "Error reading the report {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        let invalid = |err: serde_json::Error| {
+            LogError::JsonError(format!("Invalid report {}: {}", path.display(), err))
+        };
+        // Legacy reports are several documents, so only the first one is read to tell them apart.
+        let document = serde_json::Deserializer::from_str(&content)
+            .into_iter::<Value>()
+            .next()
+            .unwrap_or_else(|| serde_json::from_str(&content))
+            .map_err(invalid)?;
+        match document.get("schema_version").and_then(Value::as_u64) {
+            Some(version) if version == SCHEMA_VERSION as u64 => {
+                serde_json::from_str(&content).map_err(invalid)
+            }
+            Some(version) => Err(LogError::JsonError(format!(
+                "Unsupported report {}: schema version {}, expected {}",
+                path.display(),
+                version,
+                SCHEMA_VERSION
+            ))),
+            None => Err(LogError::JsonError(format!(
+                "Unsupported report {}: no schema_version, export it again with --format=json",
+                path.display()
+            ))),
+        }
+    }
+}
+
+impl ReportCheckpoint {
+    /// Creates the summary of the checkpoint saved by an incremental run.
+    pub fn new(checkpoint: &Checkpoint, resume: Resume) -> Self {
+        ReportCheckpoint {
+            file: checkpoint.file.clone(),
+            resumed_from: resume,
+            offset: checkpoint.offset,
+            matches: checkpoint.matches,
+        }
+    }
+}
+
+impl From<Match> for MatchReport {
+    fn from(game: Match) -> Self {
+        let scoreboard = final_scoreboard(&game.data);
+        let score_discrepancies = reconcile_scores(&game.data);
+        let data = game.data;
+        MatchReport {
+            id: game.id,
+            total_kills: data.total_kills,
+            players: data.players.into_iter().collect(),
+            kills: data.kills,
+            self_kills: data.self_kills,
+            death_causes: data.kills_by_means,
+            scoreboard,
+            score_discrepancies,
+            sessions: data.sessions,
+            settings: data.settings,
+            completed: data.completed,
+            started_at: data.started_at,
+            ended_at: data.ended_at,
+            source: (!data.source_file.is_empty()).then_some(MatchSource {
+                file: data.source_file,
+                id: data.source_id,
+            }),
+        }
+    }
+}
+
+impl From<MatchReport> for Match {
+    /// Rebuilds a match from a report, e.g. to rank the players of several reports together.
+    ///
+    /// Reports do not hold the `score:` lines of the server, so they are rebuilt from the scoreboard,
+    /// with the client id unknown (`-1`) and the ping `0`; the scoreboard and score discrepancies of
+    /// the rebuilt match are the ones of the report.
+    fn from(game: MatchReport) -> Self {
+        let (source_file, source_id) = match game.source {
+            Some(source) => (source.file, source.id),
+            None => (String::new(), 0),
+        };
+        let scores = game
+            .scoreboard
+            .into_iter()
+            .map(|player| ServerScore {
+                client_id: -1,
+                raw_name: player.name.clone(),
+                name: player.name,
+                score: player.kills,
+                ping: 0,
+            })
+            .collect();
+        Match {
+            id: game.id,
+            data: MatchData {
+                total_kills: game.total_kills,
+                players: game.players.into_iter().collect(),
+                kills: game.kills,
+                kills_by_means: game.death_causes,
+                scores,
+                started_at: game.started_at,
+                ended_at: game.ended_at,
+                sessions: game.sessions,
+                source_file,
+                source_id,
+                settings: game.settings,
+                completed: game.completed,
+                self_kills: game.self_kills,
+            },
+        }
+    }
+}
+
+/// The matches of several reports combined by `merge_reports`.
+pub struct MergedReports {
+    pub matches: Vec<Match>,
+    /// How many matches were left out because another report already had them.
+    pub skipped: usize,
+}
+
+/// Combines the matches of several reports, e.g. of different servers or days, into one list.
+///
+/// The matches are numbered again from 1, in the order of the reports. Matches that do not say which
+/// log file they were read from (reports of a single log) are attributed to the report itself, with
+/// their id within it.
+///
+/// Reports may overlap, e.g. a report of a whole log merged with the report of its last day, so a
+/// match is merged only once: a match of a log file and id already merged, or with the same content
+/// as a match of a previous report, is skipped and counted in `MergedReports::skipped`.
+///
+/// # Arguments
+///
+/// * `reports` - A vector of the path of each report with the `Report` loaded from it.
+///
+/// # Returns
+///
+/// * `MergedReports` - The matches of all the reports, to rank with e.g. `sources::report_matches`,
+///   and how many were skipped.
+pub fn merge_reports(reports: Vec<(String, Report)>) -> MergedReports {
+    let mut matches = Vec::new();
+    let mut sources = HashSet::new();
+    let mut contents = HashSet::new();
+    let mut skipped = 0;
+
+    for (path, report) in reports {
+        let mut report_contents = Vec::new();
+        for game in report.matches {
+            let id = game.id;
+            let mut game = Match::from(game);
+            let content = match_content(&game.data);
+            if game.data.source_file.is_empty() {
+                game.data.source_file = path.clone();
+                game.data.source_id = id;
+            }
+            let source = (game.data.source_file.clone(), game.data.source_id);
+            if sources.contains(&source) || contents.contains(&content) {
+                skipped += 1;
+                continue;
+            }
+            sources.insert(source);
+            report_contents.push(content);
+            game.id = matches.len() as i32 + 1;
+            matches.push(game);
+        }
+        // Identical matches within a report are different matches of the same log.
+        contents.extend(report_contents);
+    }
+    MergedReports { matches, skipped }
+}
+
+/// Serializes the data of a match without the log file it was read from, to compare its content.
+fn match_content(data: &MatchData) -> String {
+    let mut value = serde_json::to_value(data).unwrap_or(Value::Null);
+    if let Some(object) = value.as_object_mut() {
+        object.remove("source_file");
+        object.remove("source_id");
+    }
+    value.to_string()
+}
+
+/// Numbers the players of a ranking from 1, in the order given.
+pub fn rank(ranking: &[PlayerScore]) -> Vec<RankedPlayer> {
+    ranking
+        .iter()
+        .enumerate()
+        .map(|(idx, player)| RankedPlayer {
+            rank: idx + 1,
+            name: player.name.clone(),
+            kills: player.kills,
+        })
+        .collect()
+}
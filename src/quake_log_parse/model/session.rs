@@ -0,0 +1,415 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    error::LogError,
+    event::{self, KillEvent},
+    log_model::{add_count, parse_timestamp, Match, MatchData},
+    player_name::PlayerName,
+};
+
+/// Seconds after the start of a match within which a connection is not considered a late join.
+pub const JOIN_LATE_GRACE_SECS: u32 = 30;
+/// Seconds before the end of a match within which a disconnection is not considered leaving early.
+pub const LEAVE_EARLY_GRACE_SECS: u32 = 30;
+/// Consecutive deaths, without a kill in between, that make up a death streak.
+pub const RAGE_QUIT_DEATH_STREAK: u32 = 3;
+/// Seconds after the last death of a streak within which a disconnection is a rage quit.
+pub const RAGE_QUIT_WINDOW_SECS: u32 = 30;
+/// Client id used by the server for kills caused by the world.
+const WORLD_CLIENT_ID: i32 = 1022;
+
+/// Which of the names used by a client slot during a match is reported for it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub enum NamePolicy {
+    /// The name the player had when entering the match.
+    First,
+    /// The name the player had when leaving the match, as shown by the server scoreboard.
+    #[default]
+    Last,
+    /// The name the player kept for the longest time.
+    MostUsed,
+}
+
+/// The time a client slot was occupied by a player within a match.
+///
+/// Timestamps are seconds since the server started, as written at the beginning of each log line.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSession {
+    pub client_id: i32,
+    pub name: String,
+    pub raw_name: String,
+    pub connected_at: u32,
+    pub began_at: Option<u32>,
+    pub disconnected_at: Option<u32>,
+    pub joined_late: bool,
+    pub left_early: bool,
+    pub rage_quit: bool,
+    pub names: Vec<NameUse>,
+    pub kills: i32,
+    #[serde(skip)]
+    pub userinfo: HashMap<String, String>,
+    #[serde(skip)]
+    pub(crate) death_streak: u32,
+    #[serde(skip)]
+    pub(crate) last_death_at: Option<u32>,
+    /// The kills of the player by themselves, credited to `MatchData::self_kills` with `kills`.
+    #[serde(skip)]
+    pub(crate) self_kills: i32,
+}
+
+/// A name taken by a client slot, from a `ClientUserinfoChanged` line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NameUse {
+    pub name: String,
+    pub since: u32,
+}
+
+/// Total time a player spent in matches across the whole log.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerPlaytime {
+    pub name: String,
+    pub seconds: u32,
+}
+
+impl PlayerSession {
+    /// Returns the number of seconds the player was in the game during this session.
+    ///
+    /// The session starts when the client entered the game (`ClientBegin`), or when it connected if it
+    /// never did, and lasts until it disconnected or the match ended.
+    ///
+    /// # Arguments
+    ///
+    /// * `match_end` - The timestamp of the last line of the match the session belongs to.
+    pub fn duration(&self, match_end: u32) -> u32 {
+        let start = self.began_at.unwrap_or(self.connected_at);
+        self.disconnected_at
+            .unwrap_or(match_end)
+            .saturating_sub(start)
+    }
+
+    /// Returns the name reported for this session according to `policy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The `NamePolicy` used to pick one of the names in the rename history.
+    /// * `match_end` - The timestamp of the last line of the match the session belongs to.
+    pub fn reported_name(&self, policy: NamePolicy, match_end: u32) -> Option<&str> {
+        match policy {
+            NamePolicy::First => self.names.first().map(|name_use| name_use.name.as_str()),
+            NamePolicy::Last => self.names.last().map(|name_use| name_use.name.as_str()),
+            NamePolicy::MostUsed => {
+                let end = self.disconnected_at.unwrap_or(match_end);
+                let mut usage: Vec<(&str, u32)> = Vec::new();
+                for (idx, name_use) in self.names.iter().enumerate() {
+                    let until = self.names.get(idx + 1).map_or(end, |next| next.since);
+                    let held = until.saturating_sub(name_use.since);
+                    match usage.iter_mut().find(|(name, _)| *name == name_use.name) {
+                        Some((_, total)) => *total += held,
+                        None => usage.push((&name_use.name, held)),
+                    }
+                }
+                // `max_by_key` keeps the last maximum, so reverse to break ties by first use.
+                usage
+                    .into_iter()
+                    .rev()
+                    .max_by_key(|(_, held)| *held)
+                    .map(|(name, _)| name)
+            }
+        }
+    }
+}
+
+/// Processes a `ClientConnect` line and opens a new session for the client.
+///
+/// # Arguments
+///
+/// * `line` - A string containing the log line to be parsed, e.g. `20:34 ClientConnect: 2`.
+/// * `match_data` - A mutable reference to the `MatchData` struct representing the match state.
+///
+/// # Returns
+///
+/// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if a problem
+///   is encountered during parsing.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if the line does not contain a valid client id.
+pub fn process_connect_line(line: &str, match_data: &mut MatchData) -> Result<(), LogError> {
+    let client_id = parse_client_id(line, "ClientConnect:")?;
+    let timestamp = parse_timestamp(line).unwrap_or(match_data.ended_at);
+
+    open_session(client_id, timestamp, match_data);
+    Ok(())
+}
+
+/// Processes a `ClientBegin` line, recording when the client entered the game.
+///
+/// # Arguments
+///
+/// * `line` - A string containing the log line to be parsed, e.g. `20:37 ClientBegin: 2`.
+/// * `match_data` - A mutable reference to the `MatchData` struct representing the match state.
+///
+/// # Returns
+///
+/// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if a problem
+///   is encountered during parsing.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if the line does not contain a valid client id.
+pub fn process_begin_line(line: &str, match_data: &mut MatchData) -> Result<(), LogError> {
+    let client_id = parse_client_id(line, "ClientBegin:")?;
+    let timestamp = parse_timestamp(line).unwrap_or(match_data.ended_at);
+
+    let session = match current_session(client_id, match_data) {
+        Some(session) => session,
+        None => open_session(client_id, timestamp, match_data),
+    };
+    if session.began_at.is_none() {
+        session.began_at = Some(timestamp);
+    }
+    Ok(())
+}
+
+/// Processes a `ClientDisconnect` line and closes the client's session.
+///
+/// A disconnection shortly after a death streak is flagged as a rage quit. Clients without an open
+/// session in the match (e.g. in logs that start mid-match) have nothing to close, so the line is
+/// ignored.
+///
+/// # Arguments
+///
+/// * `line` - A string containing the log line to be parsed, e.g. `20:38 ClientDisconnect: 2`.
+/// * `match_data` - A mutable reference to the `MatchData` struct representing the match state.
+///
+/// # Returns
+///
+/// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if a problem
+///   is encountered during parsing.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if the line does not contain a valid client id.
+pub fn process_disconnect_line(line: &str, match_data: &mut MatchData) -> Result<(), LogError> {
+    let client_id = parse_client_id(line, "ClientDisconnect:")?;
+    let timestamp = parse_timestamp(line).unwrap_or(match_data.ended_at);
+
+    let Some(session) = current_session(client_id, match_data) else {
+        return Ok(());
+    };
+    session.disconnected_at = Some(timestamp);
+    session.rage_quit = session.death_streak >= RAGE_QUIT_DEATH_STREAK
+        && session
+            .last_death_at
+            .is_some_and(|death| timestamp.saturating_sub(death) <= RAGE_QUIT_WINDOW_SECS);
+    Ok(())
+}
+
+/// Assigns the player name and user info from a `ClientUserinfoChanged` line to the client's session.
+///
+/// A name different from the current one is appended to the session's rename history, so the slot
+/// keeps a single identity. The remaining key/value pairs (model, colours, ...) replace the session's
+/// `userinfo`. Clients that changed their user info without a `ClientConnect` (e.g. logs
+/// that start mid-match) get a session opened at the time of the line.
+///
+/// # Arguments
+///
+/// * `line` - A string containing the log line to be parsed.
+/// * `player_name` - The name extracted from the line. Changing only the colours of the name is not a
+///   rename.
+/// * `match_data` - A mutable reference to the `MatchData` struct representing the match state.
+pub fn name_session(line: &str, player_name: &PlayerName, match_data: &mut MatchData) {
+    let Ok(client_id) = parse_client_id(line, "ClientUserinfoChanged:") else {
+        return;
+    };
+    let timestamp = parse_timestamp(line).unwrap_or(match_data.ended_at);
+
+    let session = match current_session(client_id, match_data) {
+        Some(session) => session,
+        None => open_session(client_id, timestamp, match_data),
+    };
+    session.userinfo = parse_userinfo(line);
+    session.raw_name = player_name.raw.clone();
+    if session.name != player_name.clean {
+        session.name = player_name.clean.clone();
+        session.names.push(NameUse {
+            name: player_name.clean.clone(),
+            since: timestamp,
+        });
+    }
+}
+
+/// Attributes a kill line to the sessions of the client slots involved.
+///
+/// The killer's session scores a kill, or the victim's session loses one when killed by the world.
+/// The victim's death streak grows by one, while a kill of another player resets the killer's streak.
+/// Kills are kept on the sessions until the match is closed, so renames do not split them.
+///
+/// # Arguments
+///
+/// * `kill` - The parsed kill line, e.g. `20:54 Kill: 1022 2 22: <world> killed ...`.
+/// * `timestamp` - The time of the kill line, if it has one.
+/// * `match_data` - A mutable reference to the `MatchData` struct representing the match state.
+///
+/// # Returns
+///
+/// * `bool` - `true` if the kill was attributed, `false` if the client ids are missing or do not belong
+///   to a named session, in which case the caller should attribute it by name.
+pub fn attribute_kill(
+    kill: &KillEvent,
+    timestamp: Option<u32>,
+    match_data: &mut MatchData,
+) -> bool {
+    let (Some(killer), Some(victim)) = (kill.killer_id, kill.victim_id) else {
+        return false;
+    };
+    let scorer = if killer == WORLD_CLIENT_ID {
+        victim
+    } else {
+        killer
+    };
+    let named = current_session(scorer, match_data).is_some_and(|session| !session.name.is_empty());
+    if !named {
+        return false;
+    }
+    let timestamp = timestamp.unwrap_or(match_data.ended_at);
+
+    if let Some(session) = current_session(scorer, match_data) {
+        session.kills += if killer == WORLD_CLIENT_ID { -1 } else { 1 };
+        if killer == victim {
+            session.self_kills += 1;
+        }
+    }
+    if killer != victim && killer != WORLD_CLIENT_ID {
+        if let Some(session) = current_session(killer, match_data) {
+            session.death_streak = 0;
+        }
+    }
+    if let Some(session) = current_session(victim, match_data) {
+        session.death_streak += 1;
+        session.last_death_at = Some(timestamp);
+    }
+    true
+}
+
+/// Closes the sessions of a finished match.
+///
+/// Flags the sessions that joined late or left early, settles the name of each session according to
+/// `policy`, and credits the kills kept on the sessions to those names. Names a slot used before or
+/// after being renamed are dropped from `MatchData::players`. Must be called once per match.
+///
+/// # Arguments
+///
+/// * `match_data` - A mutable reference to the `MatchData` struct representing the finished match.
+/// * `policy` - The `NamePolicy` used to pick the name reported for renamed players.
+pub fn close_sessions(match_data: &mut MatchData, policy: NamePolicy) {
+    let (started_at, ended_at) = (match_data.started_at, match_data.ended_at);
+
+    for session in match_data.sessions.iter_mut() {
+        session.joined_late = session.connected_at > started_at + JOIN_LATE_GRACE_SECS;
+        session.left_early = session
+            .disconnected_at
+            .is_some_and(|disconnected| disconnected + LEAVE_EARLY_GRACE_SECS < ended_at);
+        if let Some(name) = session.reported_name(policy, ended_at) {
+            session.name = name.to_string();
+        }
+    }
+
+    for name_use in match_data
+        .sessions
+        .iter()
+        .flat_map(|session| &session.names)
+    {
+        match_data.players.remove(&name_use.name);
+    }
+    for session in match_data.sessions.iter().filter(|s| !s.name.is_empty()) {
+        match_data.players.insert(session.name.clone());
+        add_count(&mut match_data.kills, &session.name, session.kills);
+        if session.self_kills != 0 {
+            add_count(
+                &mut match_data.self_kills,
+                &session.name,
+                session.self_kills,
+            );
+        }
+    }
+}
+
+/// Sums the time each player spent in the game across all matches.
+///
+/// # Arguments
+///
+/// * `matches` - A slice of `Match` structs containing the sessions of each match.
+///
+/// # Returns
+///
+/// * `Vec<PlayerPlaytime>` - The playtime of every named player, sorted in descending order.
+pub fn playtime_by_player(matches: &[Match]) -> Vec<PlayerPlaytime> {
+    let mut totals: HashMap<&str, u32> = HashMap::new();
+
+    for mat in matches {
+        for session in mat.data.sessions.iter().filter(|s| !s.name.is_empty()) {
+            *totals.entry(session.name.as_str()).or_insert(0) +=
+                session.duration(mat.data.ended_at);
+        }
+    }
+
+    let mut playtime: Vec<PlayerPlaytime> = totals
+        .into_iter()
+        .map(|(name, seconds)| PlayerPlaytime {
+            name: name.to_string(),
+            seconds,
+        })
+        .collect();
+    playtime.sort_by(|a, b| b.seconds.cmp(&a.seconds).then_with(|| a.name.cmp(&b.name)));
+    playtime
+}
+
+/// Returns the open session of a client, if any.
+fn current_session(client_id: i32, match_data: &mut MatchData) -> Option<&mut PlayerSession> {
+    match_data
+        .sessions
+        .iter_mut()
+        .rev()
+        .find(|session| session.client_id == client_id && session.disconnected_at.is_none())
+}
+
+/// Opens a new session for a client, closing a previous one left open on the same slot.
+fn open_session(client_id: i32, timestamp: u32, match_data: &mut MatchData) -> &mut PlayerSession {
+    if let Some(previous) = current_session(client_id, match_data) {
+        previous.disconnected_at = Some(timestamp);
+    }
+    match_data.sessions.push(PlayerSession {
+        client_id,
+        connected_at: timestamp,
+        ..Default::default()
+    });
+    match_data
+        .sessions
+        .last_mut()
+        .expect("session was just pushed")
+}
+
+/// Extracts the client id that follows `tag` in a line.
+fn parse_client_id(line: &str, tag: &str) -> Result<i32, LogError> {
+    event::parse_client_id(line, tag)
+        .ok_or_else(|| LogError::SessionError(format!("Unable to find the client id in: {}", line)))
+}
+
+/// Extracts the backslash separated key/value pairs of a `ClientUserinfoChanged` line.
+fn parse_userinfo(line: &str) -> HashMap<String, String> {
+    let Some((_, rest)) = line.split_once("ClientUserinfoChanged:") else {
+        return HashMap::new();
+    };
+    let info = rest
+        .trim_start()
+        .split_once(' ')
+        .map_or("", |(_, info)| info);
+    let fields: Vec<&str> = info.split('\\').collect();
+    fields
+        .chunks_exact(2)
+        .map(|pair| (pair[0].to_string(), pair[1].to_string()))
+        .collect()
+}
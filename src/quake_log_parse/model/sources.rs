@@ -0,0 +1,296 @@
+use std::{
+    collections::HashSet,
+    fs::read_dir,
+    path::{Path, PathBuf},
+    thread,
+};
+
+use super::{
+    error::LogError,
+    identity::{process_identity_ranking, AliasMap},
+    input::read_log_file,
+    log_model::{process_events_matches_with_options, Match, ParseOptions, PlayerScore},
+    parallel::process_events_matches_parallel,
+    session::{playtime_by_player, PlayerPlaytime},
+};
+
+/// The size from which a single log file is worth splitting over several threads.
+pub const PARALLEL_MIN_BYTES: usize = 4 * 1024 * 1024;
+
+/// The matches of one log file and the ranking of its players.
+pub struct FileReport {
+    pub file: String,
+    pub matches: Vec<Match>,
+    pub ranking: Vec<PlayerScore>,
+}
+
+/// The report of several log files, with the ranking and playtime combined across all of them.
+pub struct LogsReport {
+    pub files: Vec<FileReport>,
+    pub ranking: Vec<PlayerScore>,
+    pub playtime: Vec<PlayerPlaytime>,
+}
+
+/// Expands the inputs given by the user into the list of log files to read.
+///
+/// Each input can be a file, a directory (every regular, non hidden file directly inside it, by name)
+/// or a glob pattern such as `logs/*/games.log*`. Files are returned in the order of the inputs, without
+/// duplicates.
+///
+/// # Arguments
+///
+/// * `inputs` - A slice of strings with the files, directories and patterns to read.
+///
+/// # Returns
+///
+/// * `Result<Vec<PathBuf>, LogError>` - A `Result` with the paths of the log files, or an error (`Err`)
+///   if an input cannot be expanded.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if any of the following conditions are met:
+///
+/// * A file or directory does not exist or cannot be listed.
+/// * A glob pattern is invalid or matches no file.
+pub fn resolve_inputs(inputs: &[String]) -> Result<Vec<PathBuf>, LogError> {
+    let mut paths = Vec::new();
+
+    for input in inputs {
+        let path = Path::new(input);
+        if path.is_dir() {
+            paths.extend(list_directory(path)?);
+        } else if path.exists() {
+            paths.push(path.to_path_buf());
+        } else if input.contains(['*', '?', '[']) {
+            let matched = glob_files(input)?;
+            if matched.is_empty() {
+                return Err(LogError::ReadLogError(format!(
+                    "No log file matches the pattern: {}",
+                    input
+                )));
+            }
+            paths.extend(matched);
+        } else {
+            return Err(LogError::ReadLogError(format!(
+                "The log file does not exist: {}",
+                input
+            )));
+        }
+    }
+
+    // A file given twice (e.g. by name and through its directory) is only read once.
+    let mut seen = HashSet::new();
+    paths.retain(|path| seen.insert(path.clone()));
+    Ok(paths)
+}
+
+/// Parses several log files into one list of matches.
+///
+/// Match ids are numbered across all the files in order, so they stay unique, while
+/// `MatchData::source_file` and `MatchData::source_id` record the file and the id of the match within
+/// it. With `parallel`, files are parsed on one thread per available core, and files larger than
+/// `PARALLEL_MIN_BYTES` are themselves split at match boundaries over the cores left
+/// (`parallel::process_events_matches_parallel`); the result is the same.
+///
+/// # Arguments
+///
+/// * `paths` - A slice of paths of the log files, in the order their matches are numbered.
+/// * `options` - A reference to the `ParseOptions` to parse with.
+/// * `parallel` - Whether to parse the files in parallel.
+///
+/// # Returns
+///
+/// * `Result<Vec<Match>, LogError>` - A `Result` with the matches of every file, or an error (`Err`) if
+///   a file cannot be read or parsed.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if any file cannot be read or parsed, naming the file.
+pub fn process_files(
+    paths: &[PathBuf],
+    options: &ParseOptions,
+    parallel: bool,
+) -> Result<Vec<Match>, LogError> {
+    let threads = if parallel {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        1
+    };
+
+    let per_file: Vec<Result<Vec<Match>, LogError>> = if threads > 1 && paths.len() > 1 {
+        let chunk_size = paths.len().div_ceil(threads);
+        let threads_per_file = (threads / paths.len()).max(1);
+        thread::scope(|scope| {
+            let handles: Vec<_> = paths
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|path| process_file(path, options, threads_per_file))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("log parsing thread panicked"))
+                .collect()
+        })
+    } else {
+        paths
+            .iter()
+            .map(|path| process_file(path, options, threads))
+            .collect()
+    };
+
+    let mut matches = Vec::new();
+    for file_matches in per_file {
+        for mut mat in file_matches? {
+            mat.id = matches.len() as i32 + 1;
+            matches.push(mat);
+        }
+    }
+    Ok(matches)
+}
+
+/// Builds the report of several log files.
+///
+/// Players are ranked by identity within each file and across all the files, so a player seen on
+/// several servers is ranked once with the kills of every file.
+///
+/// # Arguments
+///
+/// * `paths` - A slice of paths of the log files.
+/// * `options` - A reference to the `ParseOptions` to parse with.
+/// * `aliases` - A reference to the `AliasMap` supplied by the user.
+/// * `parallel` - Whether to parse the files in parallel.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if any file cannot be read or parsed.
+pub fn process_logs(
+    paths: &[PathBuf],
+    options: &ParseOptions,
+    aliases: &AliasMap,
+    parallel: bool,
+) -> Result<LogsReport, LogError> {
+    let matches = process_files(paths, options, parallel)?;
+    let files: Vec<String> = paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect();
+    Ok(report_matches(&files, matches, aliases))
+}
+
+/// Builds the report of matches already parsed, e.g. loaded from a database.
+///
+/// Matches are grouped by their `MatchData::source_file`, in the order of `files`; matches of files not
+/// listed only count in the combined ranking and playtime.
+///
+/// # Arguments
+///
+/// * `files` - A slice of the names of the log files to report, in order.
+/// * `matches` - A vector of the `Match` structs of every file.
+/// * `aliases` - A reference to the `AliasMap` supplied by the user.
+///
+/// # Returns
+///
+/// * `LogsReport` - The report of each file with the ranking and playtime combined across all of them.
+pub fn report_matches(files: &[String], matches: Vec<Match>, aliases: &AliasMap) -> LogsReport {
+    let mut ranking = Vec::new();
+    process_identity_ranking(&matches, aliases, &mut ranking);
+    let playtime = playtime_by_player(&matches);
+
+    let mut by_file: Vec<Vec<Match>> = files.iter().map(|_| Vec::new()).collect();
+    for mat in matches {
+        if let Some(idx) = files.iter().position(|file| *file == mat.data.source_file) {
+            by_file[idx].push(mat);
+        }
+    }
+
+    let files = files
+        .iter()
+        .zip(by_file)
+        .map(|(file, file_matches)| {
+            let mut file_ranking = Vec::new();
+            process_identity_ranking(&file_matches, aliases, &mut file_ranking);
+            FileReport {
+                file: file.clone(),
+                matches: file_matches,
+                ranking: file_ranking,
+            }
+        })
+        .collect();
+
+    LogsReport {
+        files,
+        ranking,
+        playtime,
+    }
+}
+
+/// Reads and parses a single log file, tagging its matches with the file they come from.
+///
+/// Files of at least `PARALLEL_MIN_BYTES` are parsed on `threads` threads.
+fn process_file(
+    path: &Path,
+    options: &ParseOptions,
+    threads: usize,
+) -> Result<Vec<Match>, LogError> {
+    let content = read_log_file(path)?;
+    let mut matches = Vec::new();
+    let result = if threads > 1 && content.len() >= PARALLEL_MIN_BYTES {
+        process_events_matches_parallel(&mut matches, &content, options, threads)
+    } else {
+        process_events_matches_with_options(&mut matches, &content, options)
+    };
+    result.map_err(|err| {
+        LogError::ReadLogError(format!(
+            "Error {:?} in the log file {}",
+            err,
+            path.display()
+        ))
+    })?;
+
+    let file = path.display().to_string();
+    for mat in matches.iter_mut() {
+        mat.data.source_file = file.clone();
+        mat.data.source_id = mat.id;
+    }
+    Ok(matches)
+}
+
+/// Lists the regular, non hidden files directly inside a directory, sorted by name.
+fn list_directory(dir: &Path) -> Result<Vec<PathBuf>, LogError> {
+    let entries = read_dir(dir).map_err(|err| {
+        LogError::ReadLogError(format!(
+            "Error listing the directory {}: {}",
+            dir.display(),
+            err
+        ))
+    })?;
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            !path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Expands a glob pattern into the regular files it matches.
+fn glob_files(pattern: &str) -> Result<Vec<PathBuf>, LogError> {
+    let entries = glob::glob(pattern).map_err(|err| {
+        LogError::ReadLogError(format!("Invalid file pattern {}: {}", pattern, err))
+    })?;
+    Ok(entries
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .collect())
+}
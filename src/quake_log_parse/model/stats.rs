@@ -0,0 +1,133 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use super::{
+    identity::{resolve_identities, AliasMap},
+    intern::Interner,
+    log_model::Match,
+};
+
+/// The statistics of a player across matches, as identified by `resolve_identities`.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PlayerStats {
+    pub name: String,
+    /// The position of the player in the ranking by kills, from 1.
+    pub rank: usize,
+    pub kills: i32,
+    /// Every name the player was seen with, including `name`.
+    pub aliases: Vec<String>,
+    /// The ids of the matches the player took part in.
+    pub matches: Vec<i32>,
+    /// The seconds the player spent in the game across all matches.
+    pub playtime: u32,
+}
+
+/// The kills of a means of death across matches.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct WeaponStats {
+    pub means: String,
+    pub kills: i32,
+}
+
+/// Builds the statistics of every player, in ranking order.
+///
+/// # Arguments
+///
+/// * `matches` - A slice of `Match` structs containing match data.
+/// * `aliases` - A reference to the `AliasMap` supplied by the user.
+///
+/// # Returns
+///
+/// * `Vec<PlayerStats>` - The statistics of each player, sorted in descending order of kills.
+pub fn player_stats(matches: &[Match], aliases: &AliasMap) -> Vec<PlayerStats> {
+    let identities = resolve_identities(matches, aliases);
+
+    // Every name of an identity, canonical or not, leads back to its position in the ranking.
+    let mut by_name: HashMap<&str, usize> = HashMap::new();
+    for (idx, identity) in identities.iter().enumerate() {
+        by_name.insert(&identity.name, idx);
+        for alias in &identity.aliases {
+            by_name.entry(alias).or_insert(idx);
+        }
+    }
+    let position = |name: &str| {
+        by_name
+            .get(aliases.canonical(name))
+            .or_else(|| by_name.get(name))
+            .copied()
+    };
+
+    let mut stats: Vec<PlayerStats> = identities
+        .iter()
+        .enumerate()
+        .map(|(idx, identity)| {
+            let mut names = identity.aliases.clone();
+            if !names.contains(&identity.name) {
+                names.insert(0, identity.name.clone());
+            }
+            PlayerStats {
+                name: identity.name.clone(),
+                rank: idx + 1,
+                kills: identity.kills,
+                aliases: names,
+                matches: Vec::new(),
+                playtime: 0,
+            }
+        })
+        .collect();
+
+    for mat in matches {
+        let mut seen = HashSet::new();
+        let names = mat.data.players.iter().chain(mat.data.kills.keys());
+        for idx in names.filter_map(|name| position(name)) {
+            seen.insert(idx);
+        }
+        for session in mat.data.sessions.iter().filter(|s| !s.name.is_empty()) {
+            if let Some(idx) = position(&session.name) {
+                seen.insert(idx);
+                stats[idx].playtime += session.duration(mat.data.ended_at);
+            }
+        }
+        for idx in seen {
+            stats[idx].matches.push(mat.id);
+        }
+    }
+    stats
+}
+
+/// Sums the kills by means of death across matches.
+///
+/// # Arguments
+///
+/// * `matches` - The matches to sum, e.g. the ones selected by a `MatchFilter`.
+///
+/// # Returns
+///
+/// * `Vec<WeaponStats>` - The kills of each means of death, sorted in descending order, ties broken
+///   by name.
+pub fn weapon_stats<'a>(matches: impl IntoIterator<Item = &'a Match>) -> Vec<WeaponStats> {
+    // The same few means of death are seen in every match, so their totals live in a table indexed
+    // by symbol, as in `process_ranking`.
+    let mut means_names = Interner::new();
+    let mut totals: Vec<i32> = Vec::new();
+    for mat in matches {
+        for (means, kills) in &mat.data.kills_by_means {
+            let symbol = means_names.intern(means);
+            if symbol.index() == totals.len() {
+                totals.push(0);
+            }
+            totals[symbol.index()] += kills;
+        }
+    }
+
+    let mut weapons: Vec<WeaponStats> = means_names
+        .iter()
+        .map(|(symbol, means)| WeaponStats {
+            means: means.to_string(),
+            kills: totals[symbol.index()],
+        })
+        .collect();
+    weapons.sort_by(|a, b| b.kills.cmp(&a.kills).then_with(|| a.means.cmp(&b.means)));
+    weapons
+}
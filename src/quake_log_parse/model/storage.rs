@@ -0,0 +1,696 @@
+use std::{collections::HashMap, path::Path};
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use sha2::{Digest, Sha256};
+
+use super::{
+    error::LogError,
+    event::{KillEvent, LogEvent, LogLine},
+    input::read_log_file,
+    log_model::{process_events_chunk, Match, MatchData, ParseOptions, ServerScore},
+    parallel::match_chunks,
+    player_name::strip_colors,
+    session::{NameUse, PlayerSession},
+};
+
+/// The schema migrations, in order. The database records how many were applied in `user_version`.
+///
+/// A migration is never edited once released; changes to the schema go into a new one.
+const MIGRATIONS: &[&str] = &[
+    r#"
+    CREATE TABLE players (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE
+    );
+    CREATE TABLE matches (
+        id INTEGER PRIMARY KEY,
+        content_hash TEXT NOT NULL UNIQUE,
+        source_file TEXT NOT NULL,
+        source_offset INTEGER NOT NULL,
+        source_id INTEGER NOT NULL,
+        started_at INTEGER NOT NULL,
+        ended_at INTEGER NOT NULL,
+        total_kills INTEGER NOT NULL
+    );
+    CREATE TABLE server_config (
+        match_id INTEGER NOT NULL REFERENCES matches (id) ON DELETE CASCADE,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (match_id, key)
+    );
+    CREATE TABLE match_players (
+        match_id INTEGER NOT NULL REFERENCES matches (id) ON DELETE CASCADE,
+        player_id INTEGER NOT NULL REFERENCES players (id),
+        listed INTEGER NOT NULL,
+        kills INTEGER,
+        PRIMARY KEY (match_id, player_id)
+    );
+    CREATE TABLE kill_events (
+        match_id INTEGER NOT NULL REFERENCES matches (id) ON DELETE CASCADE,
+        seq INTEGER NOT NULL,
+        at INTEGER,
+        killer_client INTEGER,
+        victim_client INTEGER,
+        killer_id INTEGER REFERENCES players (id),
+        victim_id INTEGER REFERENCES players (id),
+        means TEXT NOT NULL,
+        PRIMARY KEY (match_id, seq)
+    );
+    CREATE TABLE weapon_stats (
+        match_id INTEGER NOT NULL REFERENCES matches (id) ON DELETE CASCADE,
+        means TEXT NOT NULL,
+        kills INTEGER NOT NULL,
+        PRIMARY KEY (match_id, means)
+    );
+    CREATE TABLE scores (
+        match_id INTEGER NOT NULL REFERENCES matches (id) ON DELETE CASCADE,
+        seq INTEGER NOT NULL,
+        client_id INTEGER NOT NULL,
+        player_id INTEGER NOT NULL REFERENCES players (id),
+        raw_name TEXT NOT NULL,
+        score INTEGER NOT NULL,
+        ping INTEGER NOT NULL,
+        PRIMARY KEY (match_id, seq)
+    );
+    CREATE TABLE sessions (
+        id INTEGER PRIMARY KEY,
+        match_id INTEGER NOT NULL REFERENCES matches (id) ON DELETE CASCADE,
+        client_id INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        raw_name TEXT NOT NULL,
+        connected_at INTEGER NOT NULL,
+        began_at INTEGER,
+        disconnected_at INTEGER,
+        joined_late INTEGER NOT NULL,
+        left_early INTEGER NOT NULL,
+        rage_quit INTEGER NOT NULL,
+        kills INTEGER NOT NULL
+    );
+    CREATE TABLE session_names (
+        session_id INTEGER NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+        seq INTEGER NOT NULL,
+        player_id INTEGER NOT NULL REFERENCES players (id),
+        since INTEGER NOT NULL,
+        PRIMARY KEY (session_id, seq)
+    );
+    CREATE TABLE session_userinfo (
+        session_id INTEGER NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (session_id, key)
+    );
+    CREATE INDEX kill_events_killer ON kill_events (killer_id);
+    CREATE INDEX kill_events_victim ON kill_events (victim_id);
+    CREATE INDEX sessions_match ON sessions (match_id);
+"#,
+    r#"
+    -- Matches imported before this version are not known to be completed.
+    ALTER TABLE matches ADD COLUMN completed INTEGER NOT NULL DEFAULT 0;
+"#,
+    r#"
+    -- The length of the lines hashed in content_hash, to tell a match that grew since it was
+    -- imported. Matches imported before this version are never replaced.
+    ALTER TABLE matches ADD COLUMN content_length INTEGER;
+"#,
+    r#"
+    -- Kills of players by themselves, for the score the server reports. Matches imported before
+    -- this version count the kill events of a player on themselves under the same name.
+    ALTER TABLE match_players ADD COLUMN self_kills INTEGER NOT NULL DEFAULT 0;
+    UPDATE match_players SET self_kills = (
+        SELECT COUNT(*) FROM kill_events
+        WHERE kill_events.match_id = match_players.match_id
+          AND kill_events.killer_id = match_players.player_id
+          AND kill_events.victim_id = match_players.player_id
+    );
+"#,
+];
+
+/// The outcome of importing a log into the database.
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportSummary {
+    /// Matches added to the database.
+    pub imported: usize,
+    /// Matches already in the database, e.g. from a previous import of the same log.
+    pub skipped: usize,
+    /// Matches stored again because they were still being played when the log was last imported.
+    pub updated: usize,
+}
+
+/// A SQLite database of parsed matches.
+///
+/// Matches are stored in a normalised schema (players, matches, server settings, kill events, weapon
+/// stats, scores and sessions) and can be loaded back as `Match` structs to be reported without
+/// parsing the logs again.
+pub struct Storage {
+    connection: Connection,
+}
+
+impl Storage {
+    /// Opens or creates a database, applying the migrations it is missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the database file.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Storage, LogError>` - A `Result` with the opened database, or an error (`Err`) if it
+    ///   cannot be opened or migrated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if any of the following conditions are met:
+    ///
+    /// * The file cannot be opened or is not a SQLite database.
+    /// * The database was created by a newer version, with migrations this version does not know.
+    pub fn open(path: &Path) -> Result<Self, LogError> {
+        Self::migrate(Connection::open(path)?)
+    }
+
+    /// Opens a database kept in memory, mostly for tests.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the database cannot be created.
+    pub fn open_in_memory() -> Result<Self, LogError> {
+        Self::migrate(Connection::open_in_memory()?)
+    }
+
+    /// Returns the number of migrations applied to the database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the version cannot be read.
+    pub fn schema_version(&self) -> Result<usize, LogError> {
+        let version: i64 = self
+            .connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        Ok(version as usize)
+    }
+
+    fn migrate(connection: Connection) -> Result<Self, LogError> {
+        connection.pragma_update(None, "foreign_keys", true)?;
+        let mut storage = Storage { connection };
+        let version = storage.schema_version()?;
+        if version > MIGRATIONS.len() {
+            return Err(LogError::StorageError(format!(
+                "The database schema version {} is newer than the supported version {}",
+                version,
+                MIGRATIONS.len()
+            )));
+        }
+
+        for (idx, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let transaction = storage.connection.transaction()?;
+            transaction.execute_batch(migration)?;
+            transaction.pragma_update(None, "user_version", idx as i64 + 1)?;
+            transaction.commit()?;
+        }
+        Ok(storage)
+    }
+
+    /// Parses a log file and stores its matches, skipping the ones already stored.
+    ///
+    /// The file may be compressed (see `input::read_log_file`).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the log file.
+    /// * `options` - A reference to the `ParseOptions` to parse with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the file cannot be read or parsed, or the matches cannot
+    /// be stored. Nothing is stored in that case.
+    pub fn import_file(
+        &mut self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ImportSummary, LogError> {
+        let content = read_log_file(path)?;
+        self.import_log(&path.display().to_string(), &content, options)
+    }
+
+    /// Parses the content of a log and stores its matches, skipping the ones already stored.
+    ///
+    /// Each match is identified by the SHA-256 hash of its lines, from its `InitGame:` line to the
+    /// next one, so importing the same log twice stores its matches once. When a log grew since it
+    /// was imported, the new matches are added, and a match that was still being played, whose stored
+    /// lines are the beginning of its lines now, replaces the stored one, keeping its place. Lines
+    /// before the first match are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_file` - The name of the log the content was read from.
+    /// * `content` - A string containing the content of the log.
+    /// * `options` - A reference to the `ParseOptions` to parse with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ImportSummary, LogError>` - A `Result` with the number of matches imported, skipped
+    ///   and updated, or an error (`Err`) if the log cannot be parsed or stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if a match cannot be parsed or stored. Nothing is stored in
+    /// that case.
+    pub fn import_log(
+        &mut self,
+        source_file: &str,
+        content: &str,
+        options: &ParseOptions,
+    ) -> Result<ImportSummary, LogError> {
+        let chunks = match_chunks(content);
+        let transaction = self.connection.transaction()?;
+        let mut players = PlayerIds::default();
+        let mut summary = ImportSummary::default();
+
+        for (idx, &(offset, chunk)) in chunks.iter().enumerate() {
+            let hash = format!("{:x}", Sha256::digest(chunk.as_bytes()));
+            let known = transaction
+                .query_row(
+                    "SELECT 1 FROM matches WHERE content_hash = ?1",
+                    [&hash],
+                    |_| Ok(()),
+                )
+                .optional()?;
+            if known.is_some() {
+                summary.skipped += 1;
+                continue;
+            }
+            let grown = grown_match(&transaction, source_file, offset, chunk)?;
+
+            let mut parsed = Vec::new();
+            process_events_chunk(&mut parsed, chunk, options).map_err(|err| {
+                LogError::ReadLogError(format!("Error {:?} in the log file {}", err, source_file))
+            })?;
+            let Some(mat) = parsed.pop() else {
+                continue;
+            };
+
+            if let Some(row_id) = grown {
+                transaction
+                    .prepare_cached("DELETE FROM matches WHERE id = ?1")?
+                    .execute([row_id])?;
+            }
+            let source = MatchSource {
+                row_id: grown,
+                file: source_file,
+                offset,
+                id: idx as i32 + 1,
+                hash: &hash,
+            };
+            insert_match(&transaction, &mut players, &source, &mat.data, chunk)?;
+            match grown {
+                Some(_) => summary.updated += 1,
+                None => summary.imported += 1,
+            }
+        }
+
+        transaction.commit()?;
+        Ok(summary)
+    }
+
+    /// Loads every stored match, in the order they were imported.
+    ///
+    /// Matches are numbered from 1 in that order, while `MatchData::source_file` and
+    /// `MatchData::source_id` tell where each one was read from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Match>, LogError>` - A `Result` with the matches, or an error (`Err`) if the
+    ///   database cannot be read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the database cannot be read.
+    pub fn load_matches(&self) -> Result<Vec<Match>, LogError> {
+        let connection = &self.connection;
+        let mut statement = connection.prepare(
+            "SELECT id, source_file, source_id, started_at, ended_at, total_kills, completed
+             FROM matches ORDER BY id",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                MatchData {
+                    source_file: row.get(1)?,
+                    source_id: row.get(2)?,
+                    started_at: row.get(3)?,
+                    ended_at: row.get(4)?,
+                    total_kills: row.get(5)?,
+                    completed: row.get(6)?,
+                    ..Default::default()
+                },
+            ))
+        })?;
+
+        let mut matches = Vec::new();
+        for row in rows {
+            let (match_id, mut data) = row?;
+            load_match_details(connection, match_id, &mut data)?;
+            matches.push(Match {
+                id: matches.len() as i32 + 1,
+                data,
+            });
+        }
+        Ok(matches)
+    }
+}
+
+/// Where a stored match was read from.
+struct MatchSource<'a> {
+    /// The id of the row to store the match in, or `None` for a new row.
+    row_id: Option<i64>,
+    file: &'a str,
+    offset: usize,
+    id: i32,
+    hash: &'a str,
+}
+
+/// The ids of the players stored during an import, to avoid looking them up for every row.
+#[derive(Default)]
+struct PlayerIds(HashMap<String, i64>);
+
+impl PlayerIds {
+    fn get(&mut self, transaction: &Transaction, name: &str) -> Result<i64, LogError> {
+        if let Some(&id) = self.0.get(name) {
+            return Ok(id);
+        }
+        transaction
+            .prepare_cached("INSERT OR IGNORE INTO players (name) VALUES (?1)")?
+            .execute([name])?;
+        let id = transaction
+            .prepare_cached("SELECT id FROM players WHERE name = ?1")?
+            .query_row([name], |row| row.get(0))?;
+        self.0.insert(name.to_string(), id);
+        Ok(id)
+    }
+}
+
+/// Finds the stored match read at the same place of the same log that was still being played when
+/// it was imported, and whose lines are the beginning of `chunk`, returning the id of its row.
+fn grown_match(
+    transaction: &Transaction,
+    source_file: &str,
+    offset: usize,
+    chunk: &str,
+) -> Result<Option<i64>, LogError> {
+    let stored = transaction
+        .prepare_cached(
+            "SELECT id, content_hash, content_length FROM matches
+             WHERE source_file = ?1 AND source_offset = ?2 AND completed = 0
+                 AND content_length IS NOT NULL",
+        )?
+        .query_row(params![source_file, offset as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .optional()?;
+
+    Ok(stored.and_then(|(row_id, hash, length)| {
+        let prefix = chunk.as_bytes().get(..length as usize)?;
+        (format!("{:x}", Sha256::digest(prefix)) == hash).then_some(row_id)
+    }))
+}
+
+fn insert_match(
+    transaction: &Transaction,
+    players: &mut PlayerIds,
+    source: &MatchSource,
+    data: &MatchData,
+    chunk: &str,
+) -> Result<(), LogError> {
+    transaction
+        .prepare_cached(
+            "INSERT INTO matches (id, content_hash, source_file, source_offset, source_id,
+                 started_at, ended_at, total_kills, completed, content_length)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )?
+        .execute(params![
+            source.row_id,
+            source.hash,
+            source.file,
+            source.offset as i64,
+            source.id,
+            data.started_at,
+            data.ended_at,
+            data.total_kills,
+            data.completed,
+            chunk.len() as i64
+        ])?;
+    let match_id = transaction.last_insert_rowid();
+
+    for (key, value) in &data.settings {
+        transaction
+            .prepare_cached("INSERT OR REPLACE INTO server_config VALUES (?1, ?2, ?3)")?
+            .execute(params![match_id, key, value])?;
+    }
+
+    // Players listed in the match, and names credited with kills, which are usually the same.
+    let mut names: Vec<&String> = data.players.iter().chain(data.kills.keys()).collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        let player_id = players.get(transaction, name)?;
+        transaction
+            .prepare_cached("INSERT INTO match_players VALUES (?1, ?2, ?3, ?4, ?5)")?
+            .execute(params![
+                match_id,
+                player_id,
+                data.players.contains(name),
+                data.kills.get(name),
+                data.self_kills.get(name).copied().unwrap_or(0)
+            ])?;
+    }
+
+    for (means, kills) in &data.kills_by_means {
+        transaction
+            .prepare_cached("INSERT INTO weapon_stats VALUES (?1, ?2, ?3)")?
+            .execute(params![match_id, means, kills])?;
+    }
+
+    for (seq, score) in data.scores.iter().enumerate() {
+        let player_id = players.get(transaction, &score.name)?;
+        transaction
+            .prepare_cached("INSERT INTO scores VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)")?
+            .execute(params![
+                match_id,
+                seq,
+                score.client_id,
+                player_id,
+                score.raw_name,
+                score.score,
+                score.ping
+            ])?;
+    }
+
+    for session in &data.sessions {
+        insert_session(transaction, players, match_id, session)?;
+    }
+
+    let mut seq = 0;
+    for line in chunk.lines() {
+        let parsed = LogLine::parse(line);
+        let LogEvent::Kill(kill) = parsed.event else {
+            continue;
+        };
+        insert_kill_event(transaction, players, match_id, seq, parsed.timestamp, &kill)?;
+        seq += 1;
+    }
+    Ok(())
+}
+
+fn insert_session(
+    transaction: &Transaction,
+    players: &mut PlayerIds,
+    match_id: i64,
+    session: &PlayerSession,
+) -> Result<(), LogError> {
+    transaction
+        .prepare_cached(
+            "INSERT INTO sessions (match_id, client_id, name, raw_name, connected_at, began_at,
+                 disconnected_at, joined_late, left_early, rage_quit, kills)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )?
+        .execute(params![
+            match_id,
+            session.client_id,
+            session.name,
+            session.raw_name,
+            session.connected_at,
+            session.began_at,
+            session.disconnected_at,
+            session.joined_late,
+            session.left_early,
+            session.rage_quit,
+            session.kills
+        ])?;
+    let session_id = transaction.last_insert_rowid();
+
+    for (seq, name_use) in session.names.iter().enumerate() {
+        let player_id = players.get(transaction, &name_use.name)?;
+        transaction
+            .prepare_cached("INSERT INTO session_names VALUES (?1, ?2, ?3, ?4)")?
+            .execute(params![session_id, seq, player_id, name_use.since])?;
+    }
+    for (key, value) in &session.userinfo {
+        transaction
+            .prepare_cached("INSERT OR REPLACE INTO session_userinfo VALUES (?1, ?2, ?3)")?
+            .execute(params![session_id, key, value])?;
+    }
+    Ok(())
+}
+
+fn insert_kill_event(
+    transaction: &Transaction,
+    players: &mut PlayerIds,
+    match_id: i64,
+    seq: usize,
+    at: Option<u32>,
+    kill: &KillEvent,
+) -> Result<(), LogError> {
+    let mut player_id = |name: Option<&str>| match name {
+        Some(name) if name != "<world>" => players.get(transaction, &strip_colors(name)).map(Some),
+        _ => Ok(None),
+    };
+    let killer_id = player_id(kill.killer)?;
+    let victim_id = player_id(kill.victim)?;
+
+    transaction
+        .prepare_cached("INSERT INTO kill_events VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")?
+        .execute(params![
+            match_id,
+            seq,
+            at,
+            kill.killer_id,
+            kill.victim_id,
+            killer_id,
+            victim_id,
+            kill.means.unwrap_or("")
+        ])?;
+    Ok(())
+}
+
+/// Loads the settings, players, stats, scores and sessions of a stored match.
+fn load_match_details(
+    connection: &Connection,
+    match_id: i64,
+    data: &mut MatchData,
+) -> Result<(), LogError> {
+    let mut statement =
+        connection.prepare_cached("SELECT key, value FROM server_config WHERE match_id = ?1")?;
+    for row in statement.query_map([match_id], |row| Ok((row.get(0)?, row.get(1)?)))? {
+        let (key, value) = row?;
+        data.settings.insert(key, value);
+    }
+
+    let mut statement = connection.prepare_cached(
+        "SELECT players.name, listed, kills, self_kills FROM match_players
+         JOIN players ON players.id = player_id WHERE match_id = ?1",
+    )?;
+    let rows = statement.query_map([match_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, bool>(1)?,
+            row.get::<_, Option<i32>>(2)?,
+            row.get::<_, i32>(3)?,
+        ))
+    })?;
+    for row in rows {
+        let (name, listed, kills, self_kills) = row?;
+        if let Some(kills) = kills {
+            data.kills.insert(name.clone(), kills);
+        }
+        if self_kills != 0 {
+            data.self_kills.insert(name.clone(), self_kills);
+        }
+        if listed {
+            data.players.insert(name);
+        }
+    }
+
+    let mut statement =
+        connection.prepare_cached("SELECT means, kills FROM weapon_stats WHERE match_id = ?1")?;
+    for row in statement.query_map([match_id], |row| Ok((row.get(0)?, row.get(1)?)))? {
+        let (means, kills) = row?;
+        data.kills_by_means.insert(means, kills);
+    }
+
+    let mut statement = connection.prepare_cached(
+        "SELECT client_id, players.name, raw_name, score, ping FROM scores
+         JOIN players ON players.id = player_id WHERE match_id = ?1 ORDER BY seq",
+    )?;
+    let rows = statement.query_map([match_id], |row| {
+        Ok(ServerScore {
+            client_id: row.get(0)?,
+            name: row.get(1)?,
+            raw_name: row.get(2)?,
+            score: row.get(3)?,
+            ping: row.get(4)?,
+        })
+    })?;
+    for row in rows {
+        data.scores.push(row?);
+    }
+
+    let mut statement = connection.prepare_cached(
+        "SELECT id, client_id, name, raw_name, connected_at, began_at, disconnected_at, joined_late,
+             left_early, rage_quit, kills
+         FROM sessions WHERE match_id = ?1 ORDER BY id",
+    )?;
+    let rows = statement.query_map([match_id], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            PlayerSession {
+                client_id: row.get(1)?,
+                name: row.get(2)?,
+                raw_name: row.get(3)?,
+                connected_at: row.get(4)?,
+                began_at: row.get(5)?,
+                disconnected_at: row.get(6)?,
+                joined_late: row.get(7)?,
+                left_early: row.get(8)?,
+                rage_quit: row.get(9)?,
+                kills: row.get(10)?,
+                ..Default::default()
+            },
+        ))
+    })?;
+    for row in rows {
+        let (session_id, mut session) = row?;
+        load_session_details(connection, session_id, &mut session)?;
+        data.sessions.push(session);
+    }
+    Ok(())
+}
+
+fn load_session_details(
+    connection: &Connection,
+    session_id: i64,
+    session: &mut PlayerSession,
+) -> Result<(), LogError> {
+    let mut statement = connection.prepare_cached(
+        "SELECT players.name, since FROM session_names
+         JOIN players ON players.id = player_id WHERE session_id = ?1 ORDER BY seq",
+    )?;
+    for row in statement.query_map([session_id], |row| {
+        Ok(NameUse {
+            name: row.get(0)?,
+            since: row.get(1)?,
+        })
+    })? {
+        session.names.push(row?);
+    }
+
+    let mut statement = connection
+        .prepare_cached("SELECT key, value FROM session_userinfo WHERE session_id = ?1")?;
+    for row in statement.query_map([session_id], |row| Ok((row.get(0)?, row.get(1)?)))? {
+        let (key, value) = row?;
+        session.userinfo.insert(key, value);
+    }
+    Ok(())
+}
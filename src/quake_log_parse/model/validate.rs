@@ -0,0 +1,429 @@
+use std::{collections::BTreeSet, io::BufRead};
+
+use serde::Serialize;
+
+use super::{
+    error::LogError,
+    event::{KillEvent, LogEvent, LogLine},
+    log_model::means_of_death_id,
+};
+
+/// The client id of the world in `Kill:` lines, e.g. for falls and lava.
+const WORLD_ID: i32 = 1022;
+
+/// How serious a finding is.
+///
+/// Errors are lines the parser rejects or cannot count, so reports of the log fail or miss data;
+/// warnings are lines it counts all the same, but that suggest a damaged or unusual log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// The structural problems looked for by `validate_log`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    /// A `Kill:` line before the first `InitGame:` or after a `ShutdownGame:`.
+    KillOutsideMatch,
+    /// A `ClientUserinfoChanged:` line before the first `InitGame:` or after a `ShutdownGame:`.
+    UserinfoOutsideMatch,
+    /// A kill, item or disconnect of a client id that did not connect to the match.
+    UnknownClient,
+    /// A kill by a means of death that Quake 3 does not have.
+    UnknownMeans,
+    /// A kill whose means of death id is not the one of its name, e.g. `10` with `MOD_ROCKET`.
+    MeansMismatch,
+    /// A `Kill:` or `ClientUserinfoChanged:` line missing a client id, a name or the means of death.
+    MalformedLine,
+    /// A line logged before the previous one within the same match.
+    TimestampBackwards,
+    /// A match without a `ShutdownGame:`, cut short by the next match or the end of the log.
+    UnterminatedMatch,
+}
+
+impl Check {
+    /// Returns the name of the check, as written in the JSON findings, e.g. `unknown_means`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Check::KillOutsideMatch => "kill_outside_match",
+            Check::UserinfoOutsideMatch => "userinfo_outside_match",
+            Check::UnknownClient => "unknown_client",
+            Check::UnknownMeans => "unknown_means",
+            Check::MeansMismatch => "means_mismatch",
+            Check::MalformedLine => "malformed_line",
+            Check::TimestampBackwards => "timestamp_backwards",
+            Check::UnterminatedMatch => "unterminated_match",
+        }
+    }
+
+    /// Returns how serious a finding of this check is.
+    pub fn severity(self) -> Severity {
+        match self {
+            Check::KillOutsideMatch | Check::UnknownMeans | Check::MalformedLine => Severity::Error,
+            Check::UserinfoOutsideMatch
+            | Check::UnknownClient
+            | Check::MeansMismatch
+            | Check::TimestampBackwards
+            | Check::UnterminatedMatch => Severity::Warning,
+        }
+    }
+}
+
+/// A problem found on a line of a log.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    pub file: String,
+    /// The number of the line in the file, from 1.
+    pub line: usize,
+    /// The number of the match in the file, from 1, if the line belongs to one.
+    #[serde(rename = "match", skip_serializing_if = "Option::is_none")]
+    pub match_id: Option<i32>,
+    pub severity: Severity,
+    pub check: Check,
+    pub message: String,
+}
+
+/// The findings of one or more logs, with how many are errors and warnings.
+#[derive(Debug, Default, Serialize)]
+pub struct Validation {
+    pub errors: usize,
+    pub warnings: usize,
+    /// The findings of every log, by file and then by line.
+    pub findings: Vec<Finding>,
+}
+
+impl Validation {
+    /// Adds the findings of a log.
+    pub fn extend(&mut self, findings: Vec<Finding>) {
+        for finding in findings {
+            match finding.severity {
+                Severity::Error => self.errors += 1,
+                Severity::Warning => self.warnings += 1,
+            }
+            self.findings.push(finding);
+        }
+    }
+}
+
+/// The match being checked.
+struct OpenMatch {
+    id: i32,
+    /// The line of its `InitGame:`.
+    line: usize,
+    /// The client ids that connected, changed their user info or began, and did not disconnect.
+    clients: BTreeSet<i32>,
+    /// Whether it logged a `ShutdownGame:`.
+    shut_down: bool,
+}
+
+/// Checks the lines of a log one by one, keeping only the match being played, so a log of any size
+/// is checked in constant memory.
+///
+/// The checks do not depend on the parser, so a log is checked to the end even where the parser
+/// would stop with an error.
+pub struct LogValidator {
+    file: String,
+    findings: Vec<Finding>,
+    current: Option<OpenMatch>,
+    /// The number of matches started.
+    matches: i32,
+    /// The timestamp and number of the last line of the current match with one.
+    last_timestamp: Option<(u32, usize)>,
+}
+
+impl LogValidator {
+    /// Creates a validator for a log, named `file` in its findings.
+    pub fn new(file: &str) -> Self {
+        LogValidator {
+            file: file.to_string(),
+            findings: Vec::new(),
+            current: None,
+            matches: 0,
+            last_timestamp: None,
+        }
+    }
+
+    /// Checks a line of the log.
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The number of the line in the file, from 1.
+    /// * `line` - A reference to the parsed `LogLine`.
+    pub fn check_line(&mut self, number: usize, line: &LogLine) {
+        // Separators and other lines of no interest are not checked, not even their timestamps:
+        // the server writes one before the `InitGame:` of a restart, with the clock back at 0:00.
+        if matches!(line.event, LogEvent::Other) {
+            return;
+        }
+        if let LogEvent::InitGame { .. } = line.event {
+            self.start_match(number);
+        } else {
+            self.check_timestamp(number, line.timestamp);
+        }
+
+        match line.event {
+            LogEvent::ShutdownGame => {
+                if let Some(current) = &mut self.current {
+                    current.shut_down = true;
+                }
+            }
+            LogEvent::ClientConnect { client_id } | LogEvent::ClientBegin { client_id } => {
+                if let (Some(clients), Some(client_id)) = (self.clients(), client_id) {
+                    clients.insert(client_id);
+                }
+            }
+            LogEvent::ClientUserinfoChanged { client_id, name } => {
+                let Some(clients) = self.clients() else {
+                    self.report(
+                        number,
+                        Check::UserinfoOutsideMatch,
+                        format!("User info of client {} {}", id(client_id), self.outside()),
+                    );
+                    return;
+                };
+                match (client_id, name) {
+                    (Some(client_id), Some(_)) => {
+                        clients.insert(client_id);
+                    }
+                    _ => self.report(
+                        number,
+                        Check::MalformedLine,
+                        format!(
+                            "User info without a client id or name: {}",
+                            line.text.trim()
+                        ),
+                    ),
+                }
+            }
+            LogEvent::ClientDisconnect { client_id } => {
+                if let (Some(clients), Some(client_id)) = (self.clients(), client_id) {
+                    if !clients.remove(&client_id) {
+                        self.report_unknown_client(number, client_id, "Disconnect");
+                    }
+                }
+            }
+            LogEvent::Item { client_id, .. } => {
+                if let (Some(clients), Some(client_id)) = (self.clients(), client_id) {
+                    if !clients.contains(&client_id) {
+                        self.report_unknown_client(number, client_id, "Item picked up by");
+                    }
+                }
+            }
+            LogEvent::Kill(kill) => self.check_kill(number, line.text, &kill),
+            LogEvent::InitGame { .. }
+            | LogEvent::Score
+            | LogEvent::Say { .. }
+            | LogEvent::Other => {}
+        }
+    }
+
+    /// Finishes the log, returning its findings by line.
+    pub fn finish(mut self) -> Vec<Finding> {
+        if let Some(current) = self.current.take() {
+            if !current.shut_down {
+                self.report_unterminated(&current, "at the end of the log".to_string());
+            }
+        }
+        self.findings.sort_by_key(|finding| finding.line);
+        self.findings
+    }
+
+    fn start_match(&mut self, number: usize) {
+        if let Some(previous) = self.current.take() {
+            if !previous.shut_down {
+                self.report_unterminated(
+                    &previous,
+                    format!("when the next match starts on line {}", number),
+                );
+            }
+        }
+        self.matches += 1;
+        self.current = Some(OpenMatch {
+            id: self.matches,
+            line: number,
+            clients: BTreeSet::new(),
+            shut_down: false,
+        });
+        self.last_timestamp = None;
+    }
+
+    fn check_timestamp(&mut self, number: usize, timestamp: Option<u32>) {
+        let Some(timestamp) = timestamp else {
+            return;
+        };
+        if let Some((last, last_line)) = self.last_timestamp {
+            if timestamp < last {
+                self.report(
+                    number,
+                    Check::TimestampBackwards,
+                    format!(
+                        "Time {} is before {} on line {}",
+                        clock(timestamp),
+                        clock(last),
+                        last_line
+                    ),
+                );
+            }
+        }
+        self.last_timestamp = Some((timestamp, number));
+    }
+
+    fn check_kill(&mut self, number: usize, text: &str, kill: &KillEvent) {
+        let (Some(killer_id), Some(victim_id), Some(_), Some(_), Some(means)) = (
+            kill.killer_id,
+            kill.victim_id,
+            kill.killer,
+            kill.victim,
+            kill.means,
+        ) else {
+            self.report(
+                number,
+                Check::MalformedLine,
+                format!("Kill without client ids, names or means: {}", text.trim()),
+            );
+            return;
+        };
+
+        match self.clients() {
+            None => self.report(
+                number,
+                Check::KillOutsideMatch,
+                format!("Kill {}: {}", self.outside(), text.trim()),
+            ),
+            Some(clients) => {
+                let known = |client_id: i32| clients.contains(&client_id);
+                let (killer_known, victim_known) =
+                    (killer_id == WORLD_ID || known(killer_id), known(victim_id));
+                if !killer_known {
+                    self.report_unknown_client(number, killer_id, "Kill by");
+                }
+                if !victim_known {
+                    self.report_unknown_client(number, victim_id, "Kill of");
+                }
+            }
+        }
+
+        match (means_of_death_id(means), means_id(text)) {
+            (None, _) => self.report(
+                number,
+                Check::UnknownMeans,
+                format!("Unknown means of death {}", means),
+            ),
+            (Some(expected), Some(logged)) if expected != logged => self.report(
+                number,
+                Check::MeansMismatch,
+                format!("{} has id {}, the line says {}", means, expected, logged),
+            ),
+            _ => {}
+        }
+    }
+
+    /// Describes where a line outside a match is, and how the parser counts it.
+    fn outside(&self) -> String {
+        match &self.current {
+            Some(current) => format!(
+                "after the ShutdownGame of match {}, counted in that match",
+                current.id
+            ),
+            None => "before the first InitGame, not counted in any match".to_string(),
+        }
+    }
+
+    /// Returns the clients of the match being played, or `None` outside a match.
+    fn clients(&mut self) -> Option<&mut BTreeSet<i32>> {
+        self.current
+            .as_mut()
+            .filter(|current| !current.shut_down)
+            .map(|current| &mut current.clients)
+    }
+
+    fn report_unknown_client(&mut self, number: usize, client_id: i32, what: &str) {
+        self.report(
+            number,
+            Check::UnknownClient,
+            format!("{} client {}, which is not connected", what, client_id),
+        );
+    }
+
+    fn report_unterminated(&mut self, game: &OpenMatch, when: String) {
+        self.findings.push(Finding {
+            file: self.file.clone(),
+            line: game.line,
+            match_id: Some(game.id),
+            severity: Check::UnterminatedMatch.severity(),
+            check: Check::UnterminatedMatch,
+            message: format!("Match {} has no ShutdownGame {}", game.id, when),
+        });
+    }
+
+    fn report(&mut self, number: usize, check: Check, message: String) {
+        let match_id = self.current.as_ref().map(|current| current.id);
+        self.findings.push(Finding {
+            file: self.file.clone(),
+            line: number,
+            match_id,
+            severity: check.severity(),
+            check,
+            message,
+        });
+    }
+}
+
+/// Checks every line of a log, reading it line by line.
+///
+/// # Arguments
+///
+/// * `reader` - The log to read, e.g. from `input::open_log_file`.
+/// * `file` - The name of the log file, as written in the findings and the errors.
+///
+/// # Returns
+///
+/// * `Result<Vec<Finding>, LogError>` - A `Result` with the findings, by line, or an error (`Err`)
+///   if the log cannot be read.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if the log cannot be read. Lines that cannot be parsed are
+/// findings, not errors.
+pub fn validate_log(mut reader: impl BufRead, file: &str) -> Result<Vec<Finding>, LogError> {
+    let mut validator = LogValidator::new(file);
+    let mut buffer = Vec::new();
+    let mut number = 0;
+
+    loop {
+        buffer.clear();
+        let read = reader.read_until(b'\n', &mut buffer).map_err(|err| {
+            LogError::ReadLogError(format!("Error reading the log file {}: {}", file, err))
+        })?;
+        if read == 0 {
+            return Ok(validator.finish());
+        }
+        number += 1;
+
+        let line = String::from_utf8_lossy(&buffer);
+        validator.check_line(number, &LogLine::parse(line.trim_end_matches(['\n', '\r'])));
+    }
+}
+
+/// Extracts the means of death id of a `Kill:` line, the number after the client ids.
+fn means_id(line: &str) -> Option<i32> {
+    let (_, rest) = line.split_once("Kill:")?;
+    rest.split_whitespace()
+        .nth(2)?
+        .trim_end_matches(':')
+        .parse()
+        .ok()
+}
+
+/// Formats a client id that may be missing.
+fn id(client_id: Option<i32>) -> String {
+    client_id.map_or_else(|| "?".to_string(), |client_id| client_id.to_string())
+}
+
+/// Formats a timestamp as the log does, e.g. `3:07`.
+fn clock(seconds: u32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,102 @@
+use serde_json::{json, Value};
+
+use crate::quake_log_parse::{
+    model::{
+        log_model::{Match, PlayerScore},
+        stats::{PlayerStats, WeaponStats},
+    },
+    view::log_view::{match_json, ranking_json},
+};
+
+/// A slice of a longer list, as returned by the paginated endpoints of the HTTP API.
+pub struct Page<'a, T> {
+    pub items: &'a [T],
+    /// The number of items in the whole list.
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// Builds the JSON bodies of the HTTP API, with the same data models as the JSON report.
+pub struct ApiView {}
+impl ApiView {
+    /// Builds the body of `/matches`: a page of matches, each with its `id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - The `Page` of matches to render.
+    pub fn matches(page: Page<&Match>) -> Value {
+        let matches: Vec<Value> = page.items.iter().map(|game| Self::game(game)).collect();
+        paginated(&page, "matches", matches)
+    }
+
+    /// Builds the body of `/matches/{id}`: the data of a match, as in the `matches` of the report,
+    /// with its `id` and server settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - A reference to the `Match` to render.
+    pub fn game(game: &Match) -> Value {
+        let mut data = match_json(game);
+        data["id"] = json!(game.id);
+        data["settings"] = json!(game.data.settings);
+        data
+    }
+
+    /// Builds the body of `/players`: a page of player statistics, in ranking order.
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - The `Page` of `PlayerStats` to render.
+    pub fn players(page: Page<PlayerStats>) -> Value {
+        let players: Vec<Value> = page.items.iter().map(|player| json!(player)).collect();
+        paginated(&page, "players", players)
+    }
+
+    /// Builds the body of `/players/{name}`.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - A reference to the `PlayerStats` to render.
+    pub fn player(player: &PlayerStats) -> Value {
+        json!(player)
+    }
+
+    /// Builds the body of `/ranking`: a page of the `Player Ranking` of the report.
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - The `Page` of `PlayerScore` to render.
+    pub fn ranking(page: Page<PlayerScore>) -> Value {
+        let ranking = ranking_json(page.items);
+        paginated(&page, "Player Ranking", ranking)
+    }
+
+    /// Builds the body of `/weapons`: the kills by means of death, most used first.
+    ///
+    /// # Arguments
+    ///
+    /// * `weapons` - A slice of `WeaponStats` to render.
+    pub fn weapons(weapons: &[WeaponStats]) -> Value {
+        json!({ "weapons": weapons })
+    }
+
+    /// Builds the body of an error response.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - A description of the error.
+    pub fn error(message: &str) -> Value {
+        json!({ "error": message })
+    }
+}
+
+/// Wraps the items of a page with the pagination fields.
+fn paginated<T>(page: &Page<T>, key: &str, items: Vec<Value>) -> Value {
+    json!({
+        "total": page.total,
+        "offset": page.offset,
+        "limit": page.limit,
+        key: items,
+    })
+}
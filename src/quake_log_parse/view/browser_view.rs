@@ -0,0 +1,223 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+use crate::quake_log_parse::model::browser::{Browser, ScoreSort, Screen};
+
+/// Draws the terminal UI browsing the matches of a log.
+pub struct BrowserView {}
+impl BrowserView {
+    /// Draws the current screen of the browser, with the keys it answers to on the last line.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The `Frame` of the terminal to draw on.
+    ///
+    /// * `browser` - A reference to the `Browser` to draw.
+    pub fn draw(frame: &mut Frame, browser: &Browser) {
+        let [body, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+
+        let keys = match browser.screen {
+            Screen::Matches => "↑/↓ move  enter open  q quit",
+            Screen::Scoreboard => {
+                "↑/↓ move  enter player  s sort  ←/→ scrub  home/end jump  esc back  q quit"
+            }
+            Screen::Player => "←/→ scrub  home/end jump  esc back  q quit",
+        };
+        frame.render_widget(Paragraph::new(keys), help);
+
+        match browser.screen {
+            Screen::Matches => Self::draw_matches(frame, body, browser),
+            Screen::Scoreboard => {
+                let [scoreboard, timeline] =
+                    Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                        .areas(body);
+                Self::draw_scoreboard(frame, scoreboard, browser);
+                Self::draw_timeline(frame, timeline, browser);
+            }
+            Screen::Player => Self::draw_player(frame, body, browser),
+        }
+    }
+
+    fn draw_matches(frame: &mut Frame, area: Rect, browser: &Browser) {
+        let rows = browser.matches().iter().map(|mat| {
+            let data = &mat.data;
+            let setting = |name: &str| data.settings.get(name).cloned().unwrap_or_default();
+            Row::new([
+                mat.id.to_string(),
+                setting("mapname"),
+                setting("g_gametype"),
+                duration(data.ended_at.saturating_sub(data.started_at)),
+                data.players.len().to_string(),
+                data.total_kills.to_string(),
+                if data.completed { "yes" } else { "no" }.to_string(),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(5),
+                Constraint::Min(10),
+                Constraint::Length(9),
+                Constraint::Length(9),
+                Constraint::Length(8),
+                Constraint::Length(6),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header([
+            "#",
+            "Map",
+            "Gametype",
+            "Duration",
+            "Players",
+            "Kills",
+            "Completed",
+        ]))
+        .row_highlight_style(selected_style())
+        .block(Block::bordered().title(format!(" Matches ({}) ", browser.matches().len())));
+
+        let mut state = TableState::default().with_selected(Some(browser.selected));
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+
+    fn draw_scoreboard(frame: &mut Frame, area: Rect, browser: &Browser) {
+        let title = match browser.current_match() {
+            Some(mat) => format!(
+                " Match {} on {} — sorted by {} ",
+                mat.id,
+                mat.data.settings.get("mapname").map_or("?", String::as_str),
+                sort_name(browser.sort)
+            ),
+            None => " Scoreboard ".to_string(),
+        };
+        let rows = browser.scoreboard().into_iter().map(|row| {
+            Row::new([
+                row.name,
+                row.kills.to_string(),
+                row.deaths.to_string(),
+                duration(row.playtime),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(12),
+                Constraint::Length(6),
+                Constraint::Length(7),
+                Constraint::Length(9),
+            ],
+        )
+        .header(header(["Player", "Kills", "Deaths", "Playtime"]))
+        .row_highlight_style(selected_style())
+        .block(Block::bordered().title(title));
+
+        let mut state = TableState::default().with_selected(Some(browser.player));
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+
+    fn draw_timeline(frame: &mut Frame, area: Rect, browser: &Browser) {
+        let timeline = browser.timeline();
+        let lines: Vec<Line> = timeline
+            .iter()
+            .enumerate()
+            .map(|(idx, kill)| {
+                let line = Line::from(format!(
+                    "{:>6}  {} killed {} by {}",
+                    duration(kill.at),
+                    kill.killer,
+                    kill.victim,
+                    kill.means
+                ));
+                if idx < browser.position {
+                    line
+                } else {
+                    line.style(Style::default().add_modifier(Modifier::DIM))
+                }
+            })
+            .collect();
+
+        // Keeps the last kill played in view.
+        let height = area.height.saturating_sub(2) as usize;
+        let scroll = browser.position.saturating_sub(height);
+        let title = format!(" Timeline {}/{} ", browser.position, timeline.len());
+        let paragraph = Paragraph::new(lines)
+            .scroll((scroll as u16, 0))
+            .block(Block::bordered().title(title));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn draw_player(frame: &mut Frame, area: Rect, browser: &Browser) {
+        let name = browser.current_player().unwrap_or_default();
+        let [weapons, kills] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(area);
+
+        let rows = browser
+            .player_weapons()
+            .into_iter()
+            .map(|(means, kills, deaths)| Row::new([means, kills.to_string(), deaths.to_string()]));
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(12),
+                Constraint::Length(6),
+                Constraint::Length(7),
+            ],
+        )
+        .header(header(["Means", "Kills", "Deaths"]))
+        .block(Block::bordered().title(format!(" {} — weapons ", name)));
+        frame.render_widget(table, weapons);
+
+        let lines: Vec<Line> = browser
+            .player_kills()
+            .into_iter()
+            .map(|kill| {
+                Line::from(format!(
+                    "{:>6}  {} killed {} by {}",
+                    duration(kill.at),
+                    kill.killer,
+                    kill.victim,
+                    kill.means
+                ))
+            })
+            .collect();
+        let title = format!(
+            " {} — kills and deaths ({}/{}) ",
+            name,
+            browser.position,
+            browser.timeline().len()
+        );
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(title)),
+            kills,
+        );
+    }
+}
+
+fn header<const N: usize>(columns: [&str; N]) -> Row<'_> {
+    Row::new(columns).style(Style::default().add_modifier(Modifier::BOLD))
+}
+
+fn selected_style() -> Style {
+    Style::default().add_modifier(Modifier::REVERSED)
+}
+
+fn sort_name(sort: ScoreSort) -> &'static str {
+    match sort {
+        ScoreSort::Kills => "kills",
+        ScoreSort::Deaths => "deaths",
+        ScoreSort::Playtime => "playtime",
+        ScoreSort::Name => "name",
+    }
+}
+
+/// Formats seconds as `m:ss`.
+fn duration(seconds: u32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,163 @@
+use crate::quake_log_parse::{
+    model::{
+        diff::{CountChange, MatchSummary, RankingChange, ReportDiff},
+        error::LogError,
+    },
+    view::terminal_view::{Paint, TerminalStyle},
+};
+
+/// Renders the differences between two reports.
+pub struct DiffView {}
+impl DiffView {
+    /// Renders the differences between two reports as text: the matches added (`+`), removed (`-`)
+    /// and changed (`~`), then the players whose place in the ranking changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `diff` - A reference to the `ReportDiff` to render.
+    /// * `style` - A reference to the `TerminalStyle` to draw with.
+    pub fn render_text(diff: &ReportDiff, style: &TerminalStyle) {
+        print!("{}", Self::text(diff, style));
+    }
+
+    /// Renders the differences between two reports in JSON format.
+    ///
+    /// # Arguments
+    ///
+    /// * `diff` - A reference to the `ReportDiff` to render.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the differences cannot be serialized.
+    pub fn render_json(diff: &ReportDiff) -> Result<(), LogError> {
+        println!("{}", serde_json::to_string_pretty(diff)?);
+        Ok(())
+    }
+
+    /// Builds the text of `render_text`.
+    pub fn text(diff: &ReportDiff, style: &TerminalStyle) -> String {
+        if diff.is_empty() {
+            return "No differences.\n".to_string();
+        }
+
+        let mut text = format!(
+            "{}\n",
+            style.paint(
+                &format!(
+                    "Matches: {} added, {} removed, {} changed",
+                    diff.added_matches.len(),
+                    diff.removed_matches.len(),
+                    diff.changed_matches.len()
+                ),
+                Paint::Bold
+            )
+        );
+        for game in &diff.added_matches {
+            text.push_str(&style.paint(&format!("+ {}", summary(game)), Paint::Green));
+            text.push('\n');
+        }
+        for game in &diff.removed_matches {
+            text.push_str(&style.paint(&format!("- {}", summary(game)), Paint::Red));
+            text.push('\n');
+        }
+        for game in &diff.changed_matches {
+            text.push_str(&style.paint(
+                &format!("~ Match {} · {}", game.id, game.map),
+                Paint::Yellow,
+            ));
+            text.push('\n');
+            if let Some(total) = &game.total_kills {
+                text.push_str(&format!(
+                    "    total kills  {} → {}\n",
+                    total.before, total.after
+                ));
+            }
+            for player in &game.players_added {
+                text.push_str(&style.paint(&format!("    + player {}", player), Paint::Green));
+                text.push('\n');
+            }
+            for player in &game.players_removed {
+                text.push_str(&style.paint(&format!("    - player {}", player), Paint::Red));
+                text.push('\n');
+            }
+            text.push_str(&counts("kills", &game.kills));
+            text.push_str(&counts("means", &game.death_causes));
+        }
+
+        if !diff.ranking.is_empty() {
+            text.push_str(&style.paint("Ranking:", Paint::Bold));
+            text.push('\n');
+            let width = diff
+                .ranking
+                .iter()
+                .map(|change| change.name.chars().count())
+                .max()
+                .unwrap_or(0);
+            for change in &diff.ranking {
+                text.push_str(&format!(
+                    "  {:width$}  {}\n",
+                    change.name,
+                    movement(change, style),
+                    width = width
+                ));
+            }
+        }
+        text
+    }
+}
+
+/// Describes a match only in one of the reports.
+fn summary(game: &MatchSummary) -> String {
+    format!(
+        "Match {} · {} · {} kills",
+        game.id, game.map, game.total_kills
+    )
+}
+
+/// Lists the counts of a match that changed, one per line.
+fn counts(label: &str, changes: &[CountChange]) -> String {
+    changes
+        .iter()
+        .map(|change| {
+            format!(
+                "    {}  {}  {} → {}\n",
+                label, change.name, change.before, change.after
+            )
+        })
+        .collect()
+}
+
+/// Describes how the place of a player changed, e.g. `#3 → #1 (▲2), kills 10 → 14`.
+fn movement(change: &RankingChange, style: &TerminalStyle) -> String {
+    match (change.before, change.after) {
+        (Some(before), Some(after)) => {
+            let arrow = match after.rank.cmp(&before.rank) {
+                std::cmp::Ordering::Less => {
+                    style.paint(&format!(" (▲{})", before.rank - after.rank), Paint::Green)
+                }
+                std::cmp::Ordering::Greater => {
+                    style.paint(&format!(" (▼{})", after.rank - before.rank), Paint::Red)
+                }
+                std::cmp::Ordering::Equal => String::new(),
+            };
+            format!(
+                "#{} → #{}{}, kills {} → {}",
+                before.rank, after.rank, arrow, before.kills, after.kills
+            )
+        }
+        (None, Some(after)) => style.paint(
+            &format!("new at #{}, {} kills", after.rank, after.kills),
+            Paint::Green,
+        ),
+        (Some(before), None) => style.paint(
+            &format!("dropped from #{}, {} kills", before.rank, before.kills),
+            Paint::Red,
+        ),
+        (None, None) => String::new(),
+    }
+}
@@ -1,15 +1,27 @@
-use serde_json::json;
+use std::net::SocketAddr;
 
-use crate::quake_log_parse::model::{
-    error::LogError,
-    log_model::{Match, PlayerScore},
+use serde_json::{json, Value};
+
+use crate::quake_log_parse::{
+    model::{
+        checkpoint::{Checkpoint, Resume},
+        error::LogError,
+        log_model::{final_scoreboard, reconcile_scores, Match, MatchData, PlayerScore},
+        player_name::PlayerName,
+        report::{Report, REPORT_SCHEMA},
+        session::{playtime_by_player, PlayerPlaytime},
+        sources::LogsReport,
+        storage::ImportSummary,
+    },
+    view::terminal_view::{bar_chart, truncate, Align, Cell, Paint, Table, TerminalStyle},
 };
 pub struct LogView {}
 impl LogView {
     /// Renders game matches and player rankings to the output.
     ///
     /// This function takes a vector of `Match` structs representing game matches and a vector of `PlayerScore`
-    /// structs representing player rankings. It then prints these data to the console
+    /// structs representing player rankings. It then prints these data to the console, in the legacy
+    /// JSON shape: one document per section, without a `schema_version`.
     ///
     /// # Arguments
     ///
@@ -29,8 +41,281 @@ impl LogView {
         games: Vec<Match>,
         player_ranking: Vec<PlayerScore>,
     ) -> Result<(), LogError> {
+        let playtime = playtime_by_player(&games);
         render_matches(games)?;
         render_ranking(player_ranking)?;
+        render_playtime(playtime)?;
+        Ok(())
+    }
+
+    /// Renders the report of several log files to the output.
+    ///
+    /// Each file is printed with its matches and its own player ranking, followed by the player ranking
+    /// and playtime combined across all the files, in the legacy JSON shape of `render_report()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - A `LogsReport` struct containing the reports of each file and the combined data.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if there are any issues with rendering the report.
+    pub fn render_logs_report(report: LogsReport) -> Result<(), LogError> {
+        for file in report.files {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&json!({
+                    "file": file.file,
+                    "matches": matches_json(&file.matches),
+                    "Player Ranking": ranking_json(&file.ranking),
+                }))?
+            );
+        }
+        render_ranking(report.ranking)?;
+        render_playtime(report.playtime)?;
+        Ok(())
+    }
+
+    /// Renders a report to the output as a single JSON document, described by `REPORT_SCHEMA`.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - A reference to the `Report` to render.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if there are any issues with rendering the report.
+    pub fn render_json_report(report: &Report) -> Result<(), LogError> {
+        println!("{}", serde_json::to_string_pretty(report)?);
+        Ok(())
+    }
+
+    /// Renders the JSON Schema of the report to the output.
+    pub fn render_report_schema() {
+        print!("{}", REPORT_SCHEMA);
+    }
+
+    /// Renders game matches and player rankings to the output as terminal tables.
+    ///
+    /// Each match is drawn as its scoreboard followed by a bar chart of its means of death, then the
+    /// player ranking with the time each player spent in the matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `games` - A slice of `Match` structs containing information about game matches to be rendered.
+    ///
+    /// * `player_ranking` - A slice of `PlayerScore` structs containing player rankings to be rendered.
+    ///
+    /// * `style` - A reference to the `TerminalStyle` to draw with.
+    pub fn render_report_tables(
+        games: &[Match],
+        player_ranking: &[PlayerScore],
+        style: &TerminalStyle,
+    ) {
+        for game in games {
+            print!("{}", match_tables(game, style));
+        }
+        print!(
+            "{}",
+            ranking_table(
+                "Player Ranking",
+                player_ranking,
+                &playtime_by_player(games),
+                style
+            )
+        );
+    }
+
+    /// Renders the report of several log files to the output as terminal tables.
+    ///
+    /// Each file is drawn with its matches, as in `render_report_tables()`, and its own player
+    /// ranking, followed by the player ranking and playtime combined across all the files.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - A reference to the `LogsReport` containing the reports of each file and the
+    ///   combined data.
+    ///
+    /// * `style` - A reference to the `TerminalStyle` to draw with.
+    pub fn render_logs_report_tables(report: &LogsReport, style: &TerminalStyle) {
+        for file in &report.files {
+            println!(
+                "{}\n",
+                style.paint(&truncate(&file.file, style.width), Paint::Cyan)
+            );
+            for game in &file.matches {
+                print!("{}", match_tables(game, style));
+            }
+            let title = format!("Player Ranking of {}", file.file);
+            print!(
+                "{}",
+                ranking_table(
+                    &title,
+                    &file.ranking,
+                    &playtime_by_player(&file.matches),
+                    style
+                )
+            );
+            println!();
+        }
+        print!(
+            "{}",
+            ranking_table("Player Ranking", &report.ranking, &report.playtime, style)
+        );
+    }
+
+    /// Renders where an incremental run resumed from as a line of text, for the table output.
+    ///
+    /// # Arguments
+    ///
+    /// * `checkpoint` - A reference to the `Checkpoint` saved at the end of the run.
+    ///
+    /// * `resume` - Where the run resumed from, e.g. `Resume::Rotated` when the log was replaced.
+    ///
+    /// * `style` - A reference to the `TerminalStyle` to draw with.
+    pub fn render_checkpoint_line(checkpoint: &Checkpoint, resume: Resume, style: &TerminalStyle) {
+        let resumed = match resume {
+            Resume::Start => "from the start",
+            Resume::Checkpoint => "from the checkpoint",
+            Resume::Rotated => "from the start, as the log was rotated",
+            Resume::Truncated => "from the start, as the log was truncated",
+        };
+        println!(
+            "{}\n",
+            style.paint(
+                &format!(
+                    "{} processed {}, up to byte {} ({} matches so far)",
+                    checkpoint.file, resumed, checkpoint.offset, checkpoint.matches
+                ),
+                Paint::Dim
+            )
+        );
+    }
+
+    /// Renders where an incremental run resumed from and how far the log has been processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `checkpoint` - A reference to the `Checkpoint` saved at the end of the run.
+    ///
+    /// * `resume` - Where the run resumed from, e.g. `Resume::Rotated` when the log was replaced.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if there are any issues with rendering the checkpoint.
+    pub fn render_checkpoint(checkpoint: &Checkpoint, resume: Resume) -> Result<(), LogError> {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({
+                "Checkpoint": {
+                    "file": checkpoint.file,
+                    "resumed_from": resume,
+                    "offset": checkpoint.offset,
+                    "matches": checkpoint.matches,
+                }
+            }))?
+        );
+        Ok(())
+    }
+
+    /// Renders the address the HTTP API listens on.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address of the server, if it listens on an IP address.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if there are any issues with rendering the address.
+    pub fn render_server_address(address: Option<SocketAddr>) -> Result<(), LogError> {
+        let url = address.map(|address| format!("http://{}", address));
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({ "Listening": url }))?
+        );
+        Ok(())
+    }
+
+    /// Renders, on stderr so it does not mix with the report, how many matches were skipped while
+    /// merging reports because another report already had them.
+    ///
+    /// # Arguments
+    ///
+    /// * `skipped` - The number of matches skipped.
+    pub fn render_skipped_matches(skipped: usize) {
+        match skipped {
+            0 => {}
+            1 => eprintln!("Skipped 1 match already merged from another report"),
+            _ => eprintln!(
+                "Skipped {} matches already merged from another report",
+                skipped
+            ),
+        }
+    }
+
+    /// Renders, on stderr so it does not mix with the events, the lines of a log skipped during an
+    /// export because they could not be parsed, as `file:line: error`.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The name of the log file.
+    /// * `skipped` - The number of each line skipped with its error.
+    pub fn render_skipped_lines(file: &str, skipped: &[(usize, LogError)]) {
+        for (line, err) in skipped {
+            eprintln!("{}:{}: skipped, {:?}", file, line, err);
+        }
+    }
+
+    /// Renders the number of matches imported into the database from each log file.
+    ///
+    /// # Arguments
+    ///
+    /// * `summaries` - A vector of the log files with the `ImportSummary` of each one.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if there are any issues with rendering the summary.
+    pub fn render_import_summary(summaries: Vec<(String, ImportSummary)>) -> Result<(), LogError> {
+        let files: Vec<Value> = summaries
+            .iter()
+            .map(|(file, summary)| {
+                json!({
+                    "file": file,
+                    "imported": summary.imported,
+                    "skipped": summary.skipped,
+                    "updated": summary.updated,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({ "Imported": files }))?
+        );
         Ok(())
     }
 }
@@ -52,18 +337,51 @@ impl LogView {
 ///
 /// Returns an error of type `LogError` if there are any issues with rendering the player rankings.
 fn render_ranking(player_ranking: Vec<PlayerScore>) -> Result<(), LogError> {
-    let ranking: Vec<_> = player_ranking
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&json!({ "Player Ranking": ranking_json(&player_ranking) }))?
+    );
+    Ok(())
+}
+
+/// Builds the JSON representation of a player ranking, one `{name: kills}` object per player.
+pub(crate) fn ranking_json(player_ranking: &[PlayerScore]) -> Vec<Value> {
+    player_ranking
         .iter()
         .map(|player| json!({&player.name:player.kills}))
-        .collect();
+        .collect()
+}
 
+/// Renders the time each player spent in matches to the output in JSON format.
+///
+/// # Arguments
+///
+/// * `playtime` - A vector of `PlayerPlaytime` structs containing the playtime to be rendered.
+///
+/// # Returns
+///
+/// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+///   occurs during rendering.
+///
+/// # Errors
+///
+/// Returns an error of type `LogError` if there are any issues with rendering the playtime.
+fn render_playtime(playtime: Vec<PlayerPlaytime>) -> Result<(), LogError> {
     println!(
         "{}",
-        serde_json::to_string_pretty(&json!({ "Player Ranking": ranking }))?
+        serde_json::to_string_pretty(&json!({ "Player Playtime": playtime_json(&playtime) }))?
     );
     Ok(())
 }
 
+/// Builds the JSON representation of the playtime of players, one `{name: seconds}` object per player.
+pub(crate) fn playtime_json(playtime: &[PlayerPlaytime]) -> Vec<Value> {
+    playtime
+        .iter()
+        .map(|player| json!({&player.name: player.seconds}))
+        .collect()
+}
+
 /// Renders game match data to the output in JSON format.
 ///
 /// This function takes a vector of `Match` structs representing game matches and renders
@@ -82,24 +400,149 @@ fn render_ranking(player_ranking: Vec<PlayerScore>) -> Result<(), LogError> {
 ///
 /// Returns an error of type `LogError` if there are any issues with rendering the game match data.
 fn render_matches(games: Vec<Match>) -> Result<(), LogError> {
-    let mat: Vec<_> = games
-        .iter()
-        .map(|game| {
-            json!({
-                "game_".to_owned()
-                    + &game.id.to_string(): json!({
-                      "total_kills": game.data.total_kills,
-                        "players": game.data.players,
-                        "kills": game.data.kills,
-                        "death_causes": game.data.kills_by_means,
-                    })
-            })
-        })
-        .collect();
-
     println!(
         "{}",
-        serde_json::to_string_pretty(&json!({ "matches": mat }))?
+        serde_json::to_string_pretty(&json!({ "matches": matches_json(&games) }))?
     );
     Ok(())
 }
+
+/// Builds the JSON representation of game matches, one `{game_<id>: data}` object per match.
+pub(crate) fn matches_json(games: &[Match]) -> Vec<Value> {
+    games
+        .iter()
+        .map(|game| json!({ "game_".to_owned() + &game.id.to_string(): match_json(game) }))
+        .collect()
+}
+
+/// Builds the JSON representation of the data of a game match.
+///
+/// Matches read from one of several log files also carry the file and their id within it.
+pub(crate) fn match_json(game: &Match) -> Value {
+    let mut data = json!({
+        "total_kills": game.data.total_kills,
+        "players": game.data.players,
+        "kills": game.data.kills,
+        "death_causes": game.data.kills_by_means,
+        "scoreboard": ranking_json(&final_scoreboard(&game.data)),
+        "score_discrepancies": reconcile_scores(&game.data),
+        "sessions": game.data.sessions,
+    });
+    if !game.data.source_file.is_empty() {
+        data["source"] = json!({
+            "file": game.data.source_file,
+            "match": game.data.source_id,
+        });
+    }
+    data
+}
+
+/// Draws the scoreboard of a match and a bar chart of its means of death, followed by a blank line.
+fn match_tables(game: &Match, style: &TerminalStyle) -> String {
+    let data = &game.data;
+    let mut title = format!("Match {}", game.id);
+    if let Some(map) = data.settings.get("mapname") {
+        title.push_str(&format!(" · {}", map));
+    }
+    title.push_str(&format!(
+        " · {} · {} kills{}",
+        duration(data.ended_at.saturating_sub(data.started_at)),
+        data.total_kills,
+        if data.completed {
+            ""
+        } else {
+            " · not completed"
+        }
+    ));
+
+    let mut table = Table::new(&[("Player", Align::Left), ("Kills", Align::Right)]).title(title);
+    for score in final_scoreboard(data) {
+        let paint = match score.kills {
+            kills if kills > 0 => Paint::Green,
+            kills if kills < 0 => Paint::Red,
+            _ => Paint::Dim,
+        };
+        let name = match raw_name(data, &score.name) {
+            Some(raw) if raw != score.name => {
+                Cell::colored(&score.name, PlayerName::new(raw).to_ansi())
+            }
+            _ => Cell::from(score.name),
+        };
+        table.row(vec![name, Cell::painted(score.kills, paint)]);
+    }
+    let mut tables = table.render(style);
+
+    if !data.kills_by_means.is_empty() {
+        let mut means: Vec<(String, i64)> = data
+            .kills_by_means
+            .iter()
+            .map(|(means, kills)| (means.clone(), i64::from(*kills)))
+            .collect();
+        means.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        tables.push_str(&bar_chart("Means of death", &means, style));
+    }
+    tables.push('\n');
+    tables
+}
+
+/// Returns the name of a player of a match as last written in the log, with its colour codes.
+fn raw_name<'a>(data: &'a MatchData, name: &str) -> Option<&'a str> {
+    data.scores
+        .iter()
+        .find(|score| score.name == name)
+        .map(|score| score.raw_name.as_str())
+        .or_else(|| {
+            data.sessions
+                .iter()
+                .rev()
+                .find(|session| session.name == name)
+                .map(|session| session.raw_name.as_str())
+        })
+}
+
+/// Draws a player ranking with the time each player spent in the matches. The first three players
+/// are highlighted.
+fn ranking_table(
+    title: &str,
+    player_ranking: &[PlayerScore],
+    playtime: &[PlayerPlaytime],
+    style: &TerminalStyle,
+) -> String {
+    let mut table = Table::new(&[
+        ("#", Align::Right),
+        ("Player", Align::Left),
+        ("Kills", Align::Right),
+        ("Playtime", Align::Right),
+    ])
+    .title(title);
+    for (idx, player) in player_ranking.iter().enumerate() {
+        let seconds = playtime
+            .iter()
+            .find(|time| time.name == player.name)
+            .map_or("-".to_string(), |time| duration(time.seconds));
+        let rank = idx + 1;
+        table.row(vec![
+            match rank {
+                1..=3 => Cell::painted(rank, Paint::Yellow),
+                _ => Cell::from(rank.to_string()),
+            },
+            Cell::from(player.name.as_str()),
+            Cell::from(player.kills.to_string()),
+            Cell::from(seconds),
+        ]);
+    }
+    table.render(style)
+}
+
+/// Formats seconds as `m:ss`, or `h:mm:ss` from an hour.
+fn duration(seconds: u32) -> String {
+    match seconds {
+        0..=3599 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        _ => format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        ),
+    }
+}
@@ -0,0 +1,130 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::quake_log_parse::model::metrics::{Metrics, ServerLabels};
+
+/// The content type of the Prometheus text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Renders `Metrics` in the Prometheus text exposition format, as served on `/metrics` or written for
+/// the textfile collector of the node exporter.
+pub struct MetricsView {}
+impl MetricsView {
+    /// Renders every metric, with its help and type, sorted by labels.
+    ///
+    /// # Arguments
+    ///
+    /// * `metrics` - A reference to the `Metrics` to render.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The metrics, one sample per line.
+    pub fn render(metrics: &Metrics) -> String {
+        let mut out = String::new();
+        family(
+            &mut out,
+            "quake_log_lines_processed_total",
+            "counter",
+            "Lines of the log processed.",
+            server_samples(&metrics.lines_processed),
+        );
+        family(
+            &mut out,
+            "quake_matches_started_total",
+            "counter",
+            "Matches started (InitGame).",
+            server_samples(&metrics.matches_started),
+        );
+        family(
+            &mut out,
+            "quake_matches_finished_total",
+            "counter",
+            "Matches finished (ShutdownGame).",
+            server_samples(&metrics.matches_finished),
+        );
+        family(
+            &mut out,
+            "quake_matches_aborted_total",
+            "counter",
+            "Matches followed by the next one without a ShutdownGame.",
+            server_samples(&metrics.matches_aborted),
+        );
+        family(
+            &mut out,
+            "quake_player_connections_total",
+            "counter",
+            "Clients connected (ClientConnect).",
+            server_samples(&metrics.player_connections),
+        );
+        family(
+            &mut out,
+            "quake_players_connected",
+            "gauge",
+            "Clients connected to the match being played.",
+            server_samples(&metrics.players_connected),
+        );
+        family(
+            &mut out,
+            "quake_kills_total",
+            "counter",
+            "Kills by means of death.",
+            metrics
+                .kills
+                .iter()
+                .map(|((server, means), count)| (labels(server, Some(("means", means))), *count)),
+        );
+        family(
+            &mut out,
+            "quake_parse_errors_total",
+            "counter",
+            "Lines that could not be parsed, by kind of error.",
+            metrics
+                .parse_errors
+                .iter()
+                .map(|((server, kind), count)| (labels(server, Some(("kind", kind))), *count)),
+        );
+        out
+    }
+}
+
+fn family(
+    out: &mut String,
+    name: &str,
+    kind: &str,
+    help: &str,
+    samples: impl Iterator<Item = (String, u64)>,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+    }
+}
+
+fn server_samples(
+    counters: &BTreeMap<ServerLabels, u64>,
+) -> impl Iterator<Item = (String, u64)> + '_ {
+    counters
+        .iter()
+        .map(|(server, count)| (labels(server, None), *count))
+}
+
+/// Formats the labels of a sample, e.g. `hostname="Code Miner Server",map="q3dm17"`.
+fn labels(server: &ServerLabels, extra: Option<(&str, &str)>) -> String {
+    let mut labels = format!(
+        "hostname=\"{}\",map=\"{}\"",
+        escape(&server.hostname),
+        escape(&server.map)
+    );
+    if let Some((name, value)) = extra {
+        let _ = write!(labels, ",{}=\"{}\"", name, escape(value));
+    }
+    labels
+}
+
+/// Escapes a label value: backslashes, double quotes and line breaks.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
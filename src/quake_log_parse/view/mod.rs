@@ -1 +1,10 @@
-pub mod log_view;
\ No newline at end of file
+pub mod api_view;
+pub mod log_view;
+pub mod metrics_view;
+pub mod query_view;
+
+pub mod browser_view;
+pub mod diff_view;
+pub mod repl_view;
+pub mod validate_view;
+pub mod terminal_view;
\ No newline at end of file
@@ -0,0 +1,55 @@
+use crate::quake_log_parse::{
+    model::query::{QueryResult, QueryValue},
+    view::terminal_view::{Align, Cell, Table, TerminalStyle},
+};
+
+/// Renders the answers of queries as text tables.
+pub struct QueryView {}
+impl QueryView {
+    /// Renders the rows answering a query to the output, as a table with a header and aligned
+    /// columns, followed by the number of rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - A reference to the `QueryResult` to render.
+    pub fn render_table(result: &QueryResult) {
+        print!("{}", Self::table(result, &TerminalStyle::detect(None)));
+    }
+
+    /// Builds the table of `render_table`. Numbers are aligned to the right, text to the left.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - A reference to the `QueryResult` to render.
+    /// * `style` - A reference to the `TerminalStyle` to draw with.
+    pub fn table(result: &QueryResult, style: &TerminalStyle) -> String {
+        // A column is aligned as its first value, which is the same kind in every row.
+        let columns: Vec<(&str, Align)> = result
+            .columns
+            .iter()
+            .enumerate()
+            .map(
+                |(idx, column)| match result.rows.first().map(|row| &row[idx]) {
+                    Some(QueryValue::Number(_)) => (column.as_str(), Align::Right),
+                    _ => (column.as_str(), Align::Left),
+                },
+            )
+            .collect();
+        let mut table = Table::new(&columns);
+        for row in &result.rows {
+            table.row(
+                row.iter()
+                    .map(|value| Cell::from(value.to_string()))
+                    .collect(),
+            );
+        }
+
+        let count = result.rows.len();
+        format!(
+            "{}({} row{})\n",
+            table.render(style),
+            count,
+            if count == 1 { "" } else { "s" }
+        )
+    }
+}
@@ -0,0 +1,225 @@
+use serde_json::json;
+
+use crate::quake_log_parse::{
+    model::{
+        error::LogError,
+        log_model::Match,
+        query::{QueryResult, QueryValue},
+        repl::{ExportFormat, Workspace},
+        stats::PlayerStats,
+    },
+    view::{
+        api_view::ApiView,
+        log_view::{matches_json, playtime_json, ranking_json},
+        query_view::QueryView,
+    },
+};
+
+const HELP: &str = "Commands:
+  load INPUT...                       Load the matches of log files, directories or glob patterns
+  matches [PLAYER|MEANS]              List the matches, or those of a player or means of death
+  match ID                            Show a match
+  player NAME                         Show the statistics of a player, by name or alias
+  ranking [by kills|playtime|matches|name]
+                                      Rank the players
+  export json|csv PATH                Write the report (json) or the player statistics (csv)
+  help                                Show this message
+  quit                                Leave
+Tab completes commands, player names and means of death.";
+
+/// Renders the answers of the commands of the REPL.
+pub struct ReplView {}
+impl ReplView {
+    /// Renders the line greeting the user when the REPL starts.
+    pub fn render_welcome() {
+        println!("Type help for the commands.");
+    }
+
+    /// Renders the commands of the REPL.
+    pub fn render_help() {
+        println!("{}", HELP);
+    }
+
+    /// Renders how many matches were loaded and from how many files.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace` - A reference to the `Workspace` loaded.
+    pub fn render_loaded(workspace: &Workspace) {
+        println!(
+            "Loaded {} matches from {} file{}.",
+            workspace.matches.len(),
+            workspace.files.len(),
+            if workspace.files.len() == 1 { "" } else { "s" }
+        );
+    }
+
+    /// Renders the path a file was exported to.
+    pub fn render_exported(path: &str) {
+        println!("Exported to {}.", path);
+    }
+
+    /// Renders a list of matches as a table with their map, game type, players, kills and whether
+    /// they completed.
+    ///
+    /// # Arguments
+    ///
+    /// * `games` - A slice of the `Match` structs to render.
+    pub fn render_matches(games: &[&Match]) {
+        let rows = games
+            .iter()
+            .map(|game| {
+                let setting = |name: &str| {
+                    QueryValue::Text(game.data.settings.get(name).cloned().unwrap_or_default())
+                };
+                vec![
+                    QueryValue::Number(game.id.into()),
+                    setting("mapname"),
+                    setting("g_gametype"),
+                    QueryValue::Number(game.data.players.len() as i64),
+                    QueryValue::Number(game.data.total_kills.into()),
+                    QueryValue::Text(game.data.completed.to_string()),
+                ]
+            })
+            .collect();
+        QueryView::render_table(&QueryResult {
+            columns: ["id", "map", "gametype", "players", "kills", "completed"]
+                .map(String::from)
+                .to_vec(),
+            rows,
+        });
+    }
+
+    /// Renders a match in JSON format, as served by `/matches/{id}`.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - A reference to the `Match` to render.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the match cannot be serialized.
+    pub fn render_match(game: &Match) -> Result<(), LogError> {
+        println!("{}", serde_json::to_string_pretty(&ApiView::game(game))?);
+        Ok(())
+    }
+
+    /// Renders the statistics of a player in JSON format, as served by `/players/{name}`.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - A reference to the `PlayerStats` to render.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the statistics cannot be serialized.
+    pub fn render_player(player: &PlayerStats) -> Result<(), LogError> {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&ApiView::player(player))?
+        );
+        Ok(())
+    }
+
+    /// Renders players as a table, in the order given.
+    ///
+    /// # Arguments
+    ///
+    /// * `players` - A slice of the `PlayerStats` to render.
+    pub fn render_ranking(players: &[PlayerStats]) {
+        let rows = players
+            .iter()
+            .enumerate()
+            .map(|(idx, player)| {
+                vec![
+                    QueryValue::Number(idx as i64 + 1),
+                    QueryValue::Text(player.name.clone()),
+                    QueryValue::Number(player.kills.into()),
+                    QueryValue::Number(player.matches.len() as i64),
+                    QueryValue::Number(player.playtime.into()),
+                ]
+            })
+            .collect();
+        QueryView::render_table(&QueryResult {
+            columns: ["#", "player", "kills", "matches", "playtime"]
+                .map(String::from)
+                .to_vec(),
+            rows,
+        });
+    }
+
+    /// Renders an error of a command. Mistakes in commands are shown as is, other errors with their
+    /// kind.
+    ///
+    /// # Arguments
+    ///
+    /// * `err` - A reference to the `LogError` to render.
+    pub fn render_error(err: &LogError) {
+        match err {
+            LogError::CommandError(message) => println!("{}", message),
+            err => println!("Error: {:?}", err),
+        }
+    }
+
+    /// Builds the content of a file written by `export`.
+    ///
+    /// The JSON export holds the `matches`, `Player Ranking` and `Player Playtime` of the report in a
+    /// single object. The CSV export has a header and one row per player, in ranking order.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The `ExportFormat` to build.
+    /// * `workspace` - A reference to the `Workspace` to export.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, LogError>` - A `Result` with the content, or an error (`Err`) if it cannot be
+    ///   serialized.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the report cannot be serialized.
+    pub fn export(format: ExportFormat, workspace: &Workspace) -> Result<String, LogError> {
+        match format {
+            ExportFormat::Json => Ok(serde_json::to_string_pretty(&json!({
+                "matches": matches_json(&workspace.matches),
+                "Player Ranking": ranking_json(&workspace.player_ranking()),
+                "Player Playtime": playtime_json(&workspace.playtime()),
+            }))? + "\n"),
+            ExportFormat::Csv => {
+                let mut csv = String::from("rank,name,kills,matches,playtime,aliases\n");
+                for player in workspace.ranking(Default::default()) {
+                    csv.push_str(&format!(
+                        "{},{},{},{},{},{}\n",
+                        player.rank,
+                        csv_field(&player.name),
+                        player.kills,
+                        player.matches.len(),
+                        player.playtime,
+                        csv_field(&player.aliases.join(";"))
+                    ));
+                }
+                Ok(csv)
+            }
+        }
+    }
+}
+
+/// Quotes a CSV field when it holds a comma, a quote or a line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
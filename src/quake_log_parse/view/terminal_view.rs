@@ -0,0 +1,354 @@
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// The widest a bar of a bar chart gets, in columns.
+const MAX_BAR_WIDTH: usize = 40;
+/// The narrowest a column of text is truncated to when a table does not fit the terminal.
+const MIN_COLUMN_WIDTH: usize = 6;
+/// The eighths of a block, to draw the end of a bar.
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// How text is drawn on the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalStyle {
+    /// Whether to use ANSI colours.
+    pub color: bool,
+    /// The number of columns to fit the output in, or `None` not to truncate it.
+    pub width: Option<usize>,
+}
+
+/// The colours and effects text is painted with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Paint {
+    Bold,
+    Dim,
+    Red,
+    Green,
+    Yellow,
+    Cyan,
+}
+
+/// The alignment of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// A cell of a `Table`.
+#[derive(Debug, Clone)]
+pub struct Cell {
+    text: String,
+    paint: Option<Paint>,
+    /// The text with its own ANSI colours, drawn instead of `text` when colours are used.
+    colored: Option<String>,
+}
+
+/// A table drawn with box-drawing characters, with aligned columns.
+///
+/// When the table is wider than the terminal, the columns aligned to the left (e.g. player names)
+/// are truncated, widest first.
+pub struct Table {
+    title: Option<String>,
+    columns: Vec<(String, Align)>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl TerminalStyle {
+    /// Detects the style of the standard output.
+    ///
+    /// Colours are used when `color` is `Some(true)`, or, when it is `None`, if the standard output is
+    /// a terminal and `NO_COLOR` is not set. The width is taken from `COLUMNS`, or from the terminal.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Whether the user asked for colours, or `None` to detect it.
+    pub fn detect(color: Option<bool>) -> Self {
+        let terminal = io::stdout().is_terminal();
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        let width = env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.parse().ok())
+            .or_else(|| {
+                terminal
+                    .then(ratatui::crossterm::terminal::size)
+                    .and_then(Result::ok)
+                    .map(|(columns, _)| columns as usize)
+            });
+        TerminalStyle {
+            color: color.unwrap_or(terminal && !no_color),
+            width,
+        }
+    }
+
+    /// Paints text, if colours are used.
+    pub fn paint(&self, text: &str, paint: Paint) -> String {
+        if !self.color {
+            return text.to_string();
+        }
+        let code = match paint {
+            Paint::Bold => "1",
+            Paint::Dim => "2",
+            Paint::Red => "31",
+            Paint::Green => "32",
+            Paint::Yellow => "33",
+            Paint::Cyan => "36",
+        };
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    }
+}
+
+impl Cell {
+    /// Creates a cell painted with `paint`.
+    pub fn painted(text: impl ToString, paint: Paint) -> Self {
+        Cell {
+            text: text.to_string(),
+            paint: Some(paint),
+            colored: None,
+        }
+    }
+
+    /// Creates a cell drawn as `colored`, the same text with its own ANSI colours (e.g. a player name
+    /// with its Quake colours), when colours are used and the text is not truncated.
+    pub fn colored(text: impl ToString, colored: String) -> Self {
+        Cell {
+            text: text.to_string(),
+            paint: None,
+            colored: Some(colored),
+        }
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell {
+            text,
+            paint: None,
+            colored: None,
+        }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::from(text.to_string())
+    }
+}
+
+impl Table {
+    /// Creates a table with the given columns and no rows.
+    pub fn new(columns: &[(&str, Align)]) -> Self {
+        Table {
+            title: None,
+            columns: columns
+                .iter()
+                .map(|(name, align)| (name.to_string(), *align))
+                .collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Sets the title drawn above the table.
+    pub fn title(mut self, title: impl ToString) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Adds a row, with one cell per column.
+    pub fn row(&mut self, cells: Vec<Cell>) {
+        self.rows.push(cells);
+    }
+
+    /// Draws the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `style` - A reference to the `TerminalStyle` to draw with.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The lines of the table, each ending with a line break.
+    pub fn render(&self, style: &TerminalStyle) -> String {
+        let mut widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(idx, (name, _))| {
+                self.rows
+                    .iter()
+                    .map(|row| row[idx].text.width())
+                    .chain([name.width()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        if let Some(width) = style.width {
+            self.fit(&mut widths, width);
+        }
+
+        let rule = |left: &str, middle: &str, right: &str| {
+            let lines: Vec<String> = widths.iter().map(|width| "─".repeat(width + 2)).collect();
+            format!("{}{}{}\n", left, lines.join(middle), right)
+        };
+        let line = |cells: Vec<String>| format!("│ {} │\n", cells.join(" │ "));
+
+        let mut table = String::new();
+        if let Some(title) = &self.title {
+            table.push_str(&style.paint(&truncate(title, style.width), Paint::Bold));
+            table.push('\n');
+        }
+        table.push_str(&rule("┌", "┬", "┐"));
+        table.push_str(&line(
+            self.columns
+                .iter()
+                .zip(&widths)
+                .map(|((name, align), width)| {
+                    style.paint(
+                        &pad(&truncate(name, Some(*width)), *width, *align),
+                        Paint::Bold,
+                    )
+                })
+                .collect(),
+        ));
+        table.push_str(&rule("├", "┼", "┤"));
+        for row in &self.rows {
+            table.push_str(&line(
+                row.iter()
+                    .zip(&self.columns)
+                    .zip(&widths)
+                    .map(|((cell, (_, align)), width)| {
+                        let shown = truncate(&cell.text, Some(*width));
+                        match (&cell.colored, cell.paint) {
+                            (Some(colored), _) if style.color && shown == cell.text => {
+                                pad_colored(colored, &shown, *width, *align)
+                            }
+                            (_, Some(paint)) => style.paint(&pad(&shown, *width, *align), paint),
+                            _ => pad(&shown, *width, *align),
+                        }
+                    })
+                    .collect(),
+            ));
+        }
+        table.push_str(&rule("└", "┴", "┘"));
+        table
+    }
+
+    /// Narrows the columns aligned to the left, widest first, until the table fits `width` columns
+    /// or they cannot be narrower.
+    fn fit(&self, widths: &mut [usize], width: usize) {
+        // Each column takes its padding and a border, plus the border closing the line.
+        let total = |widths: &[usize]| widths.iter().map(|width| width + 3).sum::<usize>() + 1;
+        while total(widths) > width {
+            let widest = self
+                .columns
+                .iter()
+                .enumerate()
+                .filter(|(idx, (_, align))| {
+                    *align == Align::Left && widths[*idx] > MIN_COLUMN_WIDTH
+                })
+                .max_by_key(|(idx, _)| widths[*idx]);
+            match widest {
+                Some((idx, _)) => widths[idx] -= 1,
+                None => return,
+            }
+        }
+    }
+}
+
+/// Draws a horizontal bar chart, with the bars scaled to the largest value.
+///
+/// # Arguments
+///
+/// * `title` - The title drawn above the chart.
+/// * `bars` - The label and value of each bar, in the order to draw them. Negative values are drawn
+///   as empty bars.
+/// * `style` - A reference to the `TerminalStyle` to draw with.
+///
+/// # Returns
+///
+/// * `String` - The lines of the chart, each ending with a line break.
+pub fn bar_chart(title: &str, bars: &[(String, i64)], style: &TerminalStyle) -> String {
+    let mut chart = format!("{}\n", style.paint(title, Paint::Bold));
+    let max = bars
+        .iter()
+        .map(|(_, value)| *value)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let value_width = bars
+        .iter()
+        .map(|(_, value)| value.to_string().len())
+        .max()
+        .unwrap_or(0);
+    let mut label_width = bars
+        .iter()
+        .map(|(label, _)| label.width())
+        .max()
+        .unwrap_or(0);
+    let mut bar_width = MAX_BAR_WIDTH;
+    if let Some(width) = style.width {
+        // The label and the value are separated from the bar by two spaces each.
+        let room = width.saturating_sub(value_width + 4);
+        bar_width = bar_width.min(room.saturating_sub(label_width)).max(10);
+        label_width = label_width.min(room.saturating_sub(bar_width).max(MIN_COLUMN_WIDTH));
+    }
+
+    for (label, value) in bars {
+        let eighths = (*value).max(0) as usize * bar_width * 8 / max as usize;
+        let mut bar = "█".repeat(eighths / 8);
+        if !eighths.is_multiple_of(8) {
+            bar.push(PARTIAL_BLOCKS[eighths % 8]);
+        }
+        chart.push_str(&format!(
+            "{}  {}{}  {:>value_width$}\n",
+            pad(
+                &truncate(label, Some(label_width)),
+                label_width,
+                Align::Left
+            ),
+            style.paint(&bar, Paint::Cyan),
+            " ".repeat(bar_width - bar.chars().count()),
+            value,
+            value_width = value_width
+        ));
+    }
+    chart
+}
+
+/// Shortens text to `width` columns, ending it with `…` when it is cut.
+pub fn truncate(text: &str, width: Option<usize>) -> String {
+    let Some(width) = width.filter(|width| text.width() > *width) else {
+        return text.to_string();
+    };
+    let mut truncated = String::new();
+    let mut used = 0;
+    for char in text.chars() {
+        let char_width = char.width().unwrap_or(0);
+        if used + char_width + 1 > width {
+            break;
+        }
+        truncated.push(char);
+        used += char_width;
+    }
+    truncated.push('…');
+    truncated
+}
+
+/// Pads text with spaces to `width` columns.
+fn pad(text: &str, width: usize, align: Align) -> String {
+    pad_colored(text, text, width, align)
+}
+
+/// Pads text with ANSI colours with spaces to `width` columns, measuring it as `plain`, the same text
+/// without the escape sequences.
+fn pad_colored(colored: &str, plain: &str, width: usize, align: Align) -> String {
+    let padding = " ".repeat(width.saturating_sub(plain.width()));
+    match align {
+        Align::Left => format!("{}{}", colored, padding),
+        Align::Right => format!("{}{}", padding, colored),
+    }
+}
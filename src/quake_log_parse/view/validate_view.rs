@@ -0,0 +1,85 @@
+use crate::quake_log_parse::{
+    model::{
+        error::LogError,
+        validate::{Severity, Validation},
+    },
+    view::terminal_view::{Paint, TerminalStyle},
+};
+
+/// Renders the findings of the validation of logs.
+pub struct ValidateView {}
+impl ValidateView {
+    /// Renders the findings as text, one per line as `file:line: severity[check]: message`, followed
+    /// by how many errors and warnings were found.
+    ///
+    /// # Arguments
+    ///
+    /// * `validation` - A reference to the `Validation` to render.
+    /// * `style` - A reference to the `TerminalStyle` to draw with.
+    pub fn render_text(validation: &Validation, style: &TerminalStyle) {
+        print!("{}", Self::text(validation, style));
+    }
+
+    /// Renders the findings in JSON format, as a single document with the number of errors and
+    /// warnings.
+    ///
+    /// # Arguments
+    ///
+    /// * `validation` - A reference to the `Validation` to render.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), LogError>` - A `Result` indicating success (`Ok`) or an error (`Err`) if any problem
+    ///   occurs during rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type `LogError` if the findings cannot be serialized.
+    pub fn render_json(validation: &Validation) -> Result<(), LogError> {
+        println!("{}", serde_json::to_string_pretty(validation)?);
+        Ok(())
+    }
+
+    /// Builds the text of `render_text`.
+    pub fn text(validation: &Validation, style: &TerminalStyle) -> String {
+        if validation.findings.is_empty() {
+            return "No problems found.\n".to_string();
+        }
+
+        let mut text = String::new();
+        for finding in &validation.findings {
+            let severity = match finding.severity {
+                Severity::Error => style.paint("error", Paint::Red),
+                Severity::Warning => style.paint("warning", Paint::Yellow),
+            };
+            text.push_str(&format!(
+                "{}:{}: {}[{}]: {}\n",
+                finding.file,
+                finding.line,
+                severity,
+                finding.check.name(),
+                finding.message
+            ));
+        }
+        text.push_str(&style.paint(
+            &format!(
+                "{} {}, {} {}",
+                validation.errors,
+                plural(validation.errors, "error"),
+                validation.warnings,
+                plural(validation.warnings, "warning")
+            ),
+            Paint::Bold,
+        ));
+        text.push('\n');
+        text
+    }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        word.to_string()
+    } else {
+        format!("{}s", word)
+    }
+}
@@ -0,0 +1,65 @@
+//! Helpers shared by the integration tests.
+
+// Each test crate includes this module and uses only some of the helpers.
+#![allow(dead_code)]
+
+use std::{
+    env,
+    fs::{create_dir_all, remove_dir_all, write},
+    ops::Deref,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Number of temporary files created by the tests of this process, so each gets its own directory.
+static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+/// A file written in a directory of its own under the temporary directory of the system.
+///
+/// The directory is removed with everything in it when the `TempFile` is dropped, even if the test
+/// fails, so other files the test writes next to it (see `TempFile::sibling`) are removed too.
+pub struct TempFile {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Writes `content` to a new file named `name`.
+    pub fn new(name: &str, content: &str) -> Self {
+        let dir = env::temp_dir().join(format!(
+            "quake_log_parse_{}_{}",
+            process::id(),
+            CREATED.fetch_add(1, Ordering::Relaxed)
+        ));
+        create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        write(&path, content).unwrap();
+        TempFile { dir, path }
+    }
+
+    /// Returns the path of a file named `name` next to this one, which is not created.
+    pub fn sibling(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+}
+
+impl Deref for TempFile {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for TempFile {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.dir);
+    }
+}
@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        thread,
+    };
+
+    use quake_log_parse::quake_log_parse::{
+        controller::{handle_request, ApiServer, ApiState},
+        model::{
+            identity::AliasMap,
+            log_model::{process_events_matches, Match},
+        },
+    };
+    use serde_json::{json, Value};
+
+    const LOG: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:02 ClientBegin: 2
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Dono da Bola\t\0\model\sarge/default
+  0:04 ClientBegin: 3
+  0:10 Kill: 2 3 10: Isgalamido killed Dono da Bola by MOD_RAILGUN
+  0:20 Kill: 2 3 10: Isgalamido killed Dono da Bola by MOD_RAILGUN
+  0:30 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT
+  1:00 ShutdownGame:
+  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\4\mapname\q3dm6
+  1:06 ClientConnect: 3
+  1:06 ClientUserinfoChanged: 3 n\Dono da Bola\t\0\model\sarge
+  1:07 ClientBegin: 3
+  1:08 ClientConnect: 4
+  1:08 ClientUserinfoChanged: 4 n\Mal\t\0\model\sarge
+  1:09 ClientBegin: 4
+  1:10 Kill: 3 4 7: Dono da Bola killed Mal by MOD_ROCKET_SPLASH
+  1:20 ShutdownGame:
+"#;
+
+    fn state() -> ApiState {
+        let mut matches: Vec<Match> = Vec::new();
+        process_events_matches(&mut matches, LOG).unwrap();
+        ApiState::new(matches, &AliasMap::default())
+    }
+
+    fn get(state: &ApiState, url: &str) -> (u16, Value) {
+        let response = handle_request(state, "GET", url);
+        (response.status, response.body)
+    }
+
+    #[test]
+    fn test_matches_endpoints() {
+        let state = state();
+
+        let (status, body) = get(&state, "/matches");
+        assert_eq!(status, 200);
+        assert_eq!(body["total"], 2);
+        assert_eq!(body["matches"][0]["id"], 1);
+        assert_eq!(
+            body["matches"][0]["kills"],
+            json!({"Dono da Bola": 0, "Isgalamido": 1})
+        );
+
+        let (_, body) = get(&state, "/matches?map=q3dm6");
+        assert_eq!(body["total"], 1);
+        assert_eq!(body["matches"][0]["id"], 2);
+        let (_, body) = get(&state, "/matches?player=Isgalamido&gametype=0");
+        assert_eq!(body["total"], 1);
+        let (_, body) = get(&state, "/matches?offset=1&limit=1");
+        assert_eq!(body["matches"].as_array().unwrap().len(), 1);
+        assert_eq!(body["matches"][0]["id"], 2);
+
+        let (status, body) = get(&state, "/matches/2");
+        assert_eq!(status, 200);
+        assert_eq!(body["death_causes"], json!({"MOD_ROCKET_SPLASH": 1}));
+        assert_eq!(body["settings"]["mapname"], "q3dm6");
+        assert_eq!(get(&state, "/matches/3").0, 404);
+    }
+
+    #[test]
+    fn test_players_ranking_and_weapons() {
+        let state = state();
+
+        let (_, body) = get(&state, "/ranking");
+        assert_eq!(
+            body["Player Ranking"],
+            json!([{"Dono da Bola": 1}, {"Isgalamido": 1}, {"Mal": 0}])
+        );
+
+        let (status, body) = get(&state, "/players/Dono%20da%20Bola");
+        assert_eq!(status, 200);
+        assert_eq!(body["rank"], 1);
+        assert_eq!(body["matches"], json!([1, 2]));
+        assert_eq!(get(&state, "/players/Zeh").0, 404);
+        let (_, body) = get(&state, "/players?limit=1");
+        assert_eq!(body["total"], 3);
+        assert_eq!(body["players"][0]["name"], "Dono da Bola");
+
+        // Filtered players and rankings only cover the matches selected.
+        let (_, body) = get(&state, "/ranking?map=q3dm17");
+        assert_eq!(
+            body["Player Ranking"],
+            json!([{"Isgalamido": 1}, {"Dono da Bola": 0}])
+        );
+        let (_, body) = get(&state, "/players?time=1:00..");
+        assert_eq!(body["total"], 2);
+        assert_eq!(body["players"][0]["name"], "Dono da Bola");
+        assert_eq!(body["players"][0]["matches"], json!([2]));
+
+        let (_, body) = get(&state, "/weapons?map=q3dm17");
+        assert_eq!(
+            body["weapons"],
+            json!([
+                {"means": "MOD_RAILGUN", "kills": 2},
+                {"means": "MOD_TRIGGER_HURT", "kills": 1}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        let state = state();
+        assert_eq!(get(&state, "/").0, 404);
+        assert_eq!(get(&state, "/matches?limit=0").0, 400);
+        assert_eq!(get(&state, "/matches?limit=ten").0, 400);
+        assert_eq!(get(&state, "/ranking?time=1:x").0, 400);
+        assert_eq!(get(&state, "/matches?map=a&map=b").0, 400);
+        assert_eq!(handle_request(&state, "POST", "/matches").status, 405);
+    }
+
+    #[test]
+    fn test_serve_on_localhost() {
+        let server = ApiServer::bind("127.0.0.1:0", state()).unwrap();
+        let address = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "GET /matches/1 HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("Content-Type: application/json"));
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["total_kills"], 3);
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use quake_log_parse::quake_log_parse::model::{
+        browser::{Browser, BrowserAction, ScoreSort, Screen},
+        log_model::ParseOptions,
+    };
+
+    const LOG: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:01 ClientBegin: 2
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  0:03 ClientBegin: 3
+  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  0:20 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT
+  0:30 Kill: 3 2 6: Zeh killed Isgalamido by MOD_ROCKET
+  0:40 Kill: 3 2 6: Zeh killed Isgalamido by MOD_ROCKET
+  1:00 ShutdownGame:
+  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  1:06 ClientConnect: 4
+  1:06 ClientUserinfoChanged: 4 n\Mal\t\0\model\sarge/default
+  1:06 ClientBegin: 4
+  1:10 Kill: 1022 4 22: <world> killed Mal by MOD_TRIGGER_HURT
+"#;
+
+    fn browser() -> Browser {
+        Browser::new(LOG.to_string(), ParseOptions::default()).unwrap()
+    }
+
+    fn scores(browser: &Browser) -> Vec<(String, i32, i32)> {
+        browser
+            .scoreboard()
+            .into_iter()
+            .map(|row| (row.name, row.kills, row.deaths))
+            .collect()
+    }
+
+    #[test]
+    fn test_navigate_matches() {
+        let mut browser = browser();
+        assert_eq!(browser.matches().len(), 2);
+
+        browser.apply(BrowserAction::Down);
+        browser.apply(BrowserAction::Down);
+        assert_eq!(browser.selected, 1);
+        browser.apply(BrowserAction::Select);
+        assert_eq!(browser.screen, Screen::Scoreboard);
+        assert_eq!(browser.timeline().len(), 1);
+        assert_eq!(scores(&browser), vec![("Mal".to_string(), -1, 1)]);
+
+        browser.apply(BrowserAction::Back);
+        browser.apply(BrowserAction::Up);
+        browser.apply(BrowserAction::Select);
+        assert_eq!(browser.current_match().unwrap().id, 1);
+        assert_eq!(browser.timeline().len(), 4);
+        assert_eq!(browser.position, 4);
+    }
+
+    #[test]
+    fn test_scrub_and_sort_scoreboard() {
+        let mut browser = browser();
+        browser.apply(BrowserAction::Select);
+        assert_eq!(
+            scores(&browser),
+            vec![("Zeh".to_string(), 2, 1), ("Isgalamido".to_string(), 0, 3)]
+        );
+
+        browser.apply(BrowserAction::Backward);
+        browser.apply(BrowserAction::Backward);
+        assert_eq!(
+            scores(&browser),
+            vec![("Isgalamido".to_string(), 0, 1), ("Zeh".to_string(), 0, 1)]
+        );
+        assert_eq!(browser.timeline()[1].at, 20);
+
+        browser.apply(BrowserAction::Sort);
+        assert_eq!(browser.sort, ScoreSort::Deaths);
+        browser.apply(BrowserAction::Start);
+        assert_eq!(browser.position, 0);
+        browser.apply(BrowserAction::Backward);
+        assert_eq!(browser.position, 0);
+        browser.apply(BrowserAction::End);
+        assert_eq!(scores(&browser)[0], ("Isgalamido".to_string(), 0, 3));
+    }
+
+    #[test]
+    fn test_player_kills_and_weapons() {
+        let mut browser = browser();
+        browser.apply(BrowserAction::Select);
+        browser.apply(BrowserAction::Select);
+        assert_eq!(browser.screen, Screen::Player);
+        assert_eq!(browser.current_player().as_deref(), Some("Zeh"));
+        assert_eq!(browser.player_kills().len(), 3);
+        assert_eq!(
+            browser.player_weapons(),
+            vec![
+                ("MOD_ROCKET".to_string(), 2, 0),
+                ("MOD_RAILGUN".to_string(), 0, 1)
+            ]
+        );
+
+        browser.apply(BrowserAction::Start);
+        browser.apply(BrowserAction::Forward);
+        assert_eq!(browser.player_kills()[0].killer, "Isgalamido");
+        browser.apply(BrowserAction::Back);
+        assert_eq!(browser.screen, Screen::Scoreboard);
+    }
+}
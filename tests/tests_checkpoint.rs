@@ -0,0 +1,137 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{remove_file, write, OpenOptions},
+        io::Write,
+        path::Path,
+    };
+
+    use quake_log_parse::quake_log_parse::model::{
+        checkpoint::{process_incremental, Checkpoint, Resume},
+        log_model::{process_events_matches, ParseOptions},
+    };
+
+    use crate::common::TempFile;
+
+    const FIRST_MATCH: &str = r#"  0:00 ------------------------------------------------------------
+  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:01 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  1:00 ShutdownGame:
+"#;
+
+    /// The start of a match still being played, up to a line being written.
+    const SECOND_MATCH_START: &str = r#"  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  1:06 ClientUserinfoChanged: 4 n\Mal\t\0\model\sarge
+  1:08 say: Mal: ShutdownGame: soon
+  1:10 Kill: 4 4 7: Mal killed Mal by MOD_ROCK"#;
+
+    const SECOND_MATCH_END: &str = r#"ET_SPLASH
+  1:12 Kill: 1022 4 22: <world> killed Mal by MOD_TRIGGER_HURT
+  1:20 ShutdownGame:
+"#;
+
+    fn append(path: &Path, content: &str) {
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let path = TempFile::new(
+            "games.log",
+            &format!("{}{}", FIRST_MATCH, SECOND_MATCH_START),
+        );
+        let options = ParseOptions::default();
+
+        // The match being played is kept for the next run.
+        let run = process_incremental(&path, None, &options).unwrap();
+        assert_eq!(run.resume, Resume::Start);
+        assert_eq!(run.matches.len(), 1);
+        assert_eq!(run.checkpoint.matches, 1);
+        assert_eq!(
+            run.checkpoint.offset,
+            (FIRST_MATCH.len() + SECOND_MATCH_START.rfind('\n').unwrap() + 1) as u64
+        );
+        // A chat line does not end the match.
+        let open_match = run.checkpoint.open_match.as_ref().unwrap();
+        assert_eq!(open_match.data.started_at, 65);
+        assert_eq!(open_match.data.ended_at, 68);
+
+        let checkpoint = run.checkpoint;
+        let run = process_incremental(&path, Some(&checkpoint), &options).unwrap();
+        assert_eq!(run.resume, Resume::Checkpoint);
+        assert!(run.matches.is_empty());
+        assert_eq!(run.checkpoint, checkpoint);
+
+        append(&path, SECOND_MATCH_END);
+        let run = process_incremental(&path, Some(&run.checkpoint), &options).unwrap();
+        assert_eq!(run.resume, Resume::Checkpoint);
+        assert_eq!(run.matches.len(), 1);
+
+        // The match resumed from the checkpoint is the one a full parse of the log gives.
+        let mut full = Vec::new();
+        process_events_matches(
+            &mut full,
+            &format!("{}{}{}", FIRST_MATCH, SECOND_MATCH_START, SECOND_MATCH_END),
+        )
+        .unwrap();
+        let resumed = &run.matches[0];
+        assert_eq!(resumed.id, 2);
+        assert_eq!(resumed.data.total_kills, full[1].data.total_kills);
+        assert_eq!(resumed.data.kills_by_means, full[1].data.kills_by_means);
+        assert_eq!(resumed.data.started_at, full[1].data.started_at);
+        assert_eq!(resumed.data.ended_at, full[1].data.ended_at);
+        assert_eq!(resumed.data.sessions, full[1].data.sessions);
+        assert_eq!(run.checkpoint.matches, 2);
+        assert!(run.checkpoint.open_match.is_none());
+    }
+
+    #[test]
+    fn test_restart_after_rotation_and_truncation() {
+        let path = TempFile::new("games.log", &format!("{}{}", FIRST_MATCH, FIRST_MATCH));
+        let options = ParseOptions::default();
+        let first = process_incremental(&path, None, &options).unwrap();
+        assert_eq!(first.matches.len(), 2);
+
+        // Truncated in place, e.g. by `copytruncate`.
+        write(&path, FIRST_MATCH).unwrap();
+        let run = process_incremental(&path, Some(&first.checkpoint), &options).unwrap();
+        assert_eq!(run.resume, Resume::Truncated);
+        assert_eq!(run.matches.len(), 1);
+        assert_eq!(run.matches[0].id, 3);
+
+        // Replaced by a new log at least as long, with different first bytes.
+        remove_file(&path).unwrap();
+        let other = FIRST_MATCH.replace("q3dm17", "q3dm11");
+        write(&path, format!("{}{}{}", other, other, other)).unwrap();
+        let run = process_incremental(&path, Some(&first.checkpoint), &options).unwrap();
+        assert_eq!(run.resume, Resume::Rotated);
+        assert_eq!(run.matches.len(), 3);
+    }
+
+    #[test]
+    fn test_checkpoint_save_and_load() {
+        let log = TempFile::new("games.log", "");
+        let path = log.sibling("checkpoint.json");
+        assert_eq!(Checkpoint::load(&path).unwrap(), None);
+
+        let checkpoint = Checkpoint {
+            file: "games.log".to_string(),
+            inode: Some(42),
+            head_hash: "abc".to_string(),
+            head_len: 3,
+            offset: 1024,
+            matches: 7,
+            open_match: None,
+        };
+        checkpoint.save(&path).unwrap();
+        assert_eq!(Checkpoint::load(&path).unwrap(), Some(checkpoint));
+
+        write(&path, "not a checkpoint").unwrap();
+        assert!(Checkpoint::load(&path).is_err());
+    }
+}
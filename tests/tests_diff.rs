@@ -0,0 +1,166 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use quake_log_parse::quake_log_parse::model::{
+        diff::{diff_matches, load_matches, Change, CountChange, RankingChange, Standing},
+        identity::AliasMap,
+        log_model::{process_events_matches, Match, ParseOptions},
+    };
+    use serde_json::Value;
+
+    use crate::common::TempFile;
+
+    const BEFORE: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Mocinha\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Mocinha by MOD_RAILGUN
+  0:20 Kill: 3 2 10: Mocinha killed Isgalamido by MOD_RAILGUN
+  0:30 Kill: 3 2 10: Mocinha killed Isgalamido by MOD_RAILGUN
+  1:00 ShutdownGame:
+  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  1:06 ClientConnect: 2
+  1:06 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  2:00 ShutdownGame:
+"#;
+
+    // The first match counts a kill more for Isgalamido and a new player, the second one is gone and
+    // a third one appears.
+    const AFTER: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Mocinha\t\0\model\sarge/default
+  0:04 ClientConnect: 4
+  0:04 ClientUserinfoChanged: 4 n\Zeh\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Mocinha by MOD_RAILGUN
+  0:15 Kill: 2 4 6: Isgalamido killed Zeh by MOD_ROCKET
+  0:20 Kill: 3 2 10: Mocinha killed Isgalamido by MOD_RAILGUN
+  0:30 Kill: 3 2 10: Mocinha killed Isgalamido by MOD_RAILGUN
+  1:00 ShutdownGame:
+"#;
+
+    fn parse(log: &str) -> Vec<Match> {
+        let mut matches = Vec::new();
+        process_events_matches(&mut matches, log).unwrap();
+        matches
+    }
+
+    #[test]
+    fn test_same_reports() {
+        let diff = diff_matches(&parse(BEFORE), &parse(BEFORE), &AliasMap::default());
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_diff_matches() {
+        let mut after = parse(AFTER);
+        after.extend(parse(BEFORE).into_iter().skip(1).map(|mut game| {
+            game.id = 3;
+            game
+        }));
+        let diff = diff_matches(&parse(BEFORE), &after, &AliasMap::default());
+
+        assert_eq!(diff.removed_matches.len(), 1);
+        assert_eq!(diff.removed_matches[0].id, 2);
+        assert_eq!(diff.added_matches[0].id, 3);
+        assert_eq!(diff.added_matches[0].map, "q3dm6");
+
+        let game = &diff.changed_matches[0];
+        assert_eq!(game.id, 1);
+        assert_eq!(
+            game.total_kills,
+            Some(Change {
+                before: 3,
+                after: 4
+            })
+        );
+        assert_eq!(game.players_added, vec!["Zeh"]);
+        assert!(game.players_removed.is_empty());
+        assert_eq!(
+            game.kills,
+            vec![CountChange {
+                name: "Isgalamido".to_string(),
+                before: 1,
+                after: 2
+            }]
+        );
+        assert_eq!(game.death_causes[0].name, "MOD_ROCKET");
+
+        assert_eq!(
+            diff.ranking,
+            vec![
+                RankingChange {
+                    name: "Isgalamido".to_string(),
+                    before: Some(Standing { rank: 2, kills: 1 }),
+                    after: Some(Standing { rank: 1, kills: 2 }),
+                },
+                RankingChange {
+                    name: "Mocinha".to_string(),
+                    before: Some(Standing { rank: 1, kills: 2 }),
+                    after: Some(Standing { rank: 2, kills: 2 }),
+                },
+                RankingChange {
+                    name: "Zeh".to_string(),
+                    before: None,
+                    after: Some(Standing { rank: 3, kills: 0 }),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_command() {
+        let before = TempFile::new("before.log", BEFORE);
+        let after = TempFile::new("after.log", AFTER);
+        let run = |args: &[&str]| {
+            let output = Command::new(env!("CARGO_BIN_EXE_quake_log_parse"))
+                .arg("diff")
+                .args(args)
+                .output()
+                .unwrap();
+            (
+                output.status.code().unwrap_or(-1),
+                String::from_utf8(output.stdout).unwrap(),
+            )
+        };
+        let (before, after) = (before.to_str().unwrap(), after.to_str().unwrap());
+
+        // A report is compared with the log it was exported from.
+        let report = Command::new(env!("CARGO_BIN_EXE_quake_log_parse"))
+            .args(["report", "--format=json", before])
+            .output()
+            .unwrap();
+        let report = TempFile::new("before.json", &String::from_utf8(report.stdout).unwrap());
+        assert_eq!(
+            load_matches(&report, &ParseOptions::default())
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            run(&[before, report.to_str().unwrap()]),
+            (0, "No differences.\n".to_string())
+        );
+
+        let (code, text) = run(&[before, after]);
+        assert_eq!(code, 1);
+        assert!(text.starts_with("Matches: 0 added, 1 removed, 1 changed\n"));
+        assert!(text.contains("- Match 2 · q3dm6 · 0 kills\n"));
+        assert!(text.contains("    + player Zeh\n"));
+        assert!(text.contains("  Isgalamido  #2 → #1 (▲1), kills 1 → 2\n"));
+        assert!(text.contains("  Zeh         new at #3, 0 kills\n"));
+
+        let (code, json) = run(&["--format=json", before, after]);
+        assert_eq!(code, 1);
+        let json: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["changed_matches"][0]["kills"][0]["after"], 2);
+
+        assert_eq!(run(&[before, "/nonexistent/quake.log"]).0, 2);
+        assert_eq!(run(&[before]).0, 2);
+    }
+}
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use quake_log_parse::quake_log_parse::model::{
+        event::{KillEvent, LogEvent, LogLine},
+        intern::Interner,
+        log_model::{process_kill_line, MatchData},
+    };
+
+    #[test]
+    fn test_parse_log_line() {
+        let line = " 20:54 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT";
+        let parsed = LogLine::parse(line);
+        assert_eq!(parsed.timestamp, Some(1254));
+        assert_eq!(
+            parsed.event,
+            LogEvent::Kill(KillEvent {
+                killer_id: Some(1022),
+                victim_id: Some(2),
+                killer: Some("<world>"),
+                victim: Some("Isgalamido"),
+                means: Some("MOD_TRIGGER_HURT"),
+            })
+        );
+
+        let line = r" 20:34 ClientUserinfoChanged: 2 n\Kill: Bill\t\0\model\xian/default";
+        assert_eq!(
+            LogLine::parse(line).event,
+            LogEvent::ClientUserinfoChanged {
+                client_id: Some(2),
+                name: Some("Kill: Bill"),
+            }
+        );
+        assert_eq!(
+            LogLine::parse("  1:47 ClientBegin: 5").event,
+            LogEvent::ClientBegin { client_id: Some(5) }
+        );
+        assert_eq!(
+            LogLine::parse("  1:47 score: 20  ping: 4  client: 5 Zeh").event,
+            LogEvent::Score
+        );
+        assert_eq!(
+            LogLine::parse("  1:47 ShutdownGame:").event,
+            LogEvent::ShutdownGame
+        );
+        assert_eq!(
+            LogLine::parse("  1:02 Item: 2 weapon_rocketlauncher").event,
+            LogEvent::Item {
+                client_id: Some(2),
+                item: Some("weapon_rocketlauncher")
+            }
+        );
+        assert_eq!(
+            LogLine::parse("  2:13 sayteam: Zeh: Kill: 2 3 10: not a kill").event,
+            LogEvent::Say {
+                team: true,
+                name: Some("Zeh"),
+                message: "Kill: 2 3 10: not a kill"
+            }
+        );
+        assert_eq!(
+            LogLine::parse("  2:14 Warmup:").event,
+            LogEvent::Other
+        );
+    }
+
+    #[test]
+    fn test_kill_by_name_with_killed() {
+        let mut game = MatchData::default();
+        game.players.insert("Jack killed Jill".to_owned());
+        game.players.insert("Mal".to_owned());
+
+        let line = "1:00 Kill: 2 3 10: Jack killed Jill killed Mal by MOD_RAILGUN";
+        assert!(process_kill_line(line, &mut game).is_ok());
+        assert_eq!(game.kills.get("Jack killed Jill"), Some(&1));
+        assert_eq!(game.kills_by_means.get("MOD_RAILGUN"), Some(&1));
+    }
+
+    #[test]
+    fn test_interner() {
+        let names = [String::from("Zeh"), String::from("Isgalamido")];
+        let mut interner = Interner::new();
+        let zeh = interner.intern(&names[0]);
+        let isgalamido = interner.intern(&names[1]);
+
+        assert_eq!(interner.intern("Zeh"), zeh);
+        assert_ne!(zeh, isgalamido);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.resolve(isgalamido), "Isgalamido");
+        assert_eq!(interner.get("Mal"), None);
+        let order: Vec<&str> = interner.iter().map(|(_, name)| name).collect();
+        assert_eq!(order, vec!["Zeh", "Isgalamido"]);
+    }
+}
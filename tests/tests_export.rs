@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process::Command};
+
+    use quake_log_parse::quake_log_parse::model::{
+        event::LogEvent,
+        export::{export_events, EventExporter},
+        log_model::ParseOptions,
+    };
+    use serde_json::{json, Value};
+
+    const LOG: &str = r#"  0:00 ------------------------------------------------------------
+  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Mocinha\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Mocinha by MOD_RAILGUN
+  0:20 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT
+  0:25 Item: 3 weapon_rocketlauncher
+  1:00 ShutdownGame:
+  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  1:06 ClientConnect: 3
+"#;
+
+    #[test]
+    fn test_process_lines() {
+        let mut exporter = EventExporter::new(ParseOptions::default(), 4);
+        let events: Vec<_> = LOG
+            .lines()
+            .enumerate()
+            .filter_map(|(idx, line)| exporter.process_line("games.log", idx + 1, line).unwrap())
+            .collect();
+
+        // The separator line is of no interest.
+        assert_eq!(events.len(), 11);
+        assert_eq!(events[0].line, 2);
+        assert_eq!(events[0].match_id, 5);
+        assert_eq!(events[10].match_id, 6);
+        assert_eq!(exporter.matches(), 6);
+
+        let kill = &events[5];
+        assert_eq!((kill.line, kill.timestamp), (7, Some(10)));
+        assert_eq!(kill.killer_name.as_deref(), Some("Isgalamido"));
+        assert_eq!(kill.victim_name.as_deref(), Some("Mocinha"));
+        let world = &events[6];
+        assert_eq!(world.killer_name, None);
+        assert_eq!(world.victim_name.as_deref(), Some("Isgalamido"));
+        assert!(matches!(events[7].event, LogEvent::Item { .. }));
+        assert_eq!(events[7].player.as_deref(), Some("Mocinha"));
+    }
+
+    #[test]
+    fn test_skip_lines_before_first_match() {
+        let mut exporter = EventExporter::new(ParseOptions::default(), 0);
+        let event = exporter
+            .process_line(
+                "games.log",
+                1,
+                "  0:01 ClientUserinfoChanged: 2 n\\Zeh\\t\\0",
+            )
+            .unwrap();
+        assert!(event.is_none());
+        assert_eq!(exporter.matches(), 0);
+    }
+
+    #[test]
+    fn test_export_json_lines() {
+        let mut out = Vec::new();
+        let mut exporter = EventExporter::new(ParseOptions::default(), 0);
+        // A damaged line is skipped, the events after it are still written.
+        let damaged = LOG.replace("  0:25 Item", "  0:24 Kill: 2 x\n  0:25 Item");
+        let summary = export_events(
+            damaged.replace('\n', "\r\n").as_bytes(),
+            "games.log",
+            &mut exporter,
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(summary.events, 11);
+        assert_eq!(summary.skipped.len(), 1);
+        assert_eq!(summary.skipped[0].0, 9);
+
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(
+            lines[5],
+            json!({
+                "file": "games.log",
+                "line": 7,
+                "match": 1,
+                "timestamp": 10,
+                "type": "kill",
+                "killer_id": 2,
+                "victim_id": 3,
+                "killer": "Isgalamido",
+                "victim": "Mocinha",
+                "means": "MOD_RAILGUN",
+                "killer_name": "Isgalamido",
+                "victim_name": "Mocinha",
+            })
+        );
+    }
+
+    #[test]
+    fn test_export_files() {
+        let dir = env::temp_dir().join(format!("quake_log_parse_export_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.log"), LOG).unwrap();
+        fs::write(dir.join("b.log"), LOG).unwrap();
+        let output = env::temp_dir().join(format!(
+            "quake_log_parse_export_{}.ndjson",
+            std::process::id()
+        ));
+
+        let status = Command::new(env!("CARGO_BIN_EXE_quake_log_parse"))
+            .arg("events")
+            .arg(format!("--output={}", output.display()))
+            .arg(&dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let events: Vec<Value> = fs::read_to_string(&output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 22);
+        // Matches are numbered across the files, as in their report.
+        assert_eq!(events[11]["match"], 3);
+        assert!(events[11]["file"].as_str().unwrap().ends_with("b.log"));
+        assert_eq!(events[21]["match"], 4);
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use quake_log_parse::quake_log_parse::model::{
+        error::LogError,
+        filter::{filter_matches, retain_matches, MatchFilter},
+        identity::AliasMap,
+        log_model::{process_events_matches, Match},
+        sources::report_matches,
+    };
+
+    const LOG: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  1:00 ShutdownGame:
+  1:05 InitGame: \sv_hostname\Other Server\g_gametype\4\mapname\q3dm6
+  1:06 ClientConnect: 3
+  1:06 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  1:10 Kill: 1022 3 22: <world> killed Zeh by MOD_TRIGGER_HURT
+  1:15 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  1:16 ClientConnect: 2
+  1:16 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  1:17 ClientConnect: 4
+  1:17 ClientUserinfoChanged: 4 n\Mal\t\0\model\sarge/default
+  1:20 Kill: 4 2 7: Mal killed Isgalamido by MOD_ROCKET_SPLASH
+  1:30 ShutdownGame:
+"#;
+
+    fn matches() -> Vec<Match> {
+        let mut matches = Vec::new();
+        process_events_matches(&mut matches, LOG).unwrap();
+        matches
+    }
+
+    fn selected(filter: &MatchFilter) -> Vec<i32> {
+        let matches = matches();
+        filter_matches(&matches, filter)
+            .iter()
+            .map(|mat| mat.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_set_filter_criteria() {
+        let mut filter = MatchFilter::default();
+        filter.set("ids", "2..").unwrap();
+        filter.set("min-players", "2").unwrap();
+        filter.set("completed", "").unwrap();
+        assert_eq!(
+            filter,
+            MatchFilter {
+                min_id: Some(2),
+                min_players: Some(2),
+                completed: true,
+                ..Default::default()
+            }
+        );
+
+        filter.set("ids", "3").unwrap();
+        assert_eq!((filter.min_id, filter.max_id), (Some(3), Some(3)));
+        assert!(matches!(
+            filter.set("ids", "1..x"),
+            Err(LogError::FilterError(_))
+        ));
+        assert!(matches!(
+            filter.set("time", "10..1:x"),
+            Err(LogError::FilterError(_))
+        ));
+        // Logs have no dates, only the time since the server started.
+        assert!(matches!(
+            filter.set("date", "2024"),
+            Err(LogError::FilterError(_))
+        ));
+    }
+
+    #[test]
+    fn test_filter_by_criteria() {
+        let filter = |name: &str, value: &str| {
+            let mut filter = MatchFilter::default();
+            filter.set(name, value).unwrap();
+            selected(&filter)
+        };
+        assert_eq!(filter("hostname", "Code Miner Server"), vec![1, 3]);
+        assert_eq!(filter("gametype", "4"), vec![2]);
+        assert_eq!(filter("ids", "..2"), vec![1, 2]);
+        assert_eq!(filter("time", "1:05..1:15"), vec![2, 3]);
+        assert_eq!(filter("time", "1:10.."), vec![3]);
+        assert_eq!(filter("min-players", "2"), vec![1, 3]);
+        assert_eq!(filter("completed", "true"), vec![1, 3]);
+        assert_eq!(filter("player", "Mal"), vec![3]);
+
+        let mut both = MatchFilter::default();
+        both.set("map", "q3dm17").unwrap();
+        both.set("player", "Zeh").unwrap();
+        assert_eq!(selected(&both), vec![1]);
+    }
+
+    #[test]
+    fn test_ranking_covers_filtered_matches() {
+        let mut matches = matches();
+        let mut filter = MatchFilter::default();
+        filter.set("ids", "3").unwrap();
+        retain_matches(&mut matches, &filter);
+
+        let files = ["".to_string()];
+        let report = report_matches(&files, matches, &AliasMap::default());
+        let ranking: Vec<(&str, i32)> = report
+            .ranking
+            .iter()
+            .map(|score| (score.name.as_str(), score.kills))
+            .collect();
+        assert_eq!(ranking, vec![("Mal", 1), ("Isgalamido", 0)]);
+        assert_eq!(report.files[0].matches[0].id, 3);
+    }
+}
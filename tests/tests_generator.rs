@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use quake_log_parse::quake_log_parse::model::{
+        generator::{generate_log, GeneratorOptions},
+        log_model::{means_of_death_id, process_events_matches, reconcile_scores},
+    };
+
+    #[test]
+    fn test_generate_log_is_deterministic() {
+        let options = GeneratorOptions {
+            seed: 42,
+            ..Default::default()
+        };
+        assert_eq!(generate_log(&options), generate_log(&options));
+
+        let other_seed = GeneratorOptions {
+            seed: 43,
+            ..Default::default()
+        };
+        assert_ne!(generate_log(&options), generate_log(&other_seed));
+    }
+
+    #[test]
+    fn test_generated_log_parses() {
+        let options = GeneratorOptions {
+            seed: 7,
+            matches: 50,
+            players: 30,
+            rename_rate: 0.3,
+            disconnect_rate: 0.3,
+            truncate_rate: 0.0,
+            ..Default::default()
+        };
+        let log = generate_log(&options);
+        let mut matches = Vec::new();
+        process_events_matches(&mut matches, &log).unwrap();
+
+        assert_eq!(matches.len(), 50);
+        // The id of each means of death is the one the parser knows it by.
+        for kill in log.lines().filter_map(|line| line.split_once("Kill: ")) {
+            let (ids, text) = kill.1.split_once(": ").unwrap();
+            let means = text.rsplit(' ').next().unwrap();
+            let id = ids.rsplit(' ').next().unwrap().parse::<i32>().unwrap();
+            assert_eq!(means_of_death_id(means), Some(id));
+        }
+        for mat in &matches {
+            assert!(mat.data.players.len() >= 2);
+            assert!(!mat.data.scores.is_empty());
+            // The generated scores agree with the kills.
+            assert_eq!(reconcile_scores(&mat.data), vec![]);
+        }
+        assert!(matches
+            .iter()
+            .any(|mat| mat.data.sessions.iter().any(|s| s.names.len() > 1)));
+        assert!(matches
+            .iter()
+            .any(|mat| mat.data.sessions.iter().any(|s| s.left_early)));
+    }
+
+    #[test]
+    fn test_generated_truncations() {
+        let options = GeneratorOptions {
+            seed: 1,
+            matches: 10,
+            truncate_rate: 1.0,
+            ..Default::default()
+        };
+        let log = generate_log(&options);
+        assert!(!log.contains("ShutdownGame:"));
+
+        let mut matches = Vec::new();
+        process_events_matches(&mut matches, &log).unwrap();
+        assert_eq!(matches.len(), 10);
+        assert!(matches.iter().all(|mat| mat.data.scores.is_empty()));
+    }
+}
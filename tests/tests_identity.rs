@@ -0,0 +1,185 @@
+#[cfg(test)]
+mod tests {
+    use quake_log_parse::quake_log_parse::model::{
+        identity::{process_identity_ranking, resolve_identities, AliasMap},
+        log_model::{process_events_matches, Match, MatchData},
+    };
+
+    const LOG_DATA: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default\hmodel\xian/default\c1\5\c2\5
+  0:01 ClientConnect: 3
+  0:01 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default\hmodel\sarge/default\c1\1\c2\5
+  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  0:20 Kill: 3 2 10: Zeh killed Isgalamido by MOD_RAILGUN
+  1:00 ShutdownGame:
+  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  1:06 ClientConnect: 2
+  1:06 ClientUserinfoChanged: 2 n\Isga\t\0\model\xian/default\hmodel\xian/default\c1\5\c2\5
+  1:06 ClientConnect: 3
+  1:06 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default\hmodel\sarge/default\c1\1\c2\5
+  1:10 Kill: 2 3 10: Isga killed Zeh by MOD_RAILGUN
+  1:20 Kill: 2 3 10: Isga killed Zeh by MOD_RAILGUN
+  2:00 ShutdownGame:"#;
+
+    #[test]
+    fn test_resolve_identities_by_look_and_prefix() {
+        let mut matches = Vec::new();
+        assert!(process_events_matches(&mut matches, LOG_DATA).is_ok());
+
+        let identities = resolve_identities(&matches, &AliasMap::default());
+        assert_eq!(identities.len(), 2);
+        assert_eq!(identities[0].name, "Isgalamido");
+        assert_eq!(identities[0].aliases, vec!["Isga".to_string()]);
+        assert_eq!(identities[0].kills, 3);
+        assert_eq!(identities[1].name, "Zeh");
+        assert_eq!(identities[1].kills, 1);
+    }
+
+    #[test]
+    fn test_alias_file() {
+        let log = LOG_DATA.replace("n\\Isga\\", "n\\Mocinha\\");
+        let mut matches = Vec::new();
+        assert!(process_events_matches(&mut matches, &log).is_ok());
+        assert_eq!(resolve_identities(&matches, &AliasMap::default()).len(), 3);
+
+        let aliases = AliasMap::from_toml("[aliases]\nIsgalamido = [\"Mocinha\"]\n").unwrap();
+        let mut ranking = Vec::new();
+        process_identity_ranking(&matches, &aliases, &mut ranking);
+        assert_eq!(ranking.len(), 2);
+        assert_eq!(ranking[0].name, "Isgalamido");
+        assert_eq!(ranking[0].kills, 3);
+
+        let aliases = AliasMap::from_json(r#"{"aliases": {"Isga": ["Isgalamido", "Mocinha"]}}"#);
+        assert_eq!(aliases.unwrap().canonical("Mocinha"), "Isga");
+        assert!(AliasMap::from_json("[1, 2]").is_err());
+        assert!(AliasMap::from_toml("aliases = 3").is_err());
+    }
+
+    #[test]
+    fn test_same_name_different_guid() {
+        let log = LOG_DATA
+            .replace("n\\Zeh\\t\\0\\model", "n\\Zeh\\t\\0\\cl_guid\\AAAA\\model")
+            .replacen("cl_guid\\AAAA", "cl_guid\\BBBB", 1);
+        let mut matches = Vec::new();
+        assert!(process_events_matches(&mut matches, &log).is_ok());
+
+        let identities = resolve_identities(&matches, &AliasMap::default());
+        assert_eq!(identities.len(), 3);
+        let names: Vec<_> = identities.iter().map(|i| i.name.as_str()).collect();
+        assert!(names.contains(&"Zeh [AAAA]"));
+        assert!(names.contains(&"Zeh [BBBB]"));
+    }
+
+    #[test]
+    fn test_same_look_different_players() {
+        // "Zeh" and "Zeh 2" share a look but play each other; "Mal" and "Malandro" only share the
+        // default model.
+        let log = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Zeh\t\0\model\xian/default\hmodel\xian/default\c1\4\c2\5
+  0:01 ClientConnect: 3
+  0:01 ClientUserinfoChanged: 3 n\Zeh 2\t\0\model\xian/default\hmodel\xian/default\c1\4\c2\5
+  0:01 ClientConnect: 4
+  0:01 ClientUserinfoChanged: 4 n\Mal\t\0\model\sarge\hmodel\sarge\c1\4\c2\5
+  0:10 Kill: 2 3 10: Zeh killed Zeh 2 by MOD_RAILGUN
+  0:20 Kill: 3 2 10: Zeh 2 killed Zeh by MOD_RAILGUN
+  0:30 Kill: 3 4 10: Zeh 2 killed Mal by MOD_RAILGUN
+  1:00 ShutdownGame:
+  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  1:06 ClientConnect: 4
+  1:06 ClientUserinfoChanged: 4 n\Malandro\t\0\model\sarge\hmodel\sarge\c1\4\c2\5
+  1:10 Kill: 1022 4 22: <world> killed Malandro by MOD_TRIGGER_HURT
+  2:00 ShutdownGame:"#;
+        let mut matches = Vec::new();
+        assert!(process_events_matches(&mut matches, log).is_ok());
+
+        let identities: Vec<(String, i32)> = resolve_identities(&matches, &AliasMap::default())
+            .into_iter()
+            .map(|identity| (identity.name, identity.kills))
+            .collect();
+        assert_eq!(
+            identities,
+            vec![
+                ("Zeh 2".to_string(), 2),
+                ("Zeh".to_string(), 1),
+                ("Mal".to_string(), 0),
+                ("Malandro".to_string(), -1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identity_ranking_without_sessions() {
+        let mut match_data = MatchData::default();
+        match_data.kills.insert("Player1".to_string(), 5);
+        let mut other = MatchData::default();
+        other.kills.insert("Player1".to_string(), 2);
+        other.kills.insert("Player2".to_string(), -1);
+        let matches = vec![
+            Match {
+                id: 1,
+                data: match_data,
+            },
+            Match { id: 2, data: other },
+        ];
+
+        let mut ranking = Vec::new();
+        process_identity_ranking(&matches, &AliasMap::default(), &mut ranking);
+        assert_eq!(ranking.len(), 2);
+        assert_eq!(ranking[0].name, "Player1");
+        assert_eq!(ranking[0].kills, 7);
+        assert_eq!(ranking[1].kills, -1);
+    }
+
+    #[test]
+    fn test_links_by_look_in_a_fixed_order() {
+        // "Isga" shares a look with "Isgalamido" and another with "Isgalamido2", who meet in the
+        // first match, so only one of them can be linked to "Isga".
+        let log = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default\hmodel\xian/default\c1\5\c2\5
+  0:01 ClientConnect: 3
+  0:01 ClientUserinfoChanged: 3 n\Isgalamido2\t\0\model\razor/id\hmodel\razor/id\c1\2\c2\2
+  0:10 Kill: 2 3 10: Isgalamido killed Isgalamido2 by MOD_RAILGUN
+  0:20 Kill: 3 2 10: Isgalamido2 killed Isgalamido by MOD_RAILGUN
+  0:30 Kill: 3 2 10: Isgalamido2 killed Isgalamido by MOD_RAILGUN
+  1:00 ShutdownGame:
+  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  1:06 ClientConnect: 2
+  1:06 ClientUserinfoChanged: 2 n\Isga\t\0\model\xian/default\hmodel\xian/default\c1\5\c2\5
+  1:06 ClientConnect: 4
+  1:06 ClientUserinfoChanged: 4 n\Zeh\t\0\model\sarge/default\hmodel\sarge/default\c1\1\c2\5
+  1:10 Kill: 2 4 10: Isga killed Zeh by MOD_RAILGUN
+  2:00 ShutdownGame:
+  2:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  2:06 ClientConnect: 2
+  2:06 ClientUserinfoChanged: 2 n\Isga\t\0\model\razor/id\hmodel\razor/id\c1\2\c2\2
+  2:06 ClientConnect: 4
+  2:06 ClientUserinfoChanged: 4 n\Zeh\t\0\model\sarge/default\hmodel\sarge/default\c1\1\c2\5
+  2:10 Kill: 2 4 10: Isga killed Zeh by MOD_RAILGUN
+  2:20 Kill: 2 4 10: Isga killed Zeh by MOD_RAILGUN
+  3:00 ShutdownGame:"#;
+        let mut matches = Vec::new();
+        assert!(process_events_matches(&mut matches, log).is_ok());
+
+        let ranking = || {
+            let mut ranking = Vec::new();
+            process_identity_ranking(&matches, &AliasMap::default(), &mut ranking);
+            ranking
+                .into_iter()
+                .map(|player| (player.name, player.kills))
+                .collect::<Vec<_>>()
+        };
+        for _ in 0..30 {
+            assert_eq!(
+                ranking(),
+                vec![
+                    ("Isga".to_string(), 5),
+                    ("Isgalamido".to_string(), 1),
+                    ("Zeh".to_string(), 0),
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        fs::{create_dir_all, write},
+        io::Write,
+        path::PathBuf,
+    };
+
+    use quake_log_parse::quake_log_parse::model::{
+        input::{read_log_file, Compression},
+        log_model::ParseOptions,
+        sources::process_files,
+    };
+
+    const LOG_DATA: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:01 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  1:00 ShutdownGame:"#;
+
+    /// Writes `games.log` plainly and compressed with every supported format, unique to the test.
+    fn compressed_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("quake_log_parse_{}_{}", name, std::process::id()));
+        create_dir_all(&dir).unwrap();
+        write(dir.join("games.log"), LOG_DATA).unwrap();
+
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(LOG_DATA.as_bytes()).unwrap();
+        write(dir.join("games.log.gz"), gzip.finish().unwrap()).unwrap();
+
+        write(
+            dir.join("games.log.zst"),
+            zstd::encode_all(LOG_DATA.as_bytes(), 0).unwrap(),
+        )
+        .unwrap();
+
+        let mut bzip2 = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        bzip2.write_all(LOG_DATA.as_bytes()).unwrap();
+        write(dir.join("games.log.bz2"), bzip2.finish().unwrap()).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_compression_detect() {
+        assert_eq!(Compression::detect(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+        assert_eq!(
+            Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd]),
+            Compression::Zstd
+        );
+        assert_eq!(Compression::detect(b"BZh91AY"), Compression::Bzip2);
+        assert_eq!(Compression::detect(b"  0:00 InitGame:"), Compression::None);
+        assert_eq!(Compression::detect(&[]), Compression::None);
+    }
+
+    #[test]
+    fn test_read_compressed_logs() {
+        let dir = compressed_dir("compressed");
+        for file in [
+            "games.log",
+            "games.log.gz",
+            "games.log.zst",
+            "games.log.bz2",
+        ] {
+            assert_eq!(
+                read_log_file(&dir.join(file)).unwrap(),
+                LOG_DATA,
+                "{}",
+                file
+            );
+        }
+
+        // Concatenated gzip members, as left by appending rotated logs, are read to the end.
+        let mut first = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        first.write_all(b"first\n").unwrap();
+        let mut second = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        second.write_all(b"second\n").unwrap();
+        let mut members = first.finish().unwrap();
+        members.extend(second.finish().unwrap());
+        write(dir.join("members.log.gz"), members).unwrap();
+        assert_eq!(
+            read_log_file(&dir.join("members.log.gz")).unwrap(),
+            "first\nsecond\n"
+        );
+
+        let mut corrupt = vec![0x1f, 0x8b];
+        corrupt.extend(b"not really gzip");
+        write(dir.join("corrupt.log.gz"), corrupt).unwrap();
+        assert!(read_log_file(&dir.join("corrupt.log.gz")).is_err());
+    }
+
+    #[test]
+    fn test_process_compressed_files() {
+        let dir = compressed_dir("compressed_files");
+        let paths: Vec<PathBuf> = [
+            "games.log",
+            "games.log.gz",
+            "games.log.zst",
+            "games.log.bz2",
+        ]
+        .iter()
+        .map(|file| dir.join(file))
+        .collect();
+
+        let matches = process_files(&paths, &ParseOptions::default(), true).unwrap();
+        assert_eq!(matches.len(), 4);
+        for mat in &matches {
+            assert_eq!(mat.data.total_kills, 1);
+            assert_eq!(mat.data.kills.get("Isgalamido"), Some(&1));
+        }
+    }
+}
@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        fs::{create_dir_all, write, OpenOptions},
+        io::{BufRead, BufReader, Write},
+        net::TcpStream,
+        thread,
+    };
+
+    use quake_log_parse::quake_log_parse::{
+        controller::{ApiServer, ApiState},
+        model::{
+            identity::AliasMap,
+            live::{LiveFeed, LogTail},
+            log_model::{process_events_matches, Match, ParseOptions},
+        },
+    };
+    use serde_json::{json, Value};
+
+    const LOG: &str = r#"  0:00 ------------------------------------------------------------
+  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:02 ClientBegin: 2
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  0:04 ClientBegin: 3
+  0:05 Item: 2 weapon_railgun
+  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  1:00 ShutdownGame:
+"#;
+
+    fn events(feed: &mut LiveFeed, content: &str) -> Vec<Value> {
+        content
+            .lines()
+            .filter_map(|line| feed.process_line(line).unwrap())
+            .map(|event| serde_json::to_value(&event).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_live_feed_events() {
+        let mut feed = LiveFeed::new(ParseOptions::default(), 4);
+        let events = events(&mut feed, LOG);
+
+        let types: Vec<&str> = events
+            .iter()
+            .map(|event| event["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                "init_game",
+                "client_connect",
+                "client_userinfo_changed",
+                "client_begin",
+                "client_connect",
+                "client_userinfo_changed",
+                "client_begin",
+                "item",
+                "kill",
+                "shutdown_game"
+            ]
+        );
+        assert!(events.iter().all(|event| event["match"] == 5));
+        assert_eq!(events[3]["player"], "Isgalamido");
+        assert_eq!(events[7]["player"], "Isgalamido");
+        assert_eq!(events[7]["item"], "weapon_railgun");
+        assert_eq!(
+            events[8],
+            json!({
+                "match": 5,
+                "timestamp": 10,
+                "type": "kill",
+                "killer_id": 2,
+                "victim_id": 3,
+                "killer": "Isgalamido",
+                "victim": "Zeh",
+                "means": "MOD_RAILGUN",
+                "scoreboard": [{"name": "Isgalamido", "kills": 1}, {"name": "Zeh", "kills": 0}],
+            })
+        );
+
+        // Lines are only pushed once a match started.
+        let mut feed = LiveFeed::new(ParseOptions::default(), 0);
+        let kill = "  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN";
+        assert!(feed.process_line(kill).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_live_feed_keeps_matches() {
+        let to_value = |matches: &[Match]| serde_json::to_value(matches).unwrap();
+        let log = format!("{}{}", LOG, LOG.replace("q3dm17", "q3dm6"));
+        let mut parsed = Vec::new();
+        process_events_matches(&mut parsed, &log).unwrap();
+
+        // A malformed line is skipped instead of stopping the feed.
+        let mut feed = LiveFeed::with_history(ParseOptions::default());
+        let (first, second) = log.split_at(LOG.len());
+        feed.replay(first);
+        assert!(feed.process_line("  1:01 Kill: 2 x").is_err());
+        feed.replay(second);
+        assert_eq!(to_value(&feed.matches()), to_value(&parsed));
+
+        let mut feed = LiveFeed::new(ParseOptions::default(), 0);
+        feed.replay(&log);
+        assert_eq!(to_value(&feed.matches()), to_value(&parsed[1..]));
+    }
+
+    #[test]
+    fn test_live_feed_ends_matches_on_any_line() {
+        let to_value = |matches: &[Match]| serde_json::to_value(matches).unwrap();
+        let cut_short = LOG.replace(
+            "  1:00 ShutdownGame:\n",
+            "  0:50 ------------------------------------------------------------\n",
+        );
+        let next = LOG.split_once('\n').unwrap().1.replace("q3dm17", "q3dm6");
+        let log = format!("{}{}", cut_short, next);
+        let mut parsed = Vec::new();
+        process_events_matches(&mut parsed, &log).unwrap();
+        assert_eq!(parsed[0].data.ended_at, 50);
+
+        let mut feed = LiveFeed::with_history(ParseOptions::default());
+        let events = events(&mut feed, &log);
+        assert_eq!(events.len(), 19);
+        assert_eq!(to_value(&feed.matches()), to_value(&parsed));
+    }
+
+    #[test]
+    fn test_log_tail() {
+        let dir = env::temp_dir().join(format!("quake_log_parse_tail_{}", std::process::id()));
+        create_dir_all(&dir).unwrap();
+        let path = dir.join("games.log");
+        write(&path, "  0:00 InitGame: \\mapname\\q3dm17\n").unwrap();
+
+        let mut tail = LogTail::new(&path, 0).unwrap();
+        assert_eq!(tail.poll().unwrap(), "  0:00 InitGame: \\mapname\\q3dm17\n");
+        assert_eq!(tail.poll().unwrap(), "");
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "  0:01 ClientConnect: 2\n  0:02 Client").unwrap();
+        assert_eq!(tail.poll().unwrap(), "  0:01 ClientConnect: 2\n");
+        writeln!(file, "Begin: 2").unwrap();
+        assert_eq!(tail.poll().unwrap(), "  0:02 ClientBegin: 2\n");
+
+        // Truncated: read again from the start.
+        write(&path, "  0:00 ShutdownGame:\n").unwrap();
+        assert_eq!(tail.poll().unwrap(), "  0:00 ShutdownGame:\n");
+    }
+
+    #[test]
+    fn test_push_events_to_clients() {
+        let server = ApiServer::bind(
+            "127.0.0.1:0",
+            ApiState::new(Vec::new(), &AliasMap::default()),
+        )
+        .unwrap();
+        let address = server.local_addr().unwrap();
+        let events = server.events();
+        thread::spawn(move || server.run());
+
+        // Server-Sent Events: the headers are sent once the client is subscribed.
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "GET /events HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut headers = String::new();
+        while !headers.ends_with("\r\n\r\n") {
+            reader.read_line(&mut headers).unwrap();
+        }
+        assert!(headers.starts_with("HTTP/1.1 200"));
+        assert!(headers.contains("Content-Type: text/event-stream"));
+
+        // WebSocket.
+        let (mut socket, _) = tungstenite::connect(format!("ws://{}/events/ws", address)).unwrap();
+
+        events.publish(r#"{"type":"shutdown_game"}"#);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "data: {\"type\":\"shutdown_game\"}\n");
+        assert_eq!(
+            socket.read().unwrap().into_text().unwrap(),
+            r#"{"type":"shutdown_game"}"#
+        );
+    }
+}
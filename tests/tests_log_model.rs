@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use quake_log_parse::{self, quake_log_parse::model::{log_model::{process_events_matches, process_init_game, process_kill_line, MatchData, insert_kills_by_means, Match, process_ranking}, error::LogError}};
+    use quake_log_parse::{self, quake_log_parse::model::{log_model::{process_events_matches, process_init_game, process_kill_line, MatchData, insert_kills_by_means, Match, process_ranking, process_score_line, final_scoreboard, reconcile_scores, ScoreDiscrepancy}, error::LogError}};
     const LOG_DATA: &str = r#"6:34 InitGame: \capturelimit\8\g_maxGameClients\0\timelimit\15\fraglimit\20\dmflags\0\bot_minplayers\0\sv_allowDownload\0\sv_maxclients\16\sv_privateClients\2\g_gametype\= 0\sv_hostname\Code Miner Server\sv_minRate\0\sv_maxRate\10000\sv_minPing\0\sv_maxPing\0\sv_floodProtect\1\version\ioq3 1.36 linux-x86_64 Apr 12 2009\protocol\68\mapname\q3dm17\gamename\baseq3\g_needpass\0
 6:34 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\uriel/zael\hmodel\uriel/zael\g_redteam\\g_blueteam\\c1\5\c2\5\hc\100\w\0\l\0\tt\0\tl\0
 6:34 ClientUserinfoChanged: 3 n\Oootsimo\t\0\model\razor/id\hmodel\razor/id\g_redteam\\g_blueteam\\c1\3\c2\5\hc\100\w\0\l\0\tt\0\tl\0
@@ -155,4 +155,59 @@ mod tests {
         assert_eq!(ranking[2].name, "Player2");
         assert_eq!(ranking[2].kills, -4);
     }
+
+    #[test]
+    fn test_process_score_line() {
+        let mut game = MatchData::default();
+        let line = "20:37 score: 20  ping: 4  client: 5 Assasinu Credi";
+        assert!(process_score_line(line, &mut game).is_ok());
+        assert_eq!(game.scores.len(), 1);
+        assert_eq!(game.scores[0].client_id, 5);
+        assert_eq!(game.scores[0].name, "Assasinu Credi");
+        assert_eq!(game.scores[0].score, 20);
+        assert_eq!(game.scores[0].ping, 4);
+
+        let line = "20:37 score: twenty  ping: 4  client: 5 Assasinu Credi";
+        assert!(process_score_line(line, &mut game).is_err());
+        let line = "20:37 score: 20  ping: 4";
+        assert!(process_score_line(line, &mut game).is_err());
+        assert_eq!(game.scores.len(), 1);
+    }
+
+    #[test]
+    fn test_final_scoreboard_and_reconcile_scores() {
+        let mut matches = Vec::new();
+        let log = format!(
+            "{}\n{}\n{}\n{}",
+            LOG_DATA,
+            "14:11 Exit: Fraglimit hit.",
+            "14:11 score: 3  ping: 8  client: 3 Oootsimo",
+            "14:11 score: 1  ping: 0  client: 4 Dono da Bola"
+        );
+        assert!(process_events_matches(&mut matches, &log).is_ok());
+        let game_data = &matches[0].data;
+        assert_eq!(game_data.scores.len(), 2);
+
+        let scoreboard = final_scoreboard(game_data);
+        assert_eq!(scoreboard.len(), 2);
+        assert_eq!(scoreboard[0].name, "Oootsimo");
+        assert_eq!(scoreboard[0].kills, 3);
+
+        // The self-kill of Dono da Bola takes a point off, as the server counts it.
+        assert_eq!(
+            reconcile_scores(game_data),
+            vec![ScoreDiscrepancy {
+                name: "Dono da Bola".to_string(),
+                computed: 0,
+                reported: 1,
+            }]
+        );
+
+        let mut game = MatchData::default();
+        game.kills.insert("Player1".to_string(), 2);
+        game.kills.insert("Player2".to_string(), 5);
+        let scoreboard = final_scoreboard(&game);
+        assert_eq!(scoreboard[0].name, "Player2");
+        assert!(reconcile_scores(&game).is_empty());
+    }
 }
@@ -0,0 +1,182 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::{env, path::Path, process::Command};
+
+    use quake_log_parse::quake_log_parse::model::{
+        error::LogError,
+        identity::AliasMap,
+        log_model::{
+            final_scoreboard, process_events_matches, process_ranking, Match, PlayerScore,
+        },
+        report::{merge_reports, MatchSource, Report},
+        sources,
+    };
+
+    use crate::common::TempFile;
+
+    const MONDAY: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Mocinha\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Mocinha by MOD_RAILGUN
+  0:20 Kill: 2 3 10: Isgalamido killed Mocinha by MOD_RAILGUN
+  0:30 score: 3  ping: 4  client: 2 Isgalamido
+  0:30 score: 0  ping: 9  client: 3 Mocinha
+  1:00 ShutdownGame:
+"#;
+
+    const TUESDAY: &str = r#"  0:00 InitGame: \sv_hostname\Other Server\g_gametype\0\mapname\q3dm6
+  0:01 ClientConnect: 4
+  0:01 ClientUserinfoChanged: 4 n\Mocinha\t\0\model\sarge/default
+  0:02 ClientConnect: 5
+  0:02 ClientUserinfoChanged: 5 n\Zeh\t\0\model\sarge/default
+  0:10 Kill: 4 5 10: Mocinha killed Zeh by MOD_RAILGUN
+  0:20 Kill: 4 5 10: Mocinha killed Zeh by MOD_RAILGUN
+  0:25 Kill: 4 5 10: Mocinha killed Zeh by MOD_RAILGUN
+  1:00 ShutdownGame:
+"#;
+
+    fn parse(log: &str) -> Vec<Match> {
+        let mut matches = Vec::new();
+        process_events_matches(&mut matches, log).unwrap();
+        matches
+    }
+
+    /// Exports the report of a log and loads it back.
+    fn exported(log: &str) -> Report {
+        let mut matches = parse(log);
+        let mut ranking = Vec::new();
+        process_ranking(&mut matches, &mut ranking);
+        let json = serde_json::to_string(&Report::from_matches(matches, &ranking)).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_deserialize_matches() {
+        let matches = parse(MONDAY);
+        let json = serde_json::to_string(&matches[0]).unwrap();
+        let game: Match = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&game).unwrap(), json);
+        assert_eq!(game.data.scores.len(), 2);
+        assert_eq!(game.data.sessions[0].name, "Isgalamido");
+
+        let player: PlayerScore = serde_json::from_str(r#"{"name": "Zeh", "kills": -2}"#).unwrap();
+        assert_eq!((player.name.as_str(), player.kills), ("Zeh", -2));
+    }
+
+    #[test]
+    fn test_merge_reports() {
+        let merged = merge_reports(vec![
+            ("monday.json".to_string(), exported(MONDAY)),
+            ("tuesday.json".to_string(), exported(TUESDAY)),
+        ]);
+        assert_eq!(merged.skipped, 0);
+        let matches = merged.matches;
+        assert_eq!(
+            matches.iter().map(|mat| mat.id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(matches[1].data.source_file, "tuesday.json");
+        assert_eq!(matches[1].data.source_id, 1);
+        // The scoreboard logged by the server survives the export.
+        let scoreboard = final_scoreboard(&matches[0].data);
+        assert_eq!(
+            (scoreboard[0].name.as_str(), scoreboard[0].kills),
+            ("Isgalamido", 3)
+        );
+
+        let files = vec!["monday.json".to_string(), "tuesday.json".to_string()];
+        let report = sources::report_matches(&files, matches, &AliasMap::default());
+        let ranking: Vec<(&str, i32)> = report
+            .ranking
+            .iter()
+            .map(|player| (player.name.as_str(), player.kills))
+            .collect();
+        assert_eq!(ranking, vec![("Mocinha", 3), ("Isgalamido", 2), ("Zeh", 0)]);
+    }
+
+    #[test]
+    fn test_merge_overlapping_reports() {
+        // The same log exported twice, and a report of both days whose matches say where they were
+        // read from.
+        let mut both = exported(&format!("{}{}", MONDAY, TUESDAY));
+        for (idx, game) in both.matches.iter_mut().enumerate() {
+            game.source = Some(MatchSource {
+                file: "games.log".to_string(),
+                id: idx as i32 + 1,
+            });
+        }
+        let merged = merge_reports(vec![
+            ("monday.json".to_string(), exported(MONDAY)),
+            ("monday-again.json".to_string(), exported(MONDAY)),
+            ("both.json".to_string(), both),
+            ("tuesday.json".to_string(), exported(TUESDAY)),
+        ]);
+        assert_eq!(merged.skipped, 3);
+        let sources: Vec<(&str, i32)> = merged
+            .matches
+            .iter()
+            .map(|mat| (mat.data.source_file.as_str(), mat.data.source_id))
+            .collect();
+        assert_eq!(sources, vec![("monday.json", 1), ("games.log", 2)]);
+
+        // Identical matches of a single report are all kept.
+        let twice = merge_reports(vec![(
+            "twice.json".to_string(),
+            exported(&format!("{}{}", MONDAY, MONDAY)),
+        )]);
+        assert_eq!((twice.matches.len(), twice.skipped), (2, 0));
+    }
+
+    #[test]
+    fn test_load_reports() {
+        let monday = TempFile::new("monday.log", MONDAY);
+        let tuesday = TempFile::new("tuesday.log", TUESDAY);
+        let run = |args: &[&str]| {
+            let output = Command::new(env!("CARGO_BIN_EXE_quake_log_parse"))
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        };
+        let report = |log: &Path| run(&["report", "--format=json", log.to_str().unwrap()]);
+
+        // Merging the reports of two logs gives the report of both logs.
+        let reports = [
+            TempFile::new("monday.json", &report(&monday)),
+            TempFile::new("tuesday.json", &report(&tuesday)),
+        ];
+        let merged = run(&[
+            "merge",
+            "--format=json",
+            reports[0].to_str().unwrap(),
+            reports[1].to_str().unwrap(),
+        ]);
+        let both = run(&[
+            "report",
+            "--format=json",
+            monday.to_str().unwrap(),
+            tuesday.to_str().unwrap(),
+        ]);
+        assert_eq!(merged, both);
+
+        let legacy = TempFile::new(
+            "legacy.json",
+            &run(&["report", "--format=legacy-json", monday.to_str().unwrap()]),
+        );
+        assert!(matches!(Report::load(&legacy), Err(LogError::JsonError(_))));
+        let future = TempFile::new("future.json", r#"{"schema_version": 3}"#);
+        let Err(LogError::JsonError(message)) = Report::load(&future) else {
+            panic!("a report of another version was loaded");
+        };
+        assert!(message.contains("schema version 3"));
+        assert!(matches!(
+            Report::load(&env::temp_dir().join("quake_log_parse_missing.json")),
+            Err(LogError::ReadLogError(_))
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        thread,
+    };
+
+    use quake_log_parse::quake_log_parse::{
+        controller::{ApiServer, ApiState},
+        model::{
+            identity::AliasMap,
+            log_model::ParseOptions,
+            metrics::{Metrics, ServerLabels},
+        },
+    };
+
+    const LOG: &str = r#"  0:00 ------------------------------------------------------------
+  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  0:20 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT
+  0:25 ClientDisconnect: 3
+  1:00 ShutdownGame:
+  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  1:06 ClientConnect: 2
+  1:06 ClientUserinfoChanged: 2 n\\t\0\model\xian/default
+  1:10 Kill: 2 2 7: Isgalamido killed Isgalamido by MOD_ROCKET_SPLASH
+  1:15 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  1:16 ClientConnect: 4
+"#;
+
+    fn server(map: &str) -> ServerLabels {
+        ServerLabels {
+            hostname: "Code Miner Server".to_string(),
+            map: map.to_string(),
+        }
+    }
+
+    fn metrics() -> Metrics {
+        let mut metrics = Metrics::default();
+        metrics.record_log(LOG, ParseOptions::default());
+        metrics
+    }
+
+    #[test]
+    fn test_record_log() {
+        let metrics = metrics();
+        let (q3dm17, q3dm6) = (server("q3dm17"), server("q3dm6"));
+
+        assert_eq!(metrics.lines_processed[&ServerLabels::default()], 1);
+        assert_eq!(metrics.lines_processed[&q3dm17], 11);
+        assert_eq!(metrics.lines_processed[&q3dm6], 4);
+        assert_eq!(metrics.matches_started[&q3dm17], 2);
+        assert_eq!(metrics.matches_finished[&q3dm17], 1);
+        assert_eq!(metrics.matches_aborted[&q3dm6], 1);
+        assert!(!metrics.matches_aborted.contains_key(&q3dm17));
+        assert_eq!(metrics.player_connections[&q3dm17], 3);
+        // Only the match being played has clients connected.
+        assert_eq!(metrics.players_connected.len(), 1);
+        assert_eq!(metrics.players_connected[&q3dm17], 1);
+        assert_eq!(
+            metrics.kills[&(q3dm17.clone(), "MOD_RAILGUN".to_string())],
+            1
+        );
+        assert_eq!(metrics.kills.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_errors_by_kind() {
+        let metrics = metrics();
+        // The empty name is an error, and so is the kill of the player without a name.
+        assert_eq!(
+            metrics.parse_errors[&(server("q3dm6"), "ClientUserinfoChangedError")],
+            1
+        );
+        assert_eq!(metrics.parse_errors[&(server("q3dm6"), "KillError")], 1);
+        assert_eq!(metrics.parse_errors.len(), 2);
+    }
+
+    #[test]
+    fn test_serve_metrics() {
+        let server = ApiServer::bind(
+            "127.0.0.1:0",
+            ApiState::new(Vec::new(), &AliasMap::default()),
+        )
+        .unwrap();
+        let address = server.local_addr().unwrap();
+        *server.metrics().lock().unwrap() = metrics();
+        thread::spawn(move || server.run());
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(body.contains("# TYPE quake_kills_total counter\n"));
+        assert!(body.contains(
+            "quake_kills_total{hostname=\"Code Miner Server\",map=\"q3dm17\",means=\"MOD_RAILGUN\"} 1\n"
+        ));
+        assert!(body.contains(
+            "quake_parse_errors_total{hostname=\"Code Miner Server\",map=\"q3dm6\",kind=\"ClientUserinfoChangedError\"} 1\n"
+        ));
+        assert!(body.contains(
+            "quake_players_connected{hostname=\"Code Miner Server\",map=\"q3dm17\"} 1\n"
+        ));
+    }
+}
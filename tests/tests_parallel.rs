@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use quake_log_parse::quake_log_parse::model::{
+        log_model::{process_events_matches, Match},
+        parallel::{process_events_matches_parallel, split_at_matches},
+    };
+    use serde_json::{json, Value};
+
+    /// A match with sessions, renames, a chat line mentioning `InitGame:` and a scoreboard; the
+    /// second one is cut short without a `ShutdownGame:` line, so it ends when the next match starts.
+    const MATCHES: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:02 ClientBegin: 2
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  0:04 ClientBegin: 3
+  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  0:20 ClientUserinfoChanged: 2 n\Isga\t\0\model\xian/default
+  0:30 Kill: 1022 2 22: <world> killed Isga by MOD_TRIGGER_HURT
+  0:40 say: Isga: the next InitGame: will be on q3dm6
+  1:00 score: 1  ping: 4  client: 2 Isga
+  1:00 score: 0  ping: 4  client: 3 Zeh
+  1:00 ShutdownGame:
+  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  1:06 ClientConnect: 4
+  1:06 ClientUserinfoChanged: 4 n\Mal\t\0\model\sarge
+  1:07 ClientBegin: 4
+  1:10 Kill: 4 4 7: Mal killed Mal by MOD_ROCKET_SPLASH
+  1:20 ClientDisconnect: 4
+"#;
+
+    fn log(copies: usize) -> String {
+        format!(
+            "  0:00 ------------------------------------------------------------\n{}",
+            MATCHES.repeat(copies)
+        )
+    }
+
+    /// Serializes matches through `serde_json::Value`, whose maps do not depend on hashing order,
+    /// sorting the set of players the same way.
+    fn to_value(matches: &[Match]) -> Value {
+        matches
+            .iter()
+            .map(|mat| {
+                let mut players: Vec<&String> = mat.data.players.iter().collect();
+                players.sort();
+                let mut value = json!({ "id": mat.id, "data": mat.data });
+                value["data"]["players"] = json!(players);
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_split_at_matches() {
+        let content = log(10);
+        for parts in 1..=25 {
+            let chunks = split_at_matches(&content, parts);
+            assert!(!chunks.is_empty() && chunks.len() <= parts.min(21));
+            assert_eq!(chunks.concat(), content);
+            for chunk in &chunks[1..] {
+                assert!(chunk.lines().next().unwrap().contains("InitGame:"));
+            }
+        }
+        assert_eq!(split_at_matches(MATCHES, 1), vec![MATCHES]);
+        assert!(split_at_matches("", 4).is_empty());
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let content = log(10);
+        let mut sequential = Vec::new();
+        process_events_matches(&mut sequential, &content).unwrap();
+        assert_eq!(sequential.len(), 20);
+
+        for threads in [1, 2, 3, 7, 16, 40] {
+            let mut parallel = Vec::new();
+            process_events_matches_parallel(&mut parallel, &content, &Default::default(), threads)
+                .unwrap();
+            assert_eq!(
+                to_value(&parallel),
+                to_value(&sequential),
+                "{} threads",
+                threads
+            );
+        }
+    }
+
+    #[test]
+    fn test_parallel_error_matches_sequential() {
+        let content = log(6) + "  9:00 Kill: 2 x 10: bad line\n" + &MATCHES.repeat(4);
+
+        let mut sequential = Vec::new();
+        let sequential_err = process_events_matches(&mut sequential, &content).unwrap_err();
+        let mut parallel = Vec::new();
+        let parallel_err =
+            process_events_matches_parallel(&mut parallel, &content, &Default::default(), 4)
+                .unwrap_err();
+
+        assert_eq!(
+            format!("{:?}", parallel_err),
+            format!("{:?}", sequential_err)
+        );
+        assert_eq!(to_value(&parallel), to_value(&sequential));
+    }
+}
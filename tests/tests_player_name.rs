@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use quake_log_parse::quake_log_parse::model::{
+        log_model::{process_events_matches, process_kill_line, MatchData},
+        player_name::{strip_colors, PlayerName, QuakeColor},
+    };
+
+    #[test]
+    fn test_strip_colors() {
+        assert_eq!(strip_colors("^1Red^7Player"), "RedPlayer");
+        assert_eq!(strip_colors("Plain"), "Plain");
+        assert_eq!(strip_colors("a^^1b^"), "a^b^");
+        assert_eq!(strip_colors("^x^9Odd"), "Odd");
+    }
+
+    #[test]
+    fn test_player_name() {
+        let name = PlayerName::new("^1Red^7Player");
+        assert_eq!(name.clean, "RedPlayer");
+        assert_eq!(name.raw, "^1Red^7Player");
+        assert_eq!(
+            name.segments(),
+            vec![
+                (Some(QuakeColor::Red), "Red".to_string()),
+                (Some(QuakeColor::White), "Player".to_string()),
+            ]
+        );
+        assert_eq!(name.to_ansi(), "\x1b[31mRed\x1b[37mPlayer\x1b[0m");
+        assert_eq!(
+            name.to_html(),
+            r#"<span style="color: #ff0000">Red</span><span style="color: #ffffff">Player</span>"#
+        );
+
+        assert_eq!(PlayerName::new("<b>").to_html(), "&lt;b&gt;");
+        assert_eq!(PlayerName::new("Plain").to_ansi(), "Plain");
+        assert_eq!(PlayerName::new("^7").clean, "^7");
+    }
+
+    #[test]
+    fn test_colored_names_are_one_player() {
+        let log = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientUserinfoChanged: 2 n\^1Isga^7lamido\t\0\model\xian/default
+  0:01 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: ^1Isga^7lamido killed Zeh by MOD_RAILGUN
+  0:20 ClientUserinfoChanged: 2 n\^4Isgalamido\t\0\model\xian/default
+  0:30 Kill: 2 3 10: ^4Isgalamido killed Zeh by MOD_RAILGUN
+  0:40 score: 2  ping: 4  client: 2 ^4Isgalamido"#;
+        let mut matches = Vec::new();
+        assert!(process_events_matches(&mut matches, log).is_ok());
+        let game_data = &matches[0].data;
+
+        assert_eq!(game_data.players.len(), 2);
+        assert_eq!(game_data.kills["Isgalamido"], 2);
+        assert_eq!(game_data.sessions[0].names.len(), 1);
+        assert_eq!(game_data.sessions[0].raw_name, "^4Isgalamido");
+        assert_eq!(game_data.scores[0].name, "Isgalamido");
+        assert_eq!(game_data.scores[0].raw_name, "^4Isgalamido");
+
+        let mut game = MatchData::default();
+        game.players.insert("Isgalamido".to_string());
+        let line = "21:42 Kill: 1022 2 22: <world> killed ^1Isga^7lamido by MOD_TRIGGER_HURT";
+        assert!(process_kill_line(line, &mut game).is_ok());
+        assert_eq!(game.kills.get("Isgalamido"), Some(&-1));
+    }
+}
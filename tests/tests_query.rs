@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod tests {
+    use quake_log_parse::quake_log_parse::model::{
+        error::LogError,
+        log_model::ParseOptions,
+        query::{Query, QueryResult, QueryValue},
+        session::NamePolicy,
+    };
+
+    const LOG: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:01 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  1:00 Item: 3 weapon_railgun
+  2:00 Kill: 3 2 10: Zeh killed Isgalamido by MOD_RAILGUN
+  6:00 Kill: 3 2 10: Zeh killed Isgalamido by MOD_RAILGUN
+  6:30 say: Zeh: gg, Kill: 3 2 10 was lucky
+  7:00 Kill: 2 3 6: Isgalamido killed Zeh by MOD_ROCKET
+  8:00 ShutdownGame:
+  8:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  8:06 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  9:00 Kill: 1022 3 22: <world> killed Zeh by MOD_TRIGGER_HURT
+ 14:05 Kill: 3 3 10: Zeh killed Zeh by MOD_RAILGUN
+ 14:06 sayteam: Zeh: oops
+"#;
+
+    fn run(query: &str) -> QueryResult {
+        Query::parse(query)
+            .unwrap()
+            .run([LOG], &ParseOptions::default())
+    }
+
+    fn number(value: i64) -> QueryValue {
+        QueryValue::Number(value)
+    }
+
+    fn text(value: &str) -> QueryValue {
+        QueryValue::Text(value.to_string())
+    }
+
+    #[test]
+    fn test_filter_and_count() {
+        let result = run(
+            "kills where means = MOD_RAILGUN and killer = Zeh and map = q3dm17 and minute >= 5 count",
+        );
+        assert_eq!(result.columns, vec!["count"]);
+        assert_eq!(result.rows, vec![vec![number(1)]]);
+
+        let result =
+            run(r#"KILLS WHERE (killer = "<world>" OR victim ~ isga) AND NOT map = q3dm17"#);
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0][0], number(2));
+        assert_eq!(result.rows[0][4], number(55));
+        assert_eq!(result.rows[0][6], text("<world>"));
+    }
+
+    #[test]
+    fn test_group_order_and_limit() {
+        let result = run("kills group by killer, means order by count desc limit 2");
+        assert_eq!(result.columns, vec!["killer", "means", "count"]);
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![text("Zeh"), text("MOD_RAILGUN"), number(3)],
+                vec![text("<world>"), text("MOD_TRIGGER_HURT"), number(1)],
+            ]
+        );
+
+        let result = run("kills group by match count sum(minute) order by match desc");
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![number(2), number(2), number(6)],
+                vec![number(1), number(3), number(15)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_items_and_chat() {
+        let result = run("items");
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0][6..], [text("Zeh"), text("weapon_railgun")]);
+
+        // A message quoting a kill is not a kill.
+        let result = run("chat where team = false");
+        assert_eq!(
+            result.rows[0][6..],
+            [
+                text("Zeh"),
+                text("gg, Kill: 3 2 10 was lucky"),
+                text("false")
+            ]
+        );
+        assert_eq!(run("kills count").rows, vec![vec![number(5)]]);
+    }
+
+    #[test]
+    fn test_invalid_queries() {
+        let error = |query: &str| match Query::parse(query) {
+            Err(LogError::QueryError(message)) => message,
+            other => panic!("{:?} parsed as {:?}", query, other),
+        };
+        assert_eq!(error("deaths"), "A query starts with kills, items or chat");
+        assert_eq!(error("kills where weapon = x"), "Unknown field: weapon");
+        assert_eq!(
+            error("kills where killer"),
+            "Expected an operator after killer"
+        );
+        assert_eq!(error("kills sum(killer)"), "Cannot sum killer");
+        assert_eq!(error("kills order by count"), "Cannot order by count");
+        assert_eq!(error("kills limit ten"), "Expected a number after limit");
+        assert_eq!(
+            error(r#"chat where message = "gg"#),
+            "Unterminated quoted value"
+        );
+    }
+
+    #[test]
+    fn test_renamed_players_follow_the_name_policy() {
+        let log = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:01 ClientConnect: 3
+  0:01 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  1:00 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  1:10 ClientUserinfoChanged: 2 n\Isga\t\0\model\xian/default
+  1:20 Item: 2 weapon_railgun
+  1:30 Kill: 3 2 6: Zeh killed Isga by MOD_ROCKET
+  9:00 ShutdownGame:
+"#;
+        let players = |name_policy| {
+            let result = Query::parse("kills")
+                .unwrap()
+                .run([log], &ParseOptions { name_policy });
+            let column = |name| result.columns.iter().position(|c| c == name).unwrap();
+            let (killer, victim) = (column("killer"), column("victim"));
+            result
+                .rows
+                .iter()
+                .map(|row| (row[killer].to_string(), row[victim].to_string()))
+                .collect::<Vec<_>>()
+        };
+        let pair = |killer: &str, victim: &str| (killer.to_string(), victim.to_string());
+
+        assert_eq!(
+            players(NamePolicy::Last),
+            vec![pair("Isga", "Zeh"), pair("Zeh", "Isga")]
+        );
+        assert_eq!(
+            players(NamePolicy::First),
+            vec![pair("Isgalamido", "Zeh"), pair("Zeh", "Isgalamido")]
+        );
+        assert_eq!(
+            players(NamePolicy::MostUsed),
+            vec![pair("Isga", "Zeh"), pair("Zeh", "Isga")]
+        );
+
+        let items = Query::parse("items").unwrap().run(
+            [log],
+            &ParseOptions {
+                name_policy: NamePolicy::First,
+            },
+        );
+        let player = items.columns.iter().position(|c| c == "player").unwrap();
+        assert_eq!(items.rows[0][player].to_string(), "Isgalamido");
+    }
+}
@@ -0,0 +1,134 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use quake_log_parse::quake_log_parse::model::{
+        error::LogError,
+        identity::AliasMap,
+        log_model::ParseOptions,
+        repl::{Completion, ExportFormat, RankingOrder, ReplCommand, Workspace},
+    };
+
+    use crate::common::TempFile;
+
+    const LOG: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Dono da Bola\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Dono da Bola by MOD_RAILGUN
+  0:20 Kill: 2 3 10: Isgalamido killed Dono da Bola by MOD_RAILGUN
+  5:00 ShutdownGame:
+  5:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  5:06 ClientConnect: 3
+  5:06 ClientUserinfoChanged: 3 n\Dono da Bola\t\0\model\sarge/default
+  5:10 Kill: 1022 3 22: <world> killed Dono da Bola by MOD_TRIGGER_HURT
+ 20:00 ShutdownGame:
+"#;
+
+    fn workspace(name: &str) -> Workspace {
+        let path = TempFile::new(&format!("{}.log", name), LOG);
+        let mut workspace = Workspace::new(AliasMap::default(), ParseOptions::default());
+        workspace.load(&[path.display().to_string()]).unwrap();
+        workspace
+    }
+
+    fn parse(line: &str) -> Result<Option<ReplCommand>, LogError> {
+        ReplCommand::parse(line)
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse("  ").unwrap(), None);
+        assert_eq!(
+            parse("player Dono da Bola").unwrap(),
+            Some(ReplCommand::Player("Dono da Bola".to_string()))
+        );
+        assert_eq!(
+            parse("ranking by playtime").unwrap(),
+            Some(ReplCommand::Ranking(RankingOrder::Playtime))
+        );
+        assert_eq!(
+            parse("export csv out/players.csv").unwrap(),
+            Some(ReplCommand::Export(
+                ExportFormat::Csv,
+                "out/players.csv".to_string()
+            ))
+        );
+        assert_eq!(parse("match 2").unwrap(), Some(ReplCommand::Match(2)));
+
+        for line in [
+            "match two",
+            "ranking by deaths",
+            "export xml a",
+            "load",
+            "frag",
+        ] {
+            assert!(
+                matches!(parse(line), Err(LogError::CommandError(_))),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_query_workspace() {
+        let workspace = workspace("query");
+        assert_eq!(workspace.matches.len(), 2);
+        assert_eq!(workspace.find_match(2).unwrap().data.total_kills, 1);
+
+        let ids = |name: Option<&str>| -> Vec<i32> {
+            workspace
+                .matches_with(name)
+                .iter()
+                .map(|mat| mat.id)
+                .collect()
+        };
+        assert_eq!(ids(None), vec![1, 2]);
+        assert_eq!(ids(Some("MOD_TRIGGER_HURT")), vec![2]);
+        assert_eq!(ids(Some("Isgalamido")), vec![1]);
+
+        let names = |order| -> Vec<String> {
+            workspace
+                .ranking(order)
+                .into_iter()
+                .map(|player| player.name)
+                .collect()
+        };
+        assert_eq!(
+            names(RankingOrder::Kills),
+            vec!["Isgalamido", "Dono da Bola"]
+        );
+        assert_eq!(
+            names(RankingOrder::Playtime),
+            vec!["Dono da Bola", "Isgalamido"]
+        );
+
+        let player = workspace.player("dono da bola").unwrap();
+        assert_eq!((player.kills, player.matches), (-1, vec![1, 2]));
+        assert!(workspace.player("Zeh").is_none());
+    }
+
+    #[test]
+    fn test_complete_names() {
+        let completions = workspace("complete").completions();
+        assert_eq!(completions.players, vec!["Dono da Bola", "Isgalamido"]);
+
+        let complete = |line: &str| completions.complete(line, line.len());
+        assert_eq!(
+            complete("ma"),
+            Completion::Words(0, vec!["matches".to_string(), "match".to_string()])
+        );
+        assert_eq!(
+            complete("player do"),
+            Completion::Words(7, vec!["Dono da Bola".to_string()])
+        );
+        assert_eq!(
+            complete("matches MOD_T"),
+            Completion::Words(8, vec!["MOD_TRIGGER_HURT".to_string()])
+        );
+        assert_eq!(complete("load /var/lo"), Completion::Path);
+        assert_eq!(complete("export json /tm"), Completion::Path);
+    }
+}
@@ -0,0 +1,214 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use quake_log_parse::quake_log_parse::model::{
+        identity::AliasMap,
+        log_model::{process_events_matches, process_ranking, Match},
+        report::{RankedPlayer, Report, REPORT_SCHEMA, SCHEMA_VERSION},
+        sources,
+    };
+    use serde_json::Value;
+
+    use crate::common::TempFile;
+
+    const LOG: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Zeh\t\0\model\xian/default
+  0:02 ClientConnect: 3
+  0:02 ClientUserinfoChanged: 3 n\Mal\t\0\model\sarge/default
+  0:03 ClientConnect: 4
+  0:03 ClientUserinfoChanged: 4 n\Isgalamido\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Zeh killed Mal by MOD_RAILGUN
+  0:20 Kill: 4 2 7: Isgalamido killed Zeh by MOD_ROCKET_SPLASH
+  0:30 Kill: 1022 4 22: <world> killed Isgalamido by MOD_TRIGGER_HURT
+  0:40 Kill: 3 4 1: Mal killed Isgalamido by MOD_SHOTGUN
+  1:00 ShutdownGame:
+"#;
+
+    fn parse() -> Vec<Match> {
+        let mut matches = Vec::new();
+        process_events_matches(&mut matches, LOG).unwrap();
+        matches
+    }
+
+    fn run(args: &[&str]) -> (i32, String) {
+        let output = Command::new(env!("CARGO_BIN_EXE_quake_log_parse"))
+            .args(args)
+            .output()
+            .unwrap();
+        (
+            output.status.code().unwrap_or(-1),
+            String::from_utf8(output.stdout).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_stable_order() {
+        let mut matches = parse();
+        let mut ranking = Vec::new();
+        process_ranking(&mut matches, &mut ranking);
+        let report = Report::from_matches(matches, &ranking);
+
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+        // Mal and Zeh tie, so they are ranked by name.
+        assert_eq!(
+            report.ranking,
+            vec![
+                RankedPlayer {
+                    rank: 1,
+                    name: "Mal".to_string(),
+                    kills: 1
+                },
+                RankedPlayer {
+                    rank: 2,
+                    name: "Zeh".to_string(),
+                    kills: 1
+                },
+                RankedPlayer {
+                    rank: 3,
+                    name: "Isgalamido".to_string(),
+                    kills: 0
+                },
+            ]
+        );
+
+        let game = &report.matches[0];
+        assert_eq!(game.players, vec!["Isgalamido", "Mal", "Zeh"]);
+        assert_eq!(
+            game.death_causes.keys().collect::<Vec<_>>(),
+            vec![
+                "MOD_RAILGUN",
+                "MOD_ROCKET_SPLASH",
+                "MOD_SHOTGUN",
+                "MOD_TRIGGER_HURT"
+            ]
+        );
+        assert!(game.source.is_none());
+    }
+
+    #[test]
+    fn test_json_matches_schema() {
+        let schema: Value = serde_json::from_str(REPORT_SCHEMA).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        let path = TempFile::new("schema.log", LOG);
+        let (code, output) = run(&["report", "--format=json", path.to_str().unwrap()]);
+        assert_eq!(code, 0);
+        let report: Value = serde_json::from_str(&output).unwrap();
+        assert!(validator.is_valid(&report), "{}", output);
+        assert_eq!(report["schema_version"], 2);
+        assert_eq!(report["files"][0]["matches"], serde_json::json!([1]));
+        assert_eq!(report["matches"][0]["source"]["match"], 1);
+        assert_eq!(
+            run(&["report", "--format=json", path.to_str().unwrap()]).1,
+            output
+        );
+
+        let checkpoint = path.sibling("report.ckpt");
+        let (code, output) = run(&[
+            "report",
+            "--format=json",
+            &format!("--checkpoint={}", checkpoint.display()),
+            path.to_str().unwrap(),
+        ]);
+        assert_eq!(code, 0);
+        let report: Value = serde_json::from_str(&output).unwrap();
+        assert!(validator.is_valid(&report), "{}", output);
+        assert_eq!(report["checkpoint"]["resumed_from"], "start");
+
+        let (code, output) = run(&["schema"]);
+        assert_eq!((code, output.as_str()), (0, REPORT_SCHEMA));
+    }
+
+    #[test]
+    fn test_legacy_json() {
+        let path = TempFile::new("legacy.log", LOG);
+        let (code, output) = run(&["report", "--format=legacy-json", path.to_str().unwrap()]);
+        assert_eq!(code, 0);
+        assert!(!output.contains("schema_version"));
+
+        let documents: Vec<Value> = serde_json::Deserializer::from_str(&output)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(documents.len(), 3);
+        assert_eq!(documents[0]["matches"][0]["game_1"]["total_kills"], 4);
+        assert_eq!(
+            documents[1]["Player Ranking"],
+            serde_json::json!([{ "Mal": 1 }, { "Zeh": 1 }, { "Isgalamido": 0 }])
+        );
+        assert!(documents[2]["Player Playtime"].is_array());
+    }
+
+    #[test]
+    fn test_files_of_logs_report() {
+        let files = vec!["a.log".to_string(), "b.log".to_string()];
+        let mut matches = parse();
+        matches.extend(parse());
+        for (idx, (mat, file)) in matches.iter_mut().zip(&files).enumerate() {
+            mat.id = idx as i32 + 1;
+            mat.data.source_file = file.clone();
+            mat.data.source_id = 1;
+        }
+
+        let report = Report::from_logs_report(sources::report_matches(
+            &files,
+            matches,
+            &AliasMap::default(),
+        ));
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.files[1].file, "b.log");
+        assert_eq!(report.files[1].matches, vec![2]);
+        assert_eq!(report.files[1].ranking.len(), 3);
+        assert_eq!(
+            report.matches.iter().map(|mat| mat.id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(report.ranking[0].kills, 2);
+    }
+
+    #[test]
+    fn test_name_policy_option() {
+        let renamed = LOG.replace(
+            "  0:20 Kill",
+            "  0:15 ClientUserinfoChanged: 2 n\\Zeh_BR\\t\\0\\model\\xian/default\n  0:20 Kill",
+        );
+        let log = TempFile::new("name_policy.log", &renamed);
+        let path = log.to_str().unwrap();
+
+        let players = |args: &[&str]| {
+            let (code, output) = run(args);
+            assert_eq!(code, 0);
+            let report: Value = serde_json::from_str(&output).unwrap();
+            report["matches"][0]["players"].clone()
+        };
+        assert_eq!(
+            players(&["report", "--format=json", path]),
+            serde_json::json!(["Isgalamido", "Mal", "Zeh_BR"])
+        );
+        assert_eq!(
+            players(&["report", "--format=json", "--name-policy=first", path]),
+            serde_json::json!(["Isgalamido", "Mal", "Zeh"])
+        );
+        assert_eq!(run(&["report", "--name-policy=longest", path]).0, 2);
+
+        let database = log.sibling("name_policy.db");
+        let database = database.to_str().unwrap();
+        let db_flag = format!("--db={}", database);
+        assert_eq!(run(&["import", &db_flag, "--name-policy=first", path]).0, 0);
+        assert_eq!(
+            players(&["report", "--format=json", &db_flag]),
+            serde_json::json!(["Isgalamido", "Mal", "Zeh"])
+        );
+
+        let (code, output) = run(&["query", "--name-policy=first", "kills", path]);
+        assert_eq!(code, 0);
+        assert!(output.contains("Zeh") && !output.contains("Zeh_BR"));
+        let (code, output) = run(&["query", "kills", path]);
+        assert_eq!(code, 0);
+        assert!(output.contains("Zeh_BR"));
+    }
+}
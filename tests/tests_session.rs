@@ -0,0 +1,203 @@
+#[cfg(test)]
+mod tests {
+    use quake_log_parse::quake_log_parse::model::{
+        log_model::{
+            parse_timestamp, process_events_matches, process_events_matches_with_options,
+            reconcile_scores, MatchData, ParseOptions,
+        },
+        session::{playtime_by_player, process_disconnect_line, NamePolicy},
+    };
+
+    const LOG_DATA: &str = r#"  0:00 InitGame: \sv_floodProtect\1\sv_maxPing\0\sv_minPing\0\sv_maxRate\10000\sv_minRate\0\sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:02 ClientConnect: 2
+  0:02 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default\hmodel\xian/default
+  0:03 ClientBegin: 2
+  0:04 ClientConnect: 3
+  0:04 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default\hmodel\sarge/default
+  0:05 ClientBegin: 3
+  1:10 Kill: 3 2 10: Zeh killed Isgalamido by MOD_RAILGUN
+  1:20 Kill: 3 2 10: Zeh killed Isgalamido by MOD_RAILGUN
+  1:30 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT
+  1:40 ClientDisconnect: 2
+  2:00 ClientConnect: 4
+  2:00 ClientUserinfoChanged: 4 n\Mocinha\t\0\model\sarge\hmodel\sarge
+  2:01 ClientBegin: 4
+  2:30 ClientDisconnect: 4
+  3:05 ClientConnect: 2
+  3:05 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default\hmodel\xian/default
+  3:06 ClientBegin: 2
+  4:00 ShutdownGame:"#;
+
+    const RENAME_LOG: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:01 ClientConnect: 3
+  0:01 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  0:20 ClientUserinfoChanged: 2 n\Isga\t\0\model\xian/default
+  0:30 Kill: 2 3 10: Isga killed Zeh by MOD_RAILGUN
+  0:40 Kill: 1022 2 22: <world> killed Isga by MOD_TRIGGER_HURT
+  1:00 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  1:05 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  1:10 score: 2  ping: 4  client: 2 Isgalamido
+  1:10 score: 0  ping: 4  client: 3 Zeh
+  1:10 ShutdownGame:"#;
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("  0:02 ClientConnect: 2"), Some(2));
+        assert_eq!(parse_timestamp("981:27 ClientBegin: 2"), Some(58887));
+        assert_eq!(parse_timestamp("ClientBegin: 2"), None);
+    }
+
+    #[test]
+    fn test_sessions() {
+        let mut matches = Vec::new();
+        assert!(process_events_matches(&mut matches, LOG_DATA).is_ok());
+        let game_data = &matches[0].data;
+        assert_eq!(game_data.started_at, 0);
+        assert_eq!(game_data.ended_at, 240);
+        assert_eq!(game_data.sessions.len(), 4);
+
+        let first = &game_data.sessions[0];
+        assert_eq!(first.name, "Isgalamido");
+        assert_eq!(first.began_at, Some(3));
+        assert_eq!(first.disconnected_at, Some(100));
+        assert!(!first.joined_late);
+        assert!(first.left_early);
+        assert!(first.rage_quit);
+
+        let mocinha = &game_data.sessions[2];
+        assert_eq!(mocinha.name, "Mocinha");
+        assert!(mocinha.joined_late);
+        assert!(mocinha.left_early);
+        assert!(!mocinha.rage_quit);
+
+        let reconnected = &game_data.sessions[3];
+        assert_eq!(reconnected.client_id, 2);
+        assert!(reconnected.joined_late);
+        assert_eq!(reconnected.disconnected_at, None);
+        assert!(!reconnected.left_early);
+    }
+
+    #[test]
+    fn test_playtime_by_player() {
+        let mut matches = Vec::new();
+        assert!(process_events_matches(&mut matches, LOG_DATA).is_ok());
+        let playtime = playtime_by_player(&matches);
+
+        assert_eq!(playtime.len(), 3);
+        assert_eq!(playtime[0].name, "Zeh");
+        assert_eq!(playtime[0].seconds, 235);
+        assert_eq!(playtime[1].name, "Isgalamido");
+        assert_eq!(playtime[1].seconds, 97 + 54);
+        assert_eq!(playtime[2].name, "Mocinha");
+        assert_eq!(playtime[2].seconds, 29);
+    }
+
+    #[test]
+    fn test_disconnect_without_session() {
+        let mut game = MatchData::default();
+        assert!(process_disconnect_line("  1:40 ClientDisconnect: 2", &mut game).is_ok());
+        assert!(game.sessions.is_empty());
+        assert!(process_disconnect_line("  1:40 ClientDisconnect:", &mut game).is_err());
+
+        // A log that starts mid-match, after the client connected.
+        let log = "  0:00 InitGame: \\mapname\\q3dm17\n  0:10 ClientDisconnect: 5\n  1:00 ShutdownGame:\n";
+        let mut matches = Vec::new();
+        assert!(process_events_matches(&mut matches, log).is_ok());
+        assert!(matches[0].data.sessions.is_empty());
+    }
+
+    #[test]
+    fn test_renames_keep_stats_together() {
+        let mut matches = Vec::new();
+        assert!(process_events_matches(&mut matches, RENAME_LOG).is_ok());
+        let game_data = &matches[0].data;
+
+        assert_eq!(game_data.players.len(), 2);
+        assert!(!game_data.players.contains("Isga"));
+        assert_eq!(game_data.kills["Isgalamido"], 2);
+        assert_eq!(game_data.kills.get("Isga"), None);
+        assert!(reconcile_scores(game_data).is_empty());
+
+        let names: Vec<_> = game_data.sessions[0]
+            .names
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Isgalamido", "Isga", "Isgalamido"]);
+    }
+
+    #[test]
+    fn test_name_policy() {
+        let log = RENAME_LOG.replace(
+            "1:00 ClientUserinfoChanged: 2 n\\Isgalamido",
+            "1:00 ClientUserinfoChanged: 2 n\\Isga_BR",
+        );
+        let cases = [
+            (NamePolicy::First, "Isgalamido"),
+            (NamePolicy::Last, "Isga_BR"),
+            (NamePolicy::MostUsed, "Isga"),
+        ];
+        for (name_policy, expected) in cases {
+            let mut matches = Vec::new();
+            let options = ParseOptions { name_policy };
+            assert!(process_events_matches_with_options(&mut matches, &log, &options).is_ok());
+            let game_data = &matches[0].data;
+            assert_eq!(game_data.sessions[0].name, expected);
+            assert_eq!(game_data.kills[expected], 2);
+            assert_eq!(game_data.players.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_match_cut_short_ends_at_its_last_line() {
+        let log = concat!(
+            "  0:00 InitGame: \\sv_hostname\\Code Miner Server\\g_gametype\\0\\mapname\\q3dm17\n",
+            "  0:01 ClientConnect: 2\n",
+            "  0:01 ClientUserinfoChanged: 2 n\\Isgalamido\\t\\0\\model\\xian/default\n",
+            "  0:02 ClientBegin: 2\n",
+            "  1:00 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT\n",
+            "  5:00 InitGame: \\sv_hostname\\Code Miner Server\\g_gametype\\0\\mapname\\q3dm6\n",
+            "  5:30 ShutdownGame:\n"
+        );
+        let mut matches = Vec::new();
+        process_events_matches(&mut matches, log).unwrap();
+        assert_eq!(matches[0].data.ended_at, 60);
+        let playtime = playtime_by_player(&matches[..1]);
+        assert_eq!(playtime[0].name, "Isgalamido");
+        assert_eq!(playtime[0].seconds, 58);
+        assert_eq!(matches[1].data.started_at, 300);
+    }
+
+    #[test]
+    fn test_self_kills_take_a_point_off_the_score() {
+        let log = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:01 ClientConnect: 3
+  0:01 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  0:20 Kill: 2 2 7: Isgalamido killed Isgalamido by MOD_ROCKET_SPLASH
+  1:00 score: 0  ping: 4  client: 2 Isgalamido
+  1:00 score: 0  ping: 4  client: 3 Zeh
+  1:00 ShutdownGame:"#;
+        let mut matches = Vec::new();
+        process_events_matches(&mut matches, log).unwrap();
+        let game_data = &matches[0].data;
+
+        assert_eq!(game_data.kills["Isgalamido"], 2);
+        assert_eq!(game_data.self_kills["Isgalamido"], 1);
+        assert!(reconcile_scores(game_data).is_empty());
+
+        // The same kills without client ids are counted by name.
+        let log = log
+            .replace("Kill: 2 3 10:", "Kill: 10:")
+            .replace("Kill: 2 2 7:", "Kill: 7:");
+        let mut matches = Vec::new();
+        process_events_matches(&mut matches, &log).unwrap();
+        assert_eq!(matches[0].data.self_kills["Isgalamido"], 1);
+        assert!(reconcile_scores(&matches[0].data).is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        fs::{create_dir_all, write},
+        path::PathBuf,
+    };
+
+    use quake_log_parse::quake_log_parse::model::{
+        identity::AliasMap,
+        log_model::ParseOptions,
+        sources::{process_files, process_logs, resolve_inputs},
+    };
+
+    const LOG_DATA: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:01 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  1:00 ShutdownGame:
+  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  1:06 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  1:06 ClientUserinfoChanged: 4 n\Mal\t\0\model\sarge
+  1:10 Kill: 3 4 10: Zeh killed Mal by MOD_RAILGUN
+  2:00 ShutdownGame:"#;
+
+    /// Creates a directory with `day1.log`, `day2.log` and a hidden file, unique to the test.
+    fn log_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("quake_log_parse_{}_{}", name, std::process::id()));
+        create_dir_all(&dir).unwrap();
+        write(dir.join("day1.log"), LOG_DATA).unwrap();
+        write(
+            dir.join("day2.log"),
+            LOG_DATA.replace("Isgalamido", "Mocinha"),
+        )
+        .unwrap();
+        write(dir.join(".hidden"), "not a log").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_resolve_inputs() {
+        let dir = log_dir("resolve");
+        let day1 = dir.join("day1.log").display().to_string();
+
+        let paths = resolve_inputs(&[dir.display().to_string(), day1.clone()]).unwrap();
+        assert_eq!(paths, vec![dir.join("day1.log"), dir.join("day2.log")]);
+
+        let pattern = dir.join("day*.log").display().to_string();
+        assert_eq!(resolve_inputs(&[pattern]).unwrap().len(), 2);
+
+        let missing = dir.join("missing.log").display().to_string();
+        assert!(resolve_inputs(&[missing]).is_err());
+        let no_match = dir.join("*.gz").display().to_string();
+        assert!(resolve_inputs(&[no_match]).is_err());
+    }
+
+    #[test]
+    fn test_process_files_unique_ids() {
+        let dir = log_dir("ids");
+        let paths = vec![dir.join("day1.log"), dir.join("day2.log")];
+
+        let matches = process_files(&paths, &ParseOptions::default(), false).unwrap();
+        let ids: Vec<i32> = matches.iter().map(|mat| mat.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+        assert_eq!(matches[2].data.source_file, paths[1].display().to_string());
+        assert_eq!(matches[2].data.source_id, 1);
+        assert_eq!(matches[3].data.source_id, 2);
+
+        let parallel = process_files(&paths, &ParseOptions::default(), true).unwrap();
+        let parallel_ids: Vec<(i32, &str, i32)> = parallel
+            .iter()
+            .map(|mat| (mat.id, mat.data.source_file.as_str(), mat.data.source_id))
+            .collect();
+        let sequential_ids: Vec<(i32, &str, i32)> = matches
+            .iter()
+            .map(|mat| (mat.id, mat.data.source_file.as_str(), mat.data.source_id))
+            .collect();
+        assert_eq!(parallel_ids, sequential_ids);
+    }
+
+    #[test]
+    fn test_process_logs() {
+        let dir = log_dir("report");
+        let paths = vec![dir.join("day1.log"), dir.join("day2.log")];
+
+        let report =
+            process_logs(&paths, &ParseOptions::default(), &AliasMap::default(), true).unwrap();
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.files[0].matches.len(), 2);
+        assert_eq!(report.files[1].matches[0].id, 3);
+        assert_eq!(report.files[0].ranking[0].name, "Isgalamido");
+        assert_eq!(report.files[1].ranking[0].name, "Mocinha");
+
+        assert_eq!(report.ranking[0].name, "Zeh");
+        assert_eq!(report.ranking[0].kills, 2);
+        assert_eq!(report.ranking.len(), 4);
+    }
+}
@@ -0,0 +1,158 @@
+#[cfg(test)]
+mod tests {
+    use quake_log_parse::quake_log_parse::model::{
+        log_model::{process_events_matches, Match, ParseOptions},
+        storage::{ImportSummary, Storage},
+    };
+    use serde_json::{json, Value};
+
+    const LOG: &str = r#"  0:00 ------------------------------------------------------------
+  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:02 ClientBegin: 2
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Zeh\t\0\model\sarge/default
+  0:04 ClientBegin: 3
+  0:10 Kill: 2 3 10: Isgalamido killed Zeh by MOD_RAILGUN
+  0:20 ClientUserinfoChanged: 2 n\Isga\t\0\model\xian/default
+  0:30 Kill: 1022 2 22: <world> killed Isga by MOD_TRIGGER_HURT
+  1:00 score: 1  ping: 4  client: 2 Isga
+  1:00 score: 0  ping: 4  client: 3 Zeh
+  1:00 ShutdownGame:
+  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  1:06 ClientConnect: 4
+  1:06 ClientUserinfoChanged: 4 n\Mal\t\0\model\sarge
+  1:07 ClientBegin: 4
+  1:10 Kill: 4 4 7: Mal killed Mal by MOD_ROCKET_SPLASH
+  1:20 ClientDisconnect: 4
+"#;
+
+    /// A match played after the ones of `LOG`, as if the log had grown since it was imported.
+    const NEXT_MATCH: &str = r#"  2:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  2:01 ClientConnect: 2
+  2:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  2:02 ClientBegin: 2
+  2:10 Kill: 1022 2 22: <world> killed Isgalamido by MOD_FALLING
+  2:30 ShutdownGame:
+"#;
+
+    /// Serializes matches through `serde_json::Value`, sorting the set of players.
+    fn to_value(matches: &[Match]) -> Value {
+        matches
+            .iter()
+            .map(|mat| {
+                let mut players: Vec<&String> = mat.data.players.iter().collect();
+                players.sort();
+                let mut value = json!({ "id": mat.id, "data": mat.data });
+                value["data"]["players"] = json!(players);
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_migrations_set_schema_version() {
+        let storage = Storage::open_in_memory().unwrap();
+        assert!(storage.schema_version().unwrap() >= 1);
+        assert!(storage.load_matches().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_loaded_matches_equal_parsed_matches() {
+        let mut storage = Storage::open_in_memory().unwrap();
+        let summary = storage
+            .import_log("games.log", LOG, &ParseOptions::default())
+            .unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                imported: 2,
+                skipped: 0,
+                updated: 0
+            }
+        );
+
+        let mut parsed = Vec::new();
+        process_events_matches(&mut parsed, LOG).unwrap();
+        for mat in parsed.iter_mut() {
+            mat.data.source_file = "games.log".to_string();
+            mat.data.source_id = mat.id;
+        }
+        let loaded = storage.load_matches().unwrap();
+        assert_eq!(to_value(&loaded), to_value(&parsed));
+        assert_eq!(
+            loaded[1].data.settings.get("mapname").map(String::as_str),
+            Some("q3dm6")
+        );
+    }
+
+    #[test]
+    fn test_import_skips_stored_matches() {
+        let mut storage = Storage::open_in_memory().unwrap();
+        let options = ParseOptions::default();
+        storage.import_log("games.log", LOG, &options).unwrap();
+
+        let summary = storage.import_log("games.log", LOG, &options).unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                imported: 0,
+                skipped: 2,
+                updated: 0
+            }
+        );
+
+        // Once the log grows, only the new match is imported. The last match of `LOG` now ends
+        // where the next one starts, but its lines, and so its hash, did not change.
+        let grown = format!("{}{}", LOG, NEXT_MATCH);
+        let summary = storage.import_log("games.log", &grown, &options).unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                imported: 1,
+                skipped: 2,
+                updated: 0
+            }
+        );
+
+        let loaded = storage.load_matches().unwrap();
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded[2].id, 3);
+        assert_eq!(loaded[2].data.source_id, 3);
+        assert_eq!(loaded[2].data.kills_by_means.get("MOD_FALLING"), Some(&1));
+    }
+
+    #[test]
+    fn test_import_updates_match_in_progress() {
+        let mut storage = Storage::open_in_memory().unwrap();
+        let options = ParseOptions::default();
+        let playing = &NEXT_MATCH[..NEXT_MATCH.find("  2:30").unwrap()];
+        storage
+            .import_log("games.log", &format!("{}{}", LOG, playing), &options)
+            .unwrap();
+
+        // The match goes on and ends after the import: it is stored once, with every kill.
+        let ended = format!(
+            "{}{}{}",
+            LOG,
+            playing,
+            "  2:20 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT\n  2:30 ShutdownGame:\n"
+        );
+        let summary = storage.import_log("games.log", &ended, &options).unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                imported: 0,
+                skipped: 2,
+                updated: 1
+            }
+        );
+
+        let loaded = storage.load_matches().unwrap();
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded[2].data.source_id, 3);
+        assert_eq!(loaded[2].data.total_kills, 2);
+        assert!(loaded[2].data.completed);
+    }
+}
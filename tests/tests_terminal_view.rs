@@ -0,0 +1,90 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, process::Command};
+
+    use crate::common::TempFile;
+
+    const LOG: &str = r#"  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:03 ClientConnect: 3
+  0:03 ClientUserinfoChanged: 3 n\Assasinu Credi da Silva\t\0\model\sarge/default
+  0:10 Kill: 2 3 10: Isgalamido killed Assasinu Credi da Silva by MOD_RAILGUN
+  0:20 Kill: 2 3 10: Isgalamido killed Assasinu Credi da Silva by MOD_RAILGUN
+  0:30 Kill: 1022 3 22: <world> killed Assasinu Credi da Silva by MOD_TRIGGER_HURT
+  1:00 ShutdownGame:
+"#;
+
+    /// Runs `report` on a log with the given flags, returning its exit code and output.
+    fn report(log: &Path, flags: &[&str], envs: &[(&str, &str)]) -> (i32, String) {
+        let output = Command::new(env!("CARGO_BIN_EXE_quake_log_parse"))
+            .arg("report")
+            .args(flags)
+            .arg(log)
+            .env_remove("NO_COLOR")
+            .envs(envs.iter().copied())
+            .output()
+            .unwrap();
+        (
+            output.status.code().unwrap_or(-1),
+            String::from_utf8(output.stdout).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_tables_fit_the_terminal() {
+        let log = TempFile::new("games.log", LOG);
+        let (code, output) = report(&log, &["--format=table"], &[("COLUMNS", "30")]);
+        assert_eq!(code, 0);
+        assert!(output.contains("Match 1 · q3dm17 · 1:00 · 3 k…\n"));
+        assert!(output.contains("│ Isgalamido         │     2 │"));
+        assert!(output.contains("│ Assasinu Credi da… │    -1 │"));
+        assert!(output.contains("MOD_RAILGUN      ██████████  2"));
+        assert!(output.contains("MOD_TRIGGER_HU…  █████       1"));
+        // The file, the scoreboard and the chart of the match fit; the ranking cannot be narrower.
+        assert!(output
+            .lines()
+            .take(12)
+            .all(|line| line.chars().count() <= 30));
+        assert!(!output.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_colors() {
+        let log = TempFile::new("games.log", LOG);
+        let (_, output) = report(&log, &["--format=table", "--color=always"], &[]);
+        assert!(output.contains("\x1b[32m    2\x1b[0m"));
+        assert!(output.contains("\x1b[31m   -1\x1b[0m"));
+
+        // Not a terminal, so no colours even without NO_COLOR.
+        let (_, output) = report(&log, &["--format=table"], &[]);
+        assert!(!output.contains('\x1b'));
+        let (_, output) = report(&log, &["--format=table"], &[("NO_COLOR", "1")]);
+        assert!(!output.contains('\x1b'));
+
+        // Player names are drawn with their Quake colours on the scoreboard, and aligned as without.
+        let log = TempFile::new(
+            "quake_colors.log",
+            &LOG.replace(r"n\Isgalamido", r"n\^1Isga^7lamido"),
+        );
+        let (_, output) = report(&log, &["--format=table", "--color=always"], &[]);
+        assert!(output.contains("│ \x1b[31mIsga\x1b[37mlamido\x1b[0m              │"));
+        let (_, output) = report(&log, &["--format=table"], &[]);
+        assert!(output.contains("│ Isgalamido              │"));
+    }
+
+    #[test]
+    fn test_json_when_piped() {
+        let log = TempFile::new("games.log", LOG);
+        let (code, output) = report(&log, &[], &[]);
+        assert_eq!(code, 0);
+        assert!(output.starts_with('{'));
+        let (_, json) = report(&log, &["--format=json", "--color=always"], &[]);
+        assert_eq!(json, output);
+
+        let (code, _) = report(&log, &["--format=xml"], &[]);
+        assert_eq!(code, 2);
+    }
+}
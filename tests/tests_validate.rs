@@ -0,0 +1,147 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use quake_log_parse::quake_log_parse::model::{
+        log_model::{means_of_death_id, process_events_matches},
+        validate::{validate_log, Check, Severity},
+    };
+    use serde_json::Value;
+
+    use crate::common::TempFile;
+
+    const DAMAGED: &str = r#"  0:00 Kill: 2 3 10: Isgalamido killed Mocinha by MOD_RAILGUN
+  0:00 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:10 Kill: 2 3 6: Isgalamido killed Mocinha by MOD_RAILGUN
+  0:09 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT
+  0:20 Kill: 2 2 40: Isgalamido killed Isgalamido by MOD_LASER
+  0:25 Kill: 2 2
+  0:30 Item: 7 weapon_rocketlauncher
+  0:40 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  0:41 ClientConnect: 2
+  1:00 ShutdownGame:
+  1:01 Kill: 2 2 20: Isgalamido killed Isgalamido by MOD_SUICIDE
+  1:05 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm4
+"#;
+
+    // A server restart sets the clock back to 0:00 between two matches.
+    const RESTARTED: &str = r#"  0:00 ------------------------------------------------------------
+  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm17
+  0:01 ClientConnect: 2
+  0:01 ClientUserinfoChanged: 2 n\Isgalamido\t\0\model\xian/default
+  0:02 ClientBegin: 2
+  0:05 Item: 2 weapon_rocketlauncher
+  0:10 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT
+  0:20 ClientDisconnect: 2
+  0:30 ShutdownGame:
+  0:30 ------------------------------------------------------------
+  0:00 ------------------------------------------------------------
+  0:00 InitGame: \sv_hostname\Code Miner Server\g_gametype\0\mapname\q3dm6
+  0:01 ClientBegin: 3
+  0:10 Kill: 3 3 20: Mocinha killed Mocinha by MOD_SUICIDE
+  0:30 ShutdownGame:
+"#;
+
+    #[test]
+    fn test_kill_before_init_game() {
+        let log = concat!(
+            "  0:00 Kill: 2 3 10: Isgalamido killed Mocinha by MOD_RAILGUN\n",
+            "  0:00 ClientUserinfoChanged: 2 n\\Isgalamido\\t\\0\n"
+        );
+        let mut matches = Vec::new();
+        process_events_matches(&mut matches, log).unwrap();
+        assert!(matches.is_empty());
+
+        let findings = validate_log(log.as_bytes(), "games.log").unwrap();
+        assert_eq!(findings[0].check, Check::KillOutsideMatch);
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!(findings[0].match_id, None);
+        assert_eq!(findings[1].check, Check::UserinfoOutsideMatch);
+    }
+
+    #[test]
+    fn test_damaged_log() {
+        let findings: Vec<(usize, Check, Option<i32>)> =
+            validate_log(DAMAGED.as_bytes(), "games.log")
+                .unwrap()
+                .into_iter()
+                .map(|finding| (finding.line, finding.check, finding.match_id))
+                .collect();
+        assert_eq!(
+            findings,
+            vec![
+                (1, Check::KillOutsideMatch, None),
+                (2, Check::UserinfoOutsideMatch, None),
+                (3, Check::UnterminatedMatch, Some(1)),
+                (6, Check::UnknownClient, Some(1)),
+                (6, Check::MeansMismatch, Some(1)),
+                (7, Check::TimestampBackwards, Some(1)),
+                (8, Check::UnknownMeans, Some(1)),
+                (9, Check::MalformedLine, Some(1)),
+                (10, Check::UnknownClient, Some(1)),
+                (14, Check::KillOutsideMatch, Some(2)),
+                (15, Check::UnterminatedMatch, Some(3)),
+            ]
+        );
+        assert_eq!(means_of_death_id("MOD_RAILGUN"), Some(10));
+        assert_eq!(means_of_death_id("MOD_GRAPPLE"), Some(28));
+        assert_eq!(means_of_death_id("MOD_LASER"), None);
+    }
+
+    #[test]
+    fn test_restarted_server() {
+        assert_eq!(
+            validate_log(RESTARTED.as_bytes(), "games.log").unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_validate_command() {
+        let files = (
+            TempFile::new("damaged.log", DAMAGED),
+            TempFile::new("restarted.log", RESTARTED),
+        );
+        let (damaged, restarted) = (files.0.display().to_string(), files.1.display().to_string());
+        let run = |args: &[&str]| {
+            let output = Command::new(env!("CARGO_BIN_EXE_quake_log_parse"))
+                .arg("validate")
+                .args(args)
+                .output()
+                .unwrap();
+            (
+                output.status.code().unwrap_or(-1),
+                String::from_utf8(output.stdout).unwrap(),
+            )
+        };
+
+        assert_eq!(run(&[&restarted]), (0, "No problems found.\n".to_string()));
+
+        let (code, text) = run(&[&damaged]);
+        assert_eq!(code, 1);
+        assert!(text.contains(&format!(
+            "{}:8: error[unknown_means]: Unknown means of death MOD_LASER\n",
+            damaged
+        )));
+        assert!(text.ends_with("4 errors, 7 warnings\n"));
+
+        let (code, json) = run(&["--format=json", &restarted, &damaged]);
+        assert_eq!(code, 1);
+        let json: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            (json["errors"].as_u64(), json["warnings"].as_u64()),
+            (Some(4), Some(7))
+        );
+        assert_eq!(json["findings"][0]["file"], damaged.as_str());
+        assert_eq!(json["findings"][0]["check"], "kill_outside_match");
+        assert_eq!(json["findings"][0]["severity"], "error");
+        assert_eq!(json["findings"][3]["match"], 1);
+
+        assert_eq!(run(&["--strict", &damaged]).0, 2);
+    }
+}